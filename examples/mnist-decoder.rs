@@ -1,13 +1,14 @@
 use nalgebra::DVector;
-use rand::Rng;
 use rust_mnist::Mnist;
 
 use neuramethyst::{
-    argmax, cycle_shuffling,
+    augment::{NeuraAugmentIter, NeuraTranslate},
+    cycle_shuffling,
     derivable::{
         activation::{Linear, Logistic, Relu, Swish, Tanh},
         loss::{CrossEntropy, Euclidean},
     },
+    metrics::{NeuraClassificationMetrics, NeuraMetric},
     one_hot, plot_losses,
     prelude::*,
 };
@@ -70,28 +71,29 @@ pub fn main() {
     trainer.learning_momentum = 0.002;
     // trainer.log_iterations = 1;
 
-    let mut rng = rand::thread_rng();
     let losses = trainer.train(
         &NeuraBackprop::new(Euclidean),
         &mut network,
-        cycle_shuffling(train_images.clone(), rand::thread_rng()).map(move |input| {
-            let dx = rng.gen_range(-4..4);
-            let dy = rng.gen_range(-4..4);
-
-            let shifted = shift(&input, dx, dy);
-            (shifted.clone(), shifted)
-        }),
+        cycle_shuffling(train_images.clone(), rand::thread_rng())
+            .map(|input| (input, ()))
+            .augment(
+                NeuraTranslate::new(4, 4),
+                NeuraShape::Matrix(HEIGHT, WIDTH),
+                rand::thread_rng(),
+            )
+            .map(|(shifted, ())| (shifted.clone(), shifted)),
         &test_data,
     );
 
     plot_losses(losses, 128, 48);
 
-    // Then, train a small network to decode the encoded data into the categories
+    // Then, train a small network to decode the encoded data into the categories,
+    // keeping the encoder frozen at first
 
     let trimmed_network = network.clone().trim_tail().trim_tail();
 
     let mut network = neura_sequential![
-        ..trimmed_network.lock(),
+        ..trimmed_network,
         neura_layer!("dense", LATENT_SIZE)
             .activation(Tanh)
             .construct(NeuraShape::Vector(LATENT_SIZE))
@@ -101,7 +103,10 @@ pub fn main() {
             .construct(NeuraShape::Vector(LATENT_SIZE))
             .unwrap(),
         neura_layer!("softmax")
-    ];
+    ]
+    .lockable();
+    network.lock_layers(..network.layer_count() - 3);
+
     let test_data = test_images
         .clone()
         .zip(test_labels.clone())
@@ -110,51 +115,40 @@ pub fn main() {
     let trainer = NeuraBatchedTrainer::with_epochs(0.03, 10, 128, TRAIN_SIZE);
 
     plot_losses(
-        trainer.train(
+        trainer.train_with_metrics(
             &NeuraBackprop::new(CrossEntropy),
             &mut network,
-            cycle_shuffling(train_images.clone().zip(train_labels), rand::thread_rng()),
+            cycle_shuffling(
+                train_images.clone().zip(train_labels.clone()),
+                rand::thread_rng(),
+            ),
             &test_data,
+            &[NeuraMetric::Accuracy, NeuraMetric::TopK(3)],
         ),
         128,
         48,
     );
 
-    let mut correct = 0;
-    for (test_image, test_label) in test_images.zip(test_labels) {
-        let guess = network.eval(&test_image);
-        let guess = argmax(guess.as_slice());
-        let actual = argmax(test_label.as_slice());
+    // Finally, fine-tune the whole network with a lower learning rate
 
-        if guess == actual {
-            correct += 1;
-        }
-    }
+    network.unlock();
+    let trainer = NeuraBatchedTrainer::with_epochs(0.003, 2, 128, TRAIN_SIZE);
 
-    println!("");
-    println!(
-        "{} correct out of {}: {:.2}%",
-        correct,
-        TEST_SIZE,
-        (correct as f32 / TEST_SIZE as f32) * 100.0
+    plot_losses(
+        trainer.train_with_metrics(
+            &NeuraBackprop::new(CrossEntropy),
+            &mut network,
+            cycle_shuffling(train_images.clone().zip(train_labels), rand::thread_rng()),
+            &test_data,
+            &[NeuraMetric::Accuracy, NeuraMetric::TopK(3)],
+        ),
+        128,
+        48,
     );
-}
 
-fn shift(image: &DVector<f32>, dx: i32, dy: i32) -> DVector<f32> {
-    let mut res = DVector::from_element(image.len(), 0.0);
-    let width = WIDTH as i32;
-    let height = HEIGHT as i32;
-
-    for y in 0..height {
-        for x in 0..width {
-            let x2 = x + dx;
-            let y2 = y + dy;
-            if y2 < 0 || y2 >= height || x2 < 0 || x2 >= width {
-                continue;
-            }
-            res[(y2 * width + x2) as usize] = image[(y * width + x) as usize];
-        }
-    }
-
-    res
+    println!();
+    println!(
+        "{}",
+        NeuraClassificationMetrics::evaluate(&network, &test_data)
+    );
 }
@@ -4,6 +4,7 @@ use rust_mnist::Mnist;
 use std::io::Write;
 
 use neuramethyst::{
+    augment::{NeuraAugmentation, NeuraTranslate},
     cycle_shuffling,
     derivable::{
         activation::{Logistic, Relu, Swish},
@@ -23,7 +24,7 @@ const EPOCHS: usize = 80;
 
 // const BASE_NOISE: f32 = 0.05;
 const NOISE_AMOUNT: f32 = 0.5;
-const SHIFT_AMOUNT: i32 = 9;
+const SHIFT_AMOUNT: usize = 9;
 
 pub fn main() {
     let Mnist {
@@ -160,35 +161,16 @@ fn add_noise(mut image: DVector<f32>, rng: &mut impl Rng, amount: f32) -> DVecto
     image
 }
 
-fn shift(image: &DVector<f32>, dx: i32, dy: i32) -> DVector<f32> {
-    let mut res = DVector::from_element(image.len(), 0.0);
-    let width = WIDTH as i32;
-    let height = HEIGHT as i32;
-
-    for y in 0..height {
-        for x in 0..width {
-            let x2 = x + dx;
-            let y2 = y + dy;
-            if y2 < 0 || y2 >= height || x2 < 0 || x2 >= width {
-                continue;
-            }
-            res[(y2 * width + x2) as usize] = image[(y * width + x) as usize];
-        }
-    }
-
-    res
-}
-
 fn augment_data(
     iter: impl Iterator<Item = (DVector<f32>, DVector<f32>)>,
 ) -> impl Iterator<Item = (DVector<f32>, DVector<f32>)> {
     let mut rng = rand::thread_rng();
     iter.map(move |(image, label)| {
         let noise_amount = rng.gen_range(0.05..NOISE_AMOUNT);
-        let base_image = shift(
+        let base_image = NeuraTranslate::new(SHIFT_AMOUNT, SHIFT_AMOUNT).augment(
             &image,
-            rng.gen_range(-SHIFT_AMOUNT..SHIFT_AMOUNT),
-            rng.gen_range(-SHIFT_AMOUNT..SHIFT_AMOUNT),
+            NeuraShape::Matrix(HEIGHT, WIDTH),
+            &mut rng,
         ) * rng.gen_range(0.6..1.0);
         // let base_image = add_noise(base_image, &mut rng, base_noise);
 
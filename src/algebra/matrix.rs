@@ -183,6 +183,22 @@ impl<const WIDTH: usize, const HEIGHT: usize, F: NeuraVectorSpace + Clone> Neura
 
         sum
     }
+
+    fn clip_values(&mut self, max_value: f64) {
+        for i in 0..HEIGHT {
+            for j in 0..WIDTH {
+                self.data[i][j].clip_values(max_value);
+            }
+        }
+    }
+
+    fn map_entries(&mut self, callback: &mut dyn FnMut(f64) -> f64) {
+        for i in 0..HEIGHT {
+            for j in 0..WIDTH {
+                self.data[i][j].map_entries(callback);
+            }
+        }
+    }
 }
 
 impl<const WIDTH: usize, const HEIGHT: usize, F> From<Box<[[F; WIDTH]; HEIGHT]>>
@@ -3,6 +3,9 @@ use std::any::Any;
 
 pub use matrix::NeuraMatrix;
 
+mod tensor;
+pub use tensor::NeuraTensor;
+
 mod vector;
 use nalgebra::Matrix;
 use num::Float;
@@ -15,6 +18,28 @@ pub trait NeuraVectorSpace {
     fn mul_assign(&mut self, by: f64);
 
     fn norm_squared(&self) -> f64;
+
+    /// Replaces each scalar entry of the vector with `callback(entry)`.
+    /// The entries must always be visited in the same order, so that they can be indexed.
+    fn map_entries(&mut self, callback: &mut dyn FnMut(f64) -> f64);
+
+    /// Clamps each entry of the vector to `[-max_value; max_value]`,
+    /// the default implementation goes through `map_entries`
+    fn clip_values(&mut self, max_value: f64) {
+        self.map_entries(&mut |x| x.clamp(-max_value, max_value));
+    }
+
+    /// Rescales the vector so that its L2 norm does not exceed `max_norm`,
+    /// returns the norm of the vector before it was rescaled.
+    fn clip_norm(&mut self, max_norm: f64) -> f64 {
+        let norm = self.norm_squared().sqrt();
+
+        if norm > max_norm {
+            self.mul_assign(max_norm / norm);
+        }
+
+        norm
+    }
 }
 
 pub trait NeuraDynVectorSpace: Send {
@@ -24,6 +49,10 @@ pub trait NeuraDynVectorSpace: Send {
 
     fn norm_squared(&self) -> f64;
 
+    fn clip_values(&mut self, max_value: f64);
+
+    fn map_entries(&mut self, callback: &mut dyn FnMut(f64) -> f64);
+
     /// Trampoline for allowing NeuraDynVectorSpace to be cast back into a known type for add_assign
     fn into_any(&self) -> &dyn Any;
 }
@@ -45,6 +74,14 @@ impl<T: NeuraVectorSpace + Send + 'static> NeuraDynVectorSpace for T {
         <Self as NeuraVectorSpace>::norm_squared(self)
     }
 
+    fn clip_values(&mut self, max_value: f64) {
+        <Self as NeuraVectorSpace>::clip_values(self, max_value);
+    }
+
+    fn map_entries(&mut self, callback: &mut dyn FnMut(f64) -> f64) {
+        <Self as NeuraVectorSpace>::map_entries(self, callback);
+    }
+
     fn into_any(&self) -> &dyn Any {
         self
     }
@@ -64,6 +101,16 @@ impl NeuraVectorSpace for () {
     fn norm_squared(&self) -> f64 {
         0.0
     }
+
+    #[inline(always)]
+    fn clip_values(&mut self, _max_value: f64) {
+        // Noop
+    }
+
+    #[inline(always)]
+    fn map_entries(&mut self, _callback: &mut dyn FnMut(f64) -> f64) {
+        // Noop
+    }
 }
 
 impl<T: NeuraVectorSpace + ?Sized> NeuraVectorSpace for Box<T> {
@@ -78,6 +125,14 @@ impl<T: NeuraVectorSpace + ?Sized> NeuraVectorSpace for Box<T> {
     fn norm_squared(&self) -> f64 {
         self.as_ref().norm_squared()
     }
+
+    fn clip_values(&mut self, max_value: f64) {
+        self.as_mut().clip_values(max_value);
+    }
+
+    fn map_entries(&mut self, callback: &mut dyn FnMut(f64) -> f64) {
+        self.as_mut().map_entries(callback);
+    }
 }
 
 impl NeuraVectorSpace for dyn NeuraDynVectorSpace {
@@ -92,6 +147,14 @@ impl NeuraVectorSpace for dyn NeuraDynVectorSpace {
     fn norm_squared(&self) -> f64 {
         <dyn NeuraDynVectorSpace>::norm_squared(self)
     }
+
+    fn clip_values(&mut self, max_value: f64) {
+        <dyn NeuraDynVectorSpace>::clip_values(self, max_value)
+    }
+
+    fn map_entries(&mut self, callback: &mut dyn FnMut(f64) -> f64) {
+        <dyn NeuraDynVectorSpace>::map_entries(self, callback)
+    }
 }
 
 impl<Left: NeuraVectorSpace, Right: NeuraVectorSpace> NeuraVectorSpace for (Left, Right) {
@@ -112,6 +175,16 @@ impl<Left: NeuraVectorSpace, Right: NeuraVectorSpace> NeuraVectorSpace for (Left
     fn norm_squared(&self) -> f64 {
         self.0.norm_squared() + self.1.norm_squared()
     }
+
+    fn clip_values(&mut self, max_value: f64) {
+        NeuraVectorSpace::clip_values(&mut self.0, max_value);
+        NeuraVectorSpace::clip_values(&mut self.1, max_value);
+    }
+
+    fn map_entries(&mut self, callback: &mut dyn FnMut(f64) -> f64) {
+        NeuraVectorSpace::map_entries(&mut self.0, callback);
+        NeuraVectorSpace::map_entries(&mut self.1, callback);
+    }
 }
 
 impl<const N: usize, T: NeuraVectorSpace + Clone> NeuraVectorSpace for [T; N] {
@@ -130,6 +203,18 @@ impl<const N: usize, T: NeuraVectorSpace + Clone> NeuraVectorSpace for [T; N] {
     fn norm_squared(&self) -> f64 {
         self.iter().map(T::norm_squared).sum()
     }
+
+    fn clip_values(&mut self, max_value: f64) {
+        for item in self.iter_mut() {
+            NeuraVectorSpace::clip_values(item, max_value);
+        }
+    }
+
+    fn map_entries(&mut self, callback: &mut dyn FnMut(f64) -> f64) {
+        for item in self.iter_mut() {
+            NeuraVectorSpace::map_entries(item, callback);
+        }
+    }
 }
 
 impl<T: NeuraVectorSpace> NeuraVectorSpace for Vec<T> {
@@ -156,9 +241,21 @@ impl<T: NeuraVectorSpace> NeuraVectorSpace for Vec<T> {
 
         res
     }
+
+    fn clip_values(&mut self, max_value: f64) {
+        for item in self.iter_mut() {
+            item.clip_values(max_value);
+        }
+    }
+
+    fn map_entries(&mut self, callback: &mut dyn FnMut(f64) -> f64) {
+        for item in self.iter_mut() {
+            item.map_entries(callback);
+        }
+    }
 }
 
-impl<F: Float, R: nalgebra::Dim, C: nalgebra::Dim, S: nalgebra::RawStorage<F, R, C>>
+impl<F: Float, R: nalgebra::Dim, C: nalgebra::Dim, S: nalgebra::RawStorageMut<F, R, C>>
     NeuraVectorSpace for Matrix<F, R, C, S>
 where
     Matrix<F, R, C, S>: std::ops::MulAssign<F>,
@@ -180,6 +277,53 @@ where
             .to_f64()
             .unwrap_or(0.0)
     }
+
+    fn clip_values(&mut self, max_value: f64) {
+        let max_value = F::from(max_value).unwrap();
+
+        for x in self.iter_mut() {
+            *x = x.max(-max_value).min(max_value);
+        }
+    }
+
+    fn map_entries(&mut self, callback: &mut dyn FnMut(f64) -> f64) {
+        for x in self.iter_mut() {
+            *x = F::from(callback(x.to_f64().unwrap())).unwrap();
+        }
+    }
+}
+
+impl<F: Float, S: ndarray::DataMut<Elem = F>, D: ndarray::Dimension> NeuraVectorSpace
+    for ndarray::ArrayBase<S, D>
+{
+    fn add_assign(&mut self, other: &Self) {
+        self.zip_mut_with(other, |x, &y| *x = *x + y);
+    }
+
+    fn mul_assign(&mut self, by: f64) {
+        let by = F::from(by).unwrap();
+        self.map_inplace(|x| *x = *x * by);
+    }
+
+    fn norm_squared(&self) -> f64 {
+        self.iter()
+            .fold(F::zero(), |sum, &x| sum + x * x)
+            .to_f64()
+            .unwrap_or(0.0)
+    }
+
+    fn clip_values(&mut self, max_value: f64) {
+        let max_value = F::from(max_value).unwrap();
+
+        self.map_inplace(|x| *x = x.max(-max_value).min(max_value));
+    }
+
+    fn map_entries(&mut self, callback: &mut dyn FnMut(f64) -> f64) {
+        // `iter_mut` visits the entries in logical order, regardless of the memory layout
+        for x in self.iter_mut() {
+            *x = F::from(callback(x.to_f64().unwrap())).unwrap();
+        }
+    }
 }
 
 macro_rules! base {
@@ -196,9 +340,50 @@ macro_rules! base {
             fn norm_squared(&self) -> f64 {
                 (self * self) as f64
             }
+
+            fn clip_values(&mut self, max_value: f64) {
+                *self = self.clamp(-max_value as $type, max_value as $type);
+            }
+
+            fn map_entries(&mut self, callback: &mut dyn FnMut(f64) -> f64) {
+                *self = callback(*self as f64) as $type;
+            }
         }
     };
 }
 
 base!(f32);
 base!(f64);
+
+/// Same as `base!`, for the types of the `half` crate, which can't be cast with `as`
+#[cfg(feature = "half")]
+macro_rules! half_base {
+    ( $type:ty ) => {
+        impl NeuraVectorSpace for $type {
+            fn add_assign(&mut self, other: &Self) {
+                std::ops::AddAssign::add_assign(self, *other);
+            }
+
+            fn mul_assign(&mut self, other: f64) {
+                std::ops::MulAssign::mul_assign(self, <$type>::from_f64(other));
+            }
+
+            fn norm_squared(&self) -> f64 {
+                (*self * *self).to_f64()
+            }
+
+            fn clip_values(&mut self, max_value: f64) {
+                *self = self.clamp(<$type>::from_f64(-max_value), <$type>::from_f64(max_value));
+            }
+
+            fn map_entries(&mut self, callback: &mut dyn FnMut(f64) -> f64) {
+                *self = <$type>::from_f64(callback(self.to_f64()));
+            }
+        }
+    };
+}
+
+#[cfg(feature = "half")]
+half_base!(half::f16);
+#[cfg(feature = "half")]
+half_base!(half::bf16);
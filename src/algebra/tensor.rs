@@ -0,0 +1,232 @@
+use nalgebra::{DVector, Scalar};
+use num::{Float, Zero};
+
+use super::NeuraVectorSpace;
+use crate::{err::NeuraTensorErr, layer::NeuraShape};
+
+/// A flat `DVector`, tagged with its `NeuraShape` at runtime.
+///
+/// The entries follow the layout of `NeuraShape`: row-major, with the last dimension varying the fastest.
+/// The conversions from `DVector` are checked, so that layers receiving a `NeuraTensor` can assert its shape,
+/// instead of reinterpreting the entries of a vector of the right length.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NeuraTensor<F: Scalar> {
+    data: DVector<F>,
+    shape: NeuraShape,
+}
+
+impl<F: Scalar> NeuraTensor<F> {
+    /// Tags `data` with `shape`, returning an error if their sizes don't match
+    pub fn from_vector(
+        data: DVector<F>,
+        shape: impl Into<NeuraShape>,
+    ) -> Result<Self, NeuraTensorErr> {
+        let shape = shape.into();
+        if data.len() != shape.size() {
+            return Err(NeuraTensorErr::SizeMismatch {
+                shape,
+                len: data.len(),
+            });
+        }
+
+        Ok(Self { data, shape })
+    }
+
+    pub fn from_element(shape: impl Into<NeuraShape>, value: F) -> Self {
+        let shape = shape.into();
+
+        Self {
+            data: DVector::from_element(shape.size(), value),
+            shape,
+        }
+    }
+
+    pub fn zeros(shape: impl Into<NeuraShape>) -> Self
+    where
+        F: Zero,
+    {
+        Self::from_element(shape, F::zero())
+    }
+
+    /// Creates a tensor by calling `callback` with the index of each entry, in the order in which they are stored
+    pub fn from_fn(shape: impl Into<NeuraShape>, mut callback: impl FnMut(&[usize]) -> F) -> Self {
+        let shape = shape.into();
+        let dimensions = shape.dimensions();
+        let mut index = dimensions;
+        index.fill(0);
+
+        let data = DVector::from_fn(shape.size(), |_, _| {
+            let value = callback(&index);
+
+            // Increment the index, starting from the last dimension
+            for (i, dimension) in index.iter_mut().zip(dimensions.iter()).rev() {
+                *i += 1;
+                if *i < *dimension {
+                    break;
+                }
+                *i = 0;
+            }
+
+            value
+        });
+
+        Self { data, shape }
+    }
+
+    pub fn shape(&self) -> NeuraShape {
+        self.shape
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn as_vector(&self) -> &DVector<F> {
+        &self.data
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [F] {
+        self.data.as_mut_slice()
+    }
+
+    pub fn into_vector(self) -> DVector<F> {
+        self.data
+    }
+
+    /// Changes the shape of the tensor without moving its entries, returning an error if the sizes don't match
+    pub fn reshape(self, shape: impl Into<NeuraShape>) -> Result<Self, NeuraTensorErr> {
+        Self::from_vector(self.data, shape)
+    }
+
+    /// Returns an error if the tensor doesn't have the shape `expected`
+    pub fn check_shape(&self, expected: NeuraShape) -> Result<(), NeuraTensorErr> {
+        if self.shape != expected {
+            return Err(NeuraTensorErr::ShapeMismatch {
+                expected,
+                got: self.shape,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Panics if the tensor doesn't have the shape `expected`, used by layers to check the layout of their inputs
+    #[track_caller]
+    pub fn assert_shape(&self, expected: NeuraShape) {
+        assert_eq!(
+            self.shape, expected,
+            "Tensor does not have the shape expected by the layer"
+        );
+    }
+
+    /// Returns the entry at `index`, or `None` if `index` has the wrong number of dimensions or is out of bounds
+    pub fn get(&self, index: &[usize]) -> Option<&F> {
+        self.shape.offset(index).map(|offset| &self.data[offset])
+    }
+
+    pub fn get_mut(&mut self, index: &[usize]) -> Option<&mut F> {
+        self.shape
+            .offset(index)
+            .map(|offset| &mut self.data[offset])
+    }
+}
+
+impl<F: Scalar> From<DVector<F>> for NeuraTensor<F> {
+    fn from(data: DVector<F>) -> Self {
+        let shape = NeuraShape::Vector(data.len());
+
+        Self { data, shape }
+    }
+}
+
+impl<F: Scalar> From<NeuraTensor<F>> for DVector<F> {
+    fn from(tensor: NeuraTensor<F>) -> Self {
+        tensor.data
+    }
+}
+
+impl<F: Float + Scalar> NeuraVectorSpace for NeuraTensor<F>
+where
+    DVector<F>: NeuraVectorSpace,
+{
+    fn add_assign(&mut self, other: &Self) {
+        assert_eq!(self.shape, other.shape);
+
+        self.data.add_assign(&other.data);
+    }
+
+    fn mul_assign(&mut self, by: f64) {
+        self.data.mul_assign(by);
+    }
+
+    fn norm_squared(&self) -> f64 {
+        self.data.norm_squared()
+    }
+
+    fn clip_values(&mut self, max_value: f64) {
+        self.data.clip_values(max_value);
+    }
+
+    fn map_entries(&mut self, callback: &mut dyn FnMut(f64) -> f64) {
+        self.data.map_entries(callback);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nalgebra::dvector;
+
+    use super::*;
+    use crate::layer::NeuraDims;
+
+    #[test]
+    fn test_tensor_conversions() {
+        let tensor =
+            NeuraTensor::from_vector(dvector![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], (2, 3)).unwrap();
+        assert_eq!(tensor.shape(), NeuraShape::Matrix(2, 3));
+        assert_eq!(tensor.get(&[1, 0]), Some(&4.0));
+        assert_eq!(tensor.get(&[0, 3]), None);
+        assert_eq!(tensor.get(&[0]), None);
+
+        assert_eq!(
+            NeuraTensor::from_vector(dvector![1.0, 2.0], (2, 3)),
+            Err(NeuraTensorErr::SizeMismatch {
+                shape: NeuraShape::Matrix(2, 3),
+                len: 2
+            })
+        );
+
+        let tensor = tensor.reshape(NeuraShape::new(&[1, 3, 1, 2])).unwrap();
+        assert_eq!(tensor.get(&[0, 2, 0, 1]), Some(&6.0));
+        assert!(tensor.check_shape(NeuraShape::Matrix(3, 2)).is_err());
+        assert!(tensor.check_shape(NeuraShape::new(&[1, 3, 1, 2])).is_ok());
+        assert_eq!(
+            DVector::from(tensor),
+            dvector![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]
+        );
+
+        assert_eq!(
+            NeuraShape::Nd(NeuraDims::new(&[2, 3])),
+            NeuraShape::Matrix(2, 3)
+        );
+        assert_eq!(NeuraShape::new(&[4]), NeuraShape::Vector(4));
+        assert!(matches!(
+            NeuraShape::new(&[2, 3, 4]),
+            NeuraShape::Tensor(2, 3, 4)
+        ));
+
+        let shape = NeuraShape::new(&[2, 1, 3, 2]);
+        let tensor = NeuraTensor::from_fn(shape, |index| shape.offset(index).unwrap());
+        assert_eq!(tensor.into_vector(), DVector::from_fn(12, |i, _| i));
+    }
+
+    #[test]
+    #[should_panic(expected = "Shapes must have at least one dimension")]
+    fn test_shape_rank_zero() {
+        NeuraShape::new(&[]);
+    }
+}
@@ -109,6 +109,20 @@ impl<const LENGTH: usize, F: Float + From<f64> + Into<f64>> NeuraVectorSpace
 
         sum.into()
     }
+
+    fn clip_values(&mut self, max_value: f64) {
+        let max_value: F = max_value.into();
+
+        for x in self.data.iter_mut() {
+            *x = x.max(-max_value).min(max_value);
+        }
+    }
+
+    fn map_entries(&mut self, callback: &mut dyn FnMut(f64) -> f64) {
+        for x in self.data.iter_mut() {
+            *x = callback((*x).into()).into();
+        }
+    }
 }
 
 impl<const LENGTH: usize, F> std::ops::Index<usize> for NeuraVector<LENGTH, F> {
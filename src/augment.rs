@@ -0,0 +1,682 @@
+//! Data augmentation for image samples, applied as iterator adapters.
+//!
+//! Images are stored as flat `DVector`s, with their shape given by a `NeuraShape`:
+//! - `NeuraShape::Matrix(rows, columns)` is a grayscale image, stored row by row
+//! - `NeuraShape::Tensor(rows, columns, channels)` is a multi-channel image, stored row by row with its channels interleaved
+//! - `NeuraShape::Vector(length)` is treated as an image made up of a single row
+//!
+//! Shapes with more than three dimensions are not images, and are rejected.
+//!
+//! Augmentations are combined by putting them in a tuple, and are applied in order:
+//!
+//! ```
+//! use neuramethyst::{augment::*, prelude::*};
+//! use nalgebra::DVector;
+//! use rand::{rngs::StdRng, SeedableRng};
+//!
+//! let images = vec![(DVector::<f32>::from_element(28 * 28, 0.5), 1.0); 16];
+//!
+//! let augmented: Vec<_> = images
+//!     .into_iter()
+//!     .augment(
+//!         (
+//!             NeuraTranslate::new(2, 2),
+//!             NeuraRotate::new(0.2),
+//!             NeuraGaussianNoise::new(0.05),
+//!         ),
+//!         NeuraShape::Matrix(28, 28),
+//!         StdRng::seed_from_u64(0),
+//!     )
+//!     .collect();
+//! ```
+
+use nalgebra::{DVector, Scalar};
+use num::Float;
+use rand::{Rng, RngCore};
+use rand_distr::{Distribution, Normal};
+
+use crate::layer::NeuraShape;
+
+pub trait NeuraAugmentation<F: Scalar> {
+    /// Returns a randomly altered copy of `image`, whose shape is `shape`
+    fn augment(&self, image: &DVector<F>, shape: NeuraShape, rng: &mut dyn RngCore) -> DVector<F>;
+}
+
+macro_rules! impl_augmentation_tuple {
+    ( $( $type:ident : $index:tt ),+ ) => {
+        impl<F: Scalar, $( $type: NeuraAugmentation<F> ),+> NeuraAugmentation<F> for ( $( $type, )+ ) {
+            fn augment(
+                &self,
+                image: &DVector<F>,
+                shape: NeuraShape,
+                rng: &mut dyn RngCore,
+            ) -> DVector<F> {
+                let image = image.clone();
+                $(
+                    let image = self.$index.augment(&image, shape, rng);
+                )+
+                image
+            }
+        }
+    };
+}
+
+impl_augmentation_tuple!(A: 0);
+impl_augmentation_tuple!(A: 0, B: 1);
+impl_augmentation_tuple!(A: 0, B: 1, C: 2);
+impl_augmentation_tuple!(A: 0, B: 1, C: 2, D: 3);
+impl_augmentation_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4);
+impl_augmentation_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, G: 5);
+impl_augmentation_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, G: 5, H: 6);
+
+impl<F: Scalar, A: NeuraAugmentation<F> + ?Sized> NeuraAugmentation<F> for Box<A> {
+    fn augment(&self, image: &DVector<F>, shape: NeuraShape, rng: &mut dyn RngCore) -> DVector<F> {
+        self.as_ref().augment(image, shape, rng)
+    }
+}
+
+/// Iterator adapter returned by `NeuraAugmentIter::augment`
+pub struct NeuraAugmented<I, A, R> {
+    iter: I,
+    augmentation: A,
+    shape: NeuraShape,
+    rng: R,
+}
+
+impl<F: Scalar, Target, I, A, R> Iterator for NeuraAugmented<I, A, R>
+where
+    I: Iterator<Item = (DVector<F>, Target)>,
+    A: NeuraAugmentation<F>,
+    R: RngCore,
+{
+    type Item = (DVector<F>, Target);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (input, target) = self.iter.next()?;
+
+        assert_eq!(
+            input.len(),
+            self.shape.size(),
+            "Input of length {} does not match the augmentation shape {:?}",
+            input.len(),
+            self.shape
+        );
+
+        let input = self.augmentation.augment(&input, self.shape, &mut self.rng);
+        Some((input, target))
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+pub trait NeuraAugmentIter<F: Scalar, Target>:
+    Iterator<Item = (DVector<F>, Target)> + Sized
+{
+    /// Applies `augmentation` to the inputs of each `(input, target)` pair, drawing randomness from `rng`.
+    ///
+    /// Each input is augmented every time it is yielded, so this should be placed after `cycle_shuffling`
+    /// for new variations to be generated on each pass.
+    /// Use a seeded `rng` (like `StdRng::seed_from_u64` or `crate::rng::NeuraRng`) for reproducible augmentations.
+    ///
+    /// Panics if `shape` has more than three dimensions.
+    fn augment<A: NeuraAugmentation<F>, R: RngCore>(
+        self,
+        augmentation: A,
+        shape: NeuraShape,
+        rng: R,
+    ) -> NeuraAugmented<Self, A, R> {
+        if let Err(shape) = Dims::try_from(shape) {
+            panic!("Images must have at most 3 dimensions, got {:?}", shape);
+        }
+
+        NeuraAugmented {
+            iter: self,
+            augmentation,
+            shape,
+            rng,
+        }
+    }
+}
+
+impl<F: Scalar, Target, I: Iterator<Item = (DVector<F>, Target)>> NeuraAugmentIter<F, Target>
+    for I
+{
+}
+
+/// Shifts the image by a random whole number of pixels, filling the uncovered area with zeroes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NeuraTranslate {
+    pub max_rows: usize,
+    pub max_columns: usize,
+}
+
+impl NeuraTranslate {
+    pub fn new(max_rows: usize, max_columns: usize) -> Self {
+        Self {
+            max_rows,
+            max_columns,
+        }
+    }
+}
+
+impl<F: Float + Scalar> NeuraAugmentation<F> for NeuraTranslate {
+    fn augment(&self, image: &DVector<F>, shape: NeuraShape, rng: &mut dyn RngCore) -> DVector<F> {
+        let dims = Dims::try_from(shape).expect("Images must have at most 3 dimensions");
+        let max_rows = self.max_rows as isize;
+        let max_columns = self.max_columns as isize;
+        let dy = rng.gen_range(-max_rows..=max_rows);
+        let dx = rng.gen_range(-max_columns..=max_columns);
+
+        dims.map_pixels(
+            |row, column| {
+                let row = row as isize - dy;
+                let column = column as isize - dx;
+                (row >= 0
+                    && row < dims.rows as isize
+                    && column >= 0
+                    && column < dims.columns as isize)
+                    .then_some((row as usize, column as usize))
+            },
+            image,
+        )
+    }
+}
+
+/// Rotates the image around its center by a random angle in `[-max_angle; max_angle]` (in radians),
+/// using bilinear interpolation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NeuraRotate {
+    pub max_angle: f64,
+}
+
+impl NeuraRotate {
+    pub fn new(max_angle: f64) -> Self {
+        Self { max_angle }
+    }
+}
+
+impl<F: Float + Scalar> NeuraAugmentation<F> for NeuraRotate {
+    fn augment(&self, image: &DVector<F>, shape: NeuraShape, rng: &mut dyn RngCore) -> DVector<F> {
+        let dims = Dims::try_from(shape).expect("Images must have at most 3 dimensions");
+        let angle = rng.gen_range(-self.max_angle..=self.max_angle);
+        let (sin, cos) = angle.sin_cos();
+        let center_y = (dims.rows as f64 - 1.0) / 2.0;
+        let center_x = (dims.columns as f64 - 1.0) / 2.0;
+
+        dims.warp(image, |y, x| {
+            let (y, x) = (y - center_y, x - center_x);
+            (center_y + x * sin + y * cos, center_x + x * cos - y * sin)
+        })
+    }
+}
+
+/// Mirrors the image horizontally and/or vertically, each with the given probability.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NeuraFlip {
+    pub horizontal: f64,
+    pub vertical: f64,
+}
+
+impl NeuraFlip {
+    /// Flips the image horizontally half of the time
+    pub fn horizontal() -> Self {
+        Self {
+            horizontal: 0.5,
+            vertical: 0.0,
+        }
+    }
+
+    /// Flips the image vertically half of the time
+    pub fn vertical() -> Self {
+        Self {
+            horizontal: 0.0,
+            vertical: 0.5,
+        }
+    }
+}
+
+impl<F: Float + Scalar> NeuraAugmentation<F> for NeuraFlip {
+    fn augment(&self, image: &DVector<F>, shape: NeuraShape, rng: &mut dyn RngCore) -> DVector<F> {
+        let dims = Dims::try_from(shape).expect("Images must have at most 3 dimensions");
+        let horizontal = rng.gen_bool(self.horizontal);
+        let vertical = rng.gen_bool(self.vertical);
+
+        dims.map_pixels(
+            |row, column| {
+                Some((
+                    if vertical { dims.rows - row - 1 } else { row },
+                    if horizontal {
+                        dims.columns - column - 1
+                    } else {
+                        column
+                    },
+                ))
+            },
+            image,
+        )
+    }
+}
+
+/// Crops a random region of the image, whose sides are between `min_scale` and `1` times the sides of the image,
+/// and resizes it back to the size of the image using bilinear interpolation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NeuraCropResize {
+    pub min_scale: f64,
+}
+
+impl NeuraCropResize {
+    pub fn new(min_scale: f64) -> Self {
+        assert!(
+            min_scale > 0.0 && min_scale <= 1.0,
+            "min_scale must be in ]0; 1], got {}",
+            min_scale
+        );
+
+        Self { min_scale }
+    }
+}
+
+impl<F: Float + Scalar> NeuraAugmentation<F> for NeuraCropResize {
+    fn augment(&self, image: &DVector<F>, shape: NeuraShape, rng: &mut dyn RngCore) -> DVector<F> {
+        let dims = Dims::try_from(shape).expect("Images must have at most 3 dimensions");
+        let scale = rng.gen_range(self.min_scale..=1.0);
+        let top = rng.gen_range(0.0..=(1.0 - scale)) * dims.rows as f64;
+        let left = rng.gen_range(0.0..=(1.0 - scale)) * dims.columns as f64;
+
+        let max_y = dims.rows.saturating_sub(1) as f64;
+        let max_x = dims.columns.saturating_sub(1) as f64;
+
+        // Maps the centers of the output pixels to the crop region, which lies within the image
+        dims.warp(image, |y, x| {
+            (
+                (top + (y + 0.5) * scale - 0.5).clamp(0.0, max_y),
+                (left + (x + 0.5) * scale - 0.5).clamp(0.0, max_x),
+            )
+        })
+    }
+}
+
+/// Adds gaussian noise with a standard deviation of `stddev` to every value of the image.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NeuraGaussianNoise {
+    pub stddev: f64,
+}
+
+impl NeuraGaussianNoise {
+    pub fn new(stddev: f64) -> Self {
+        Self { stddev }
+    }
+}
+
+impl<F: Float + Scalar> NeuraAugmentation<F> for NeuraGaussianNoise {
+    fn augment(&self, image: &DVector<F>, _shape: NeuraShape, rng: &mut dyn RngCore) -> DVector<F> {
+        let distribution = Normal::new(0.0, self.stddev).unwrap();
+
+        image.map(|x| x + F::from(distribution.sample(rng)).unwrap())
+    }
+}
+
+/// Elastic distortion, as described by Simard et al. (2003):
+/// every pixel is displaced by a random field, smoothed by a gaussian filter of standard deviation `sigma`
+/// and scaled by `alpha` (both in pixels).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NeuraElasticDistortion {
+    pub alpha: f64,
+    pub sigma: f64,
+}
+
+impl NeuraElasticDistortion {
+    pub fn new(alpha: f64, sigma: f64) -> Self {
+        Self { alpha, sigma }
+    }
+}
+
+impl<F: Float + Scalar> NeuraAugmentation<F> for NeuraElasticDistortion {
+    fn augment(&self, image: &DVector<F>, shape: NeuraShape, rng: &mut dyn RngCore) -> DVector<F> {
+        let dims = Dims::try_from(shape).expect("Images must have at most 3 dimensions");
+        let mut random_field = || {
+            let field: Vec<f64> = (0..dims.rows * dims.columns)
+                .map(|_| rng.gen_range(-1.0..=1.0))
+                .collect();
+            dims.smooth(field, self.sigma)
+        };
+        let field_y = random_field();
+        let field_x = random_field();
+
+        dims.warp(image, |y, x| {
+            let index = y as usize * dims.columns + x as usize;
+            (
+                y + self.alpha * field_y[index],
+                x + self.alpha * field_x[index],
+            )
+        })
+    }
+}
+
+/// Randomly shifts the brightness of the image by up to `brightness`,
+/// and scales its contrast (around its mean value) by a factor in `[1 - contrast; 1 + contrast]`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NeuraBrightnessContrast {
+    pub brightness: f64,
+    pub contrast: f64,
+}
+
+impl NeuraBrightnessContrast {
+    pub fn new(brightness: f64, contrast: f64) -> Self {
+        Self {
+            brightness,
+            contrast,
+        }
+    }
+}
+
+impl<F: Float + Scalar> NeuraAugmentation<F> for NeuraBrightnessContrast {
+    fn augment(&self, image: &DVector<F>, _shape: NeuraShape, rng: &mut dyn RngCore) -> DVector<F> {
+        if image.is_empty() {
+            return image.clone();
+        }
+
+        let brightness = F::from(rng.gen_range(-self.brightness..=self.brightness)).unwrap();
+        let contrast =
+            F::from(rng.gen_range((1.0 - self.contrast)..=(1.0 + self.contrast))).unwrap();
+        let mean = image.iter().fold(F::zero(), |sum, &x| sum + x) / F::from(image.len()).unwrap();
+
+        image.map(|x| (x - mean) * contrast + mean + brightness)
+    }
+}
+
+/// The dimensions of an image, see the module documentation for its memory layout
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Dims {
+    rows: usize,
+    columns: usize,
+    channels: usize,
+}
+
+/// Fails with the given shape if it has more than three dimensions
+impl TryFrom<NeuraShape> for Dims {
+    type Error = NeuraShape;
+
+    fn try_from(shape: NeuraShape) -> Result<Self, NeuraShape> {
+        let (rows, columns, channels) = match *shape.dimensions() {
+            [length] => (1, length, 1),
+            [rows, columns] => (rows, columns, 1),
+            [rows, columns, channels] => (rows, columns, channels),
+            _ => return Err(shape),
+        };
+
+        Ok(Self {
+            rows,
+            columns,
+            channels,
+        })
+    }
+}
+
+impl Dims {
+    #[inline(always)]
+    fn index(&self, row: usize, column: usize, channel: usize) -> usize {
+        (row * self.columns + column) * self.channels + channel
+    }
+
+    /// Builds a new image, where each pixel is copied from the pixel of `image` returned by `source`, or set to zero
+    fn map_pixels<F: Float + Scalar>(
+        &self,
+        source: impl Fn(usize, usize) -> Option<(usize, usize)>,
+        image: &DVector<F>,
+    ) -> DVector<F> {
+        let mut res = DVector::from_element(image.len(), F::zero());
+
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                if let Some((source_row, source_column)) = source(row, column) {
+                    for channel in 0..self.channels {
+                        res[self.index(row, column, channel)] =
+                            image[self.index(source_row, source_column, channel)];
+                    }
+                }
+            }
+        }
+
+        res
+    }
+
+    /// Builds a new image, where each pixel is sampled from `image` at the (fractional) coordinates returned by `source`.
+    fn warp<F: Float + Scalar>(
+        &self,
+        image: &DVector<F>,
+        source: impl Fn(f64, f64) -> (f64, f64),
+    ) -> DVector<F> {
+        let mut res = DVector::from_element(image.len(), F::zero());
+
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                let (y, x) = source(row as f64, column as f64);
+
+                for channel in 0..self.channels {
+                    res[self.index(row, column, channel)] = self.sample(image, y, x, channel);
+                }
+            }
+        }
+
+        res
+    }
+
+    /// Bilinearly interpolates `image` at `(y, x)`, treating the pixels outside of the image as zero
+    fn sample<F: Float + Scalar>(&self, image: &DVector<F>, y: f64, x: f64, channel: usize) -> F {
+        let (y0, x0) = (y.floor(), x.floor());
+        let (fy, fx) = (y - y0, x - x0);
+        let mut res = F::zero();
+
+        for (row, weight_y) in [(y0, 1.0 - fy), (y0 + 1.0, fy)] {
+            for (column, weight_x) in [(x0, 1.0 - fx), (x0 + 1.0, fx)] {
+                let weight = weight_y * weight_x;
+                if weight == 0.0
+                    || row < 0.0
+                    || column < 0.0
+                    || row >= self.rows as f64
+                    || column >= self.columns as f64
+                {
+                    continue;
+                }
+
+                let value = image[self.index(row as usize, column as usize, channel)];
+                res = res + value * F::from(weight).unwrap();
+            }
+        }
+
+        res
+    }
+
+    /// Applies a separable gaussian filter to a single-channel `field`, clamping at the edges
+    fn smooth(&self, field: Vec<f64>, sigma: f64) -> Vec<f64> {
+        if sigma <= 0.0 {
+            return field;
+        }
+
+        let radius = (3.0 * sigma).ceil() as isize;
+        let kernel: Vec<f64> = (-radius..=radius)
+            .map(|i| (-(i * i) as f64 / (2.0 * sigma * sigma)).exp())
+            .collect();
+        let kernel_sum: f64 = kernel.iter().sum();
+
+        let convolve = |field: &[f64], horizontal: bool| -> Vec<f64> {
+            let mut res = vec![0.0; field.len()];
+            for row in 0..self.rows {
+                for column in 0..self.columns {
+                    let mut sum = 0.0;
+                    for (offset, weight) in (-radius..=radius).zip(kernel.iter()) {
+                        let (r, c) = if horizontal {
+                            let c = column as isize + offset;
+                            (row, c.clamp(0, self.columns as isize - 1) as usize)
+                        } else {
+                            let r = row as isize + offset;
+                            (r.clamp(0, self.rows as isize - 1) as usize, column)
+                        };
+                        sum += weight * field[r * self.columns + c];
+                    }
+                    res[row * self.columns + column] = sum / kernel_sum;
+                }
+            }
+            res
+        };
+
+        let field = convolve(&field, true);
+        convolve(&field, false)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::dvector;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::layer::NeuraDims;
+
+    fn gradient_image(shape: NeuraShape) -> DVector<f64> {
+        DVector::from_fn(shape.size(), |i, _| i as f64)
+    }
+
+    #[test]
+    fn test_identity() {
+        let shape = NeuraShape::Tensor(5, 4, 3);
+        let image = gradient_image(shape);
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let augmentation = (
+            NeuraTranslate::new(0, 0),
+            NeuraRotate::new(0.0),
+            NeuraFlip {
+                horizontal: 0.0,
+                vertical: 0.0,
+            },
+            NeuraCropResize::new(1.0),
+            NeuraGaussianNoise::new(0.0),
+            NeuraElasticDistortion::new(0.0, 2.0),
+            NeuraBrightnessContrast::new(0.0, 0.0),
+        );
+
+        assert_relative_eq!(
+            augmentation.augment(&image, shape, &mut rng),
+            image,
+            epsilon = 1e-10
+        );
+    }
+
+    #[test]
+    fn test_flip_translate() {
+        let shape = NeuraShape::Tensor(2, 3, 2);
+        let image = gradient_image(shape);
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let flip = NeuraFlip {
+            horizontal: 1.0,
+            vertical: 0.0,
+        };
+        assert_eq!(
+            flip.augment(&image, shape, &mut rng),
+            dvector![4.0, 5.0, 2.0, 3.0, 0.0, 1.0, 10.0, 11.0, 8.0, 9.0, 6.0, 7.0]
+        );
+
+        let flip = NeuraFlip {
+            horizontal: 0.0,
+            vertical: 1.0,
+        };
+        assert_eq!(
+            flip.augment(&image, shape, &mut rng),
+            dvector![6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 0.0, 1.0, 2.0, 3.0, 4.0, 5.0]
+        );
+
+        // Hand-built `Nd` shapes of rank 3 or less are images too
+        let nd_shape = NeuraShape::Nd(NeuraDims::new(&[2, 3, 2]));
+        assert_eq!(
+            flip.augment(&image, nd_shape, &mut rng),
+            flip.augment(&image, shape, &mut rng)
+        );
+        assert_eq!(
+            Dims::try_from(NeuraShape::new(&[1, 2, 3, 2])),
+            Err(NeuraShape::new(&[1, 2, 3, 2]))
+        );
+
+        // Shifting by at most one pixel uncovers either 0, 3 or 5 pixels of a 3x3 image
+        let shape = NeuraShape::Matrix(3, 3);
+        let image = gradient_image(shape).add_scalar(1.0);
+        for _ in 0..10 {
+            let output = NeuraTranslate::new(1, 1).augment(&image, shape, &mut rng);
+            let zeroes = output.iter().filter(|&&x| x == 0.0).count();
+            assert!([0, 3, 5].contains(&zeroes));
+            assert_eq!(output[4] != 5.0, zeroes != 0);
+        }
+    }
+
+    #[test]
+    fn test_rotate_crop() {
+        let shape = NeuraShape::Matrix(5, 5);
+        let image = DVector::from_element(25, 1.0);
+        let mut rng = StdRng::seed_from_u64(1);
+
+        for _ in 0..10 {
+            let output = NeuraRotate::new(std::f64::consts::PI).augment(&image, shape, &mut rng);
+            assert_relative_eq!(output[12], 1.0, epsilon = 1e-10);
+
+            let output = NeuraCropResize::new(0.5).augment(&image, shape, &mut rng);
+            assert_relative_eq!(output, image, epsilon = 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_pixel_augmentations() {
+        let shape = NeuraShape::Vector(10_000);
+        let image = DVector::from_element(10_000, 0.5);
+        let mut rng = StdRng::seed_from_u64(2);
+
+        let output = NeuraGaussianNoise::new(0.1).augment(&image, shape, &mut rng);
+        let mean = output.mean();
+        let stddev = output.map(|x| (x - mean).powi(2)).mean().sqrt();
+        assert_relative_eq!(mean, 0.5, epsilon = 0.01);
+        assert_relative_eq!(stddev, 0.1, epsilon = 0.01);
+
+        let image = dvector![0.0, 1.0];
+        for _ in 0..10 {
+            let output = NeuraBrightnessContrast::new(0.2, 0.5).augment(
+                &image,
+                NeuraShape::Vector(2),
+                &mut rng,
+            );
+            assert!((output.mean() - 0.5).abs() <= 0.2 + 1e-10);
+            assert!((0.5..=1.5).contains(&(output[1] - output[0])));
+        }
+    }
+
+    #[test]
+    fn test_augment_iter() {
+        let shape = NeuraShape::Matrix(8, 8);
+        let samples: Vec<_> = (0..4).map(|i| (gradient_image(shape), i)).collect();
+        let augmentation = (
+            NeuraElasticDistortion::new(2.0, 1.5),
+            NeuraGaussianNoise::new(0.1),
+        );
+
+        let run = |seed| -> Vec<_> {
+            samples
+                .clone()
+                .into_iter()
+                .augment(augmentation, shape, StdRng::seed_from_u64(seed))
+                .collect()
+        };
+
+        let first = run(3);
+        assert_eq!(first, run(3));
+        assert_ne!(first, run(4));
+        assert_eq!(
+            first.iter().map(|(_, target)| *target).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3]
+        );
+        assert_ne!(first[0].0, first[1].0);
+    }
+}
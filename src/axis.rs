@@ -2,7 +2,10 @@ use std::borrow::Borrow;
 use std::fmt::Debug;
 
 use nalgebra::{Const, DVector, Dyn, Scalar, VecStorage};
+use ndarray::{Array, Axis, RemoveAxis, Slice};
+use num::Float;
 
+use crate::algebra::NeuraTensor;
 use crate::err::NeuraAxisErr;
 use crate::prelude::NeuraShape;
 
@@ -17,9 +20,21 @@ pub trait NeuraAxisBase: Clone + Debug + 'static {
 pub trait NeuraAxis<Input>: NeuraAxisBase {
     type Combined: 'static;
 
-    fn combine(&self, inputs: &[impl Borrow<Input>]) -> Self::Combined;
+    /// Combines `inputs`, whose shapes are given by `input_shapes`.
+    /// `input_shapes` may be empty if the shapes of the inputs aren't known, in which case they are treated as vectors.
+    fn combine(&self, inputs: &[impl Borrow<Input>], input_shapes: &[NeuraShape])
+        -> Self::Combined;
 
-    fn split(&self, combined: &Self::Combined, input_shapes: &[NeuraShape]) -> Vec<Input>;
+    /// Splits the gradient of the combined value into the gradient of each input.
+    ///
+    /// `inputs` are the values that were passed to `combine`: operators that cannot be inverted,
+    /// like `NeuraAxisProduct` or `NeuraAxisMax`, need them to route the gradient.
+    fn split(
+        &self,
+        combined: &Self::Combined,
+        inputs: &[impl Borrow<Input>],
+        input_shapes: &[NeuraShape],
+    ) -> Vec<Input>;
 }
 
 /// An axis operator that
@@ -41,78 +56,658 @@ impl NeuraAxisBase for NeuraAxisDefault {
 impl<Data: Clone + 'static> NeuraAxis<Data> for NeuraAxisDefault {
     type Combined = Data;
 
-    fn combine(&self, inputs: &[impl Borrow<Data>]) -> Self::Combined {
+    fn combine(
+        &self,
+        inputs: &[impl Borrow<Data>],
+        _input_shapes: &[NeuraShape],
+    ) -> Self::Combined {
         assert!(inputs.len() == 1);
 
         inputs[0].borrow().clone()
     }
 
-    fn split(&self, combined: &Self::Combined, _input_shapes: &[NeuraShape]) -> Vec<Data> {
+    fn split(
+        &self,
+        combined: &Self::Combined,
+        _inputs: &[impl Borrow<Data>],
+        _input_shapes: &[NeuraShape],
+    ) -> Vec<Data> {
         vec![combined.clone()]
     }
 }
 
+/// An axis operator that concatenates its inputs along their last dimension:
+/// vectors are appended to one another, `Matrix`es are concatenated column-wise and `Tensor`s channel-wise.
+///
+/// All of the inputs must have the same number of dimensions, and only differ along the last one.
+/// To concatenate along another dimension, or to combine `NeuraTensor`s or `ndarray` arrays, use `NeuraAxisAppendAlong`.
 #[derive(Clone, Copy, Debug)]
 pub struct NeuraAxisAppend;
 
+/// An axis operator that concatenates its inputs along the given dimension:
+/// for a `Matrix`, `0` stacks the rows and `1` the columns; for a `Tensor`, `2` stacks the channels.
+///
+/// All of the inputs must have the same number of dimensions, and only differ along the concatenated one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NeuraAxisAppendAlong(pub usize);
+
+/// Returns the dimension along which inputs of rank `rank` are concatenated, defaulting to the last one
+fn append_axis(axis: Option<usize>, rank: usize) -> usize {
+    axis.unwrap_or(rank.saturating_sub(1))
+}
+
+/// Returns the number of blocks that each input is made of, and the length of the blocks of each input:
+/// the combined value is made of the first block of each input, then the second block of each input, etc.
+fn append_blocks(axis: Option<usize>, input_shapes: &[NeuraShape]) -> (usize, Vec<usize>) {
+    let Some(first) = input_shapes.first() else {
+        return (0, Vec::new());
+    };
+    let axis = append_axis(axis, first.dims());
+    let dimensions = first.dimensions();
+    assert!(
+        axis < dimensions.len(),
+        "Cannot append shapes of rank {} along axis {}",
+        dimensions.len(),
+        axis
+    );
+
+    let count = dimensions[..axis].iter().product();
+    let inner: usize = dimensions[axis + 1..].iter().product();
+    let lengths = input_shapes
+        .iter()
+        .map(|shape| shape.dimensions()[axis] * inner)
+        .collect();
+
+    (count, lengths)
+}
+
+fn append_shape(axis: Option<usize>, inputs: &[NeuraShape]) -> Result<NeuraShape, NeuraAxisErr> {
+    let Some(&first) = inputs.first() else {
+        return Err(NeuraAxisErr::NoInput);
+    };
+
+    let axis = append_axis(axis, first.dims());
+    if axis >= first.dims() {
+        return Err(NeuraAxisErr::InvalidAxis(axis, first));
+    }
+
+    let mut res = first.dimensions();
+    for &operand in inputs.iter().skip(1) {
+        let dimensions = operand.dimensions();
+        let compatible = dimensions.len() == res.len()
+            && (0..res.len()).all(|i| i == axis || dimensions[i] == res[i]);
+
+        if !compatible {
+            return Err(NeuraAxisErr::ConflictingShape(
+                NeuraShape::new(&res),
+                operand,
+            ));
+        }
+
+        res[axis] += dimensions[axis];
+    }
+
+    Ok(NeuraShape::new(&res))
+}
+
+fn append_combine<F: Clone + Default + Scalar>(
+    axis: Option<usize>,
+    inputs: &[impl Borrow<DVector<F>>],
+    input_shapes: &[NeuraShape],
+) -> DVector<F> {
+    assert!(!inputs.is_empty());
+    let (count, lengths) = if input_shapes.is_empty() {
+        (1, inputs.iter().map(|input| input.borrow().len()).collect())
+    } else {
+        assert_eq!(inputs.len(), input_shapes.len());
+        append_blocks(axis, input_shapes)
+    };
+    let mut res = Vec::with_capacity(inputs.iter().map(|vec| vec.borrow().len()).sum());
+
+    for block in 0..count {
+        for (input, &length) in inputs.iter().zip(lengths.iter()) {
+            let input = input.borrow();
+            assert_eq!(input.len(), count * length);
+
+            res.extend_from_slice(&input.as_slice()[block * length..(block + 1) * length]);
+        }
+    }
+
+    DVector::from_data(VecStorage::new(Dyn(res.len()), Const as Const<1>, res))
+}
+
+fn append_split<F: Clone + Default + Scalar>(
+    axis: Option<usize>,
+    combined: &DVector<F>,
+    input_shapes: &[NeuraShape],
+) -> Vec<DVector<F>> {
+    let (count, lengths) = append_blocks(axis, input_shapes);
+    let mut result: Vec<Vec<F>> = lengths
+        .iter()
+        .map(|&length| Vec::with_capacity(count * length))
+        .collect();
+    let mut offset = 0;
+
+    for _ in 0..count {
+        for (subvector, &length) in result.iter_mut().zip(lengths.iter()) {
+            subvector.extend_from_slice(&combined.as_slice()[offset..offset + length]);
+            offset += length;
+        }
+    }
+
+    result.into_iter().map(DVector::from_vec).collect()
+}
+
 impl NeuraAxisBase for NeuraAxisAppend {
     type Err = NeuraAxisErr;
 
     fn shape(&self, inputs: &[NeuraShape]) -> Result<NeuraShape, NeuraAxisErr> {
-        let mut inputs = inputs.iter().map(|x| *x.borrow());
-        if let Some(mut res) = inputs.next() {
-            for operand in inputs {
-                match (res, operand) {
-                    (NeuraShape::Vector(x), NeuraShape::Vector(y)) => {
-                        res = NeuraShape::Vector(x + y);
-                    }
-                    (x, y) => {
-                        return Err(NeuraAxisErr::ConflictingShape(x, y));
-                    }
-                }
-            }
-            Ok(res)
-        } else {
-            Err(NeuraAxisErr::NoInput)
-        }
+        append_shape(None, inputs)
     }
 }
 
 impl<F: Clone + Default + Scalar> NeuraAxis<DVector<F>> for NeuraAxisAppend {
     type Combined = DVector<F>;
 
-    fn combine(&self, inputs: &[impl Borrow<DVector<F>>]) -> Self::Combined {
+    fn combine(
+        &self,
+        inputs: &[impl Borrow<DVector<F>>],
+        input_shapes: &[NeuraShape],
+    ) -> Self::Combined {
+        append_combine(None, inputs, input_shapes)
+    }
+
+    fn split(
+        &self,
+        combined: &Self::Combined,
+        _inputs: &[impl Borrow<DVector<F>>],
+        input_shapes: &[NeuraShape],
+    ) -> Vec<DVector<F>> {
+        append_split(None, combined, input_shapes)
+    }
+}
+
+impl NeuraAxisBase for NeuraAxisAppendAlong {
+    type Err = NeuraAxisErr;
+
+    fn shape(&self, inputs: &[NeuraShape]) -> Result<NeuraShape, NeuraAxisErr> {
+        append_shape(Some(self.0), inputs)
+    }
+}
+
+impl<F: Clone + Default + Scalar> NeuraAxis<DVector<F>> for NeuraAxisAppendAlong {
+    type Combined = DVector<F>;
+
+    fn combine(
+        &self,
+        inputs: &[impl Borrow<DVector<F>>],
+        input_shapes: &[NeuraShape],
+    ) -> Self::Combined {
+        append_combine(Some(self.0), inputs, input_shapes)
+    }
+
+    fn split(
+        &self,
+        combined: &Self::Combined,
+        _inputs: &[impl Borrow<DVector<F>>],
+        input_shapes: &[NeuraShape],
+    ) -> Vec<DVector<F>> {
+        append_split(Some(self.0), combined, input_shapes)
+    }
+}
+
+/// Concatenates the tensors, using their own shapes; `input_shapes` must match them if it isn't empty
+impl<F: Clone + Default + Scalar> NeuraAxis<NeuraTensor<F>> for NeuraAxisAppendAlong {
+    type Combined = NeuraTensor<F>;
+
+    fn combine(
+        &self,
+        inputs: &[impl Borrow<NeuraTensor<F>>],
+        input_shapes: &[NeuraShape],
+    ) -> Self::Combined {
+        let shapes: Vec<_> = inputs.iter().map(|input| input.borrow().shape()).collect();
+        if !input_shapes.is_empty() {
+            assert_eq!(shapes, input_shapes);
+        }
+
+        let vectors: Vec<_> = inputs
+            .iter()
+            .map(|input| input.borrow().as_vector())
+            .collect();
+        let shape = self
+            .shape(&shapes)
+            .expect("Inputs should have compatible shapes");
+
+        let combined = append_combine(Some(self.0), &vectors, &shapes);
+
+        NeuraTensor::from_vector(combined, shape).unwrap()
+    }
+
+    fn split(
+        &self,
+        combined: &Self::Combined,
+        _inputs: &[impl Borrow<NeuraTensor<F>>],
+        input_shapes: &[NeuraShape],
+    ) -> Vec<NeuraTensor<F>> {
+        append_split(Some(self.0), combined.as_vector(), input_shapes)
+            .into_iter()
+            .zip(input_shapes.iter())
+            .map(|(vector, &shape)| NeuraTensor::from_vector(vector, shape).unwrap())
+            .collect()
+    }
+}
+
+/// Concatenates the arrays along the selected axis of the arrays
+impl<F: Clone + 'static, D: RemoveAxis + 'static> NeuraAxis<Array<F, D>> for NeuraAxisAppendAlong {
+    type Combined = Array<F, D>;
+
+    fn combine(
+        &self,
+        inputs: &[impl Borrow<Array<F, D>>],
+        _input_shapes: &[NeuraShape],
+    ) -> Self::Combined {
         assert!(!inputs.is_empty());
-        let mut res = Vec::with_capacity(inputs.iter().map(|vec| vec.borrow().len()).sum());
+        let views: Vec<_> = inputs.iter().map(|input| input.borrow().view()).collect();
 
-        for input in inputs {
-            for x in input.borrow().iter() {
-                res.push(x.clone());
+        ndarray::concatenate(Axis(self.0), &views).expect("Inputs should have compatible shapes")
+    }
+
+    fn split(
+        &self,
+        combined: &Self::Combined,
+        inputs: &[impl Borrow<Array<F, D>>],
+        _input_shapes: &[NeuraShape],
+    ) -> Vec<Array<F, D>> {
+        let axis = Axis(self.0);
+        let mut result = Vec::with_capacity(inputs.len());
+        let mut offset = 0;
+
+        for input in inputs.iter() {
+            let length = input.borrow().len_of(axis);
+
+            result.push(
+                combined
+                    .slice_axis(axis, Slice::from(offset..offset + length))
+                    .to_owned(),
+            );
+
+            offset += length;
+        }
+
+        result
+    }
+}
+
+/// Returns the shape shared by all of the inputs, for the element-wise axis operators
+fn element_wise_shape(inputs: &[NeuraShape]) -> Result<NeuraShape, NeuraAxisErr> {
+    let Some(&res) = inputs.first() else {
+        return Err(NeuraAxisErr::NoInput);
+    };
+
+    for &operand in inputs.iter().skip(1) {
+        if operand != res {
+            return Err(NeuraAxisErr::ConflictingShape(res, operand));
+        }
+    }
+
+    Ok(res)
+}
+
+macro_rules! element_wise_axis {
+    ( $name:ident ) => {
+        impl NeuraAxisBase for $name {
+            type Err = NeuraAxisErr;
+
+            fn shape(&self, inputs: &[NeuraShape]) -> Result<NeuraShape, NeuraAxisErr> {
+                element_wise_shape(inputs)
             }
         }
+    };
+}
 
-        DVector::from_data(VecStorage::new(Dyn(res.len()), Const as Const<1>, res))
+/// An axis operator that sums its inputs element-wise, as in the skip connections of ResNets.
+/// All of the inputs must have the same shape.
+#[derive(Clone, Copy, Debug)]
+pub struct NeuraAxisSum;
+
+element_wise_axis!(NeuraAxisSum);
+
+impl<F: Float + Scalar> NeuraAxis<DVector<F>> for NeuraAxisSum {
+    type Combined = DVector<F>;
+
+    fn combine(
+        &self,
+        inputs: &[impl Borrow<DVector<F>>],
+        _input_shapes: &[NeuraShape],
+    ) -> Self::Combined {
+        assert!(!inputs.is_empty());
+        let mut res = inputs[0].borrow().clone();
+
+        for input in &inputs[1..] {
+            res.zip_apply(input.borrow(), |x, y| *x = *x + y);
+        }
+
+        res
     }
 
-    fn split(&self, combined: &Self::Combined, input_shapes: &[NeuraShape]) -> Vec<DVector<F>> {
-        let mut result = Vec::with_capacity(input_shapes.len());
-        let mut offset = 0;
+    fn split(
+        &self,
+        combined: &Self::Combined,
+        inputs: &[impl Borrow<DVector<F>>],
+        _input_shapes: &[NeuraShape],
+    ) -> Vec<DVector<F>> {
+        vec![combined.clone(); inputs.len()]
+    }
+}
+
+/// An axis operator that averages its inputs element-wise.
+/// All of the inputs must have the same shape.
+#[derive(Clone, Copy, Debug)]
+pub struct NeuraAxisMean;
+
+element_wise_axis!(NeuraAxisMean);
+
+impl<F: Float + Scalar> NeuraAxis<DVector<F>> for NeuraAxisMean {
+    type Combined = DVector<F>;
+
+    fn combine(
+        &self,
+        inputs: &[impl Borrow<DVector<F>>],
+        _input_shapes: &[NeuraShape],
+    ) -> Self::Combined {
+        let count = F::from(inputs.len()).unwrap();
+
+        NeuraAxisSum.combine(inputs, &[]).map(|x| x / count)
+    }
+
+    fn split(
+        &self,
+        combined: &Self::Combined,
+        inputs: &[impl Borrow<DVector<F>>],
+        _input_shapes: &[NeuraShape],
+    ) -> Vec<DVector<F>> {
+        let count = F::from(inputs.len()).unwrap();
+
+        vec![combined.map(|x| x / count); inputs.len()]
+    }
+}
+
+/// An axis operator that multiplies its inputs element-wise, which can be used for gating.
+/// All of the inputs must have the same shape.
+#[derive(Clone, Copy, Debug)]
+pub struct NeuraAxisProduct;
+
+element_wise_axis!(NeuraAxisProduct);
+
+impl<F: Float + Scalar> NeuraAxis<DVector<F>> for NeuraAxisProduct {
+    type Combined = DVector<F>;
+
+    fn combine(
+        &self,
+        inputs: &[impl Borrow<DVector<F>>],
+        _input_shapes: &[NeuraShape],
+    ) -> Self::Combined {
+        assert!(!inputs.is_empty());
+        let mut res = inputs[0].borrow().clone();
+
+        for input in &inputs[1..] {
+            res.zip_apply(input.borrow(), |x, y| *x = *x * y);
+        }
+
+        res
+    }
+
+    fn split(
+        &self,
+        combined: &Self::Combined,
+        inputs: &[impl Borrow<DVector<F>>],
+        _input_shapes: &[NeuraShape],
+    ) -> Vec<DVector<F>> {
+        // The gradient of each input is the product of the other inputs;
+        // it is computed without dividing by the input, which could be zero
+        (0..inputs.len())
+            .map(|index| {
+                let mut gradient = combined.clone();
+
+                for (other_index, other) in inputs.iter().enumerate() {
+                    if other_index != index {
+                        gradient.zip_apply(other.borrow(), |x, y| *x = *x * y);
+                    }
+                }
+
+                gradient
+            })
+            .collect()
+    }
+}
+
+/// An axis operator that takes the element-wise maximum of its inputs.
+/// All of the inputs must have the same shape.
+///
+/// The gradient of each entry is routed to the input that had the highest value; on ties, the first one.
+#[derive(Clone, Copy, Debug)]
+pub struct NeuraAxisMax;
 
-        for &input_shape in input_shapes.iter() {
-            let NeuraShape::Vector(input_shape) = input_shape else {
-                panic!("Expected {:?} to be NeuraShape::Vector", input_shape);
-            };
+element_wise_axis!(NeuraAxisMax);
 
-            let mut subvector = DVector::from_element(input_shape, F::default());
+impl<F: Float + Scalar> NeuraAxis<DVector<F>> for NeuraAxisMax {
+    type Combined = DVector<F>;
+
+    fn combine(
+        &self,
+        inputs: &[impl Borrow<DVector<F>>],
+        _input_shapes: &[NeuraShape],
+    ) -> Self::Combined {
+        assert!(!inputs.is_empty());
+        let mut res = inputs[0].borrow().clone();
 
-            for i in 0..input_shape {
-                subvector[i] = combined[offset + i].clone();
+        for input in &inputs[1..] {
+            res.zip_apply(input.borrow(), |x, y| *x = x.max(y));
+        }
+
+        res
+    }
+
+    fn split(
+        &self,
+        combined: &Self::Combined,
+        inputs: &[impl Borrow<DVector<F>>],
+        _input_shapes: &[NeuraShape],
+    ) -> Vec<DVector<F>> {
+        let mut result = vec![DVector::zeros(combined.len()); inputs.len()];
+
+        for i in 0..combined.len() {
+            let mut max_index = 0;
+            for (index, input) in inputs.iter().enumerate().skip(1) {
+                if input.borrow()[i] > inputs[max_index].borrow()[i] {
+                    max_index = index;
+                }
             }
-            result.push(subvector);
 
-            offset += input_shape;
+            result[max_index][i] = combined[i];
         }
 
         result
     }
 }
+
+#[cfg(test)]
+mod test {
+    use nalgebra::dvector;
+
+    use super::*;
+
+    /// Compares the gradients returned by `split` with finite differences of `gradient · combine(inputs)`
+    fn check_split(axis: impl NeuraAxis<DVector<f64>, Combined = DVector<f64>>) {
+        const EPSILON: f64 = 1e-6;
+        let inputs = [
+            dvector![0.5, -1.0, 2.0],
+            dvector![1.5, 0.25, -3.0],
+            dvector![-0.75, 0.5, 1.0],
+        ];
+        let shapes = [NeuraShape::Vector(3); 3];
+        let gradient = dvector![1.0, -2.0, 0.5];
+
+        let split = axis.split(&gradient, &inputs, &shapes);
+        assert_eq!(split.len(), inputs.len());
+
+        for (index, input_gradient) in split.iter().enumerate() {
+            for i in 0..3 {
+                let mut shifted = inputs.clone();
+                shifted[index][i] += EPSILON;
+
+                let expected = (axis.combine(&shifted, &shapes).dot(&gradient)
+                    - axis.combine(&inputs, &shapes).dot(&gradient))
+                    / EPSILON;
+                assert!(
+                    (input_gradient[i] - expected).abs() < 1e-4,
+                    "{:?}: expected {}, got {} for input {}, entry {}",
+                    axis,
+                    expected,
+                    input_gradient[i],
+                    index,
+                    i
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_append_shape() {
+        assert_eq!(
+            NeuraAxisAppend
+                .shape(&[NeuraShape::Vector(2), NeuraShape::Vector(3)])
+                .unwrap(),
+            NeuraShape::Vector(5)
+        );
+        assert_eq!(
+            NeuraAxisAppend
+                .shape(&[NeuraShape::Tensor(4, 4, 3), NeuraShape::Tensor(4, 4, 5)])
+                .unwrap(),
+            NeuraShape::Tensor(4, 4, 8)
+        );
+        assert_eq!(
+            NeuraAxisAppendAlong(0)
+                .shape(&[NeuraShape::Matrix(2, 3), NeuraShape::Matrix(1, 3)])
+                .unwrap(),
+            NeuraShape::Matrix(3, 3)
+        );
+        assert_eq!(
+            NeuraAxisAppendAlong(1)
+                .shape(&[
+                    NeuraShape::new(&[2, 3, 4, 5]),
+                    NeuraShape::new(&[2, 1, 4, 5])
+                ])
+                .unwrap(),
+            NeuraShape::new(&[2, 4, 4, 5])
+        );
+
+        assert!(matches!(
+            NeuraAxisAppendAlong(0).shape(&[NeuraShape::Matrix(2, 3), NeuraShape::Matrix(2, 2)]),
+            Err(NeuraAxisErr::ConflictingShape(..))
+        ));
+        assert!(matches!(
+            NeuraAxisAppend.shape(&[NeuraShape::Vector(2), NeuraShape::Matrix(2, 2)]),
+            Err(NeuraAxisErr::ConflictingShape(..))
+        ));
+        assert!(matches!(
+            NeuraAxisAppendAlong(2).shape(&[NeuraShape::Matrix(2, 3)]),
+            Err(NeuraAxisErr::InvalidAxis(2, NeuraShape::Matrix(2, 3)))
+        ));
+    }
+
+    #[test]
+    fn test_append_matrix() {
+        // [[1, 2], [3, 4]] and [[5], [6]], stored row by row
+        let inputs = [dvector![1.0, 2.0, 3.0, 4.0], dvector![5.0, 6.0]];
+        let shapes = [NeuraShape::Matrix(2, 2), NeuraShape::Matrix(2, 1)];
+
+        let axis = NeuraAxisAppendAlong(1);
+        let combined = axis.combine(&inputs, &shapes);
+        assert_eq!(combined, dvector![1.0, 2.0, 5.0, 3.0, 4.0, 6.0]);
+        assert_eq!(axis.split(&combined, &inputs, &shapes), inputs);
+
+        // NeuraAxisAppend concatenates along the last dimension
+        assert_eq!(NeuraAxisAppend.combine(&inputs, &shapes), combined);
+        assert_eq!(NeuraAxisAppend.split(&combined, &inputs, &shapes), inputs);
+
+        let shapes = [NeuraShape::Matrix(2, 2), NeuraShape::Matrix(1, 2)];
+        let axis = NeuraAxisAppendAlong(0);
+        let combined = axis.combine(&inputs, &shapes);
+        assert_eq!(combined, dvector![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert_eq!(axis.split(&combined, &inputs, &shapes), inputs);
+    }
+
+    #[test]
+    fn test_append_tensor() {
+        let first = NeuraTensor::from_fn((2, 2, 2), |index| (index[0] * 10 + index[1]) as f64);
+        let second = NeuraTensor::from_fn((2, 2, 1), |index| -((index[0] * 10 + index[1]) as f64));
+        let inputs = [first, second];
+        let shapes = [NeuraShape::Tensor(2, 2, 2), NeuraShape::Tensor(2, 2, 1)];
+
+        let axis = NeuraAxisAppendAlong(2);
+        let combined = axis.combine(&inputs, &shapes);
+        assert_eq!(combined.shape(), NeuraShape::Tensor(2, 2, 3));
+        for row in 0..2 {
+            for column in 0..2 {
+                let value = (row * 10 + column) as f64;
+                assert_eq!(combined.get(&[row, column, 0]), Some(&value));
+                assert_eq!(combined.get(&[row, column, 1]), Some(&value));
+                assert_eq!(combined.get(&[row, column, 2]), Some(&-value));
+            }
+        }
+
+        assert_eq!(axis.split(&combined, &inputs, &shapes), inputs);
+    }
+
+    #[test]
+    fn test_append_ndarray() {
+        let inputs = [
+            ndarray::arr2(&[[1.0, 2.0], [3.0, 4.0]]),
+            ndarray::arr2(&[[5.0], [6.0]]),
+        ];
+
+        let combined = NeuraAxisAppendAlong(1).combine(&inputs, &[]);
+        assert_eq!(combined, ndarray::arr2(&[[1.0, 2.0, 5.0], [3.0, 4.0, 6.0]]));
+        assert_eq!(
+            NeuraAxisAppendAlong(1).split(&combined, &inputs, &[]),
+            inputs
+        );
+    }
+
+    #[test]
+    fn test_element_wise_axes() {
+        let inputs = [dvector![1.0, -2.0], dvector![3.0, 4.0]];
+
+        assert_eq!(NeuraAxisSum.combine(&inputs, &[]), dvector![4.0, 2.0]);
+        assert_eq!(NeuraAxisMean.combine(&inputs, &[]), dvector![2.0, 1.0]);
+        assert_eq!(NeuraAxisProduct.combine(&inputs, &[]), dvector![3.0, -8.0]);
+        assert_eq!(NeuraAxisMax.combine(&inputs, &[]), dvector![3.0, 4.0]);
+
+        check_split(NeuraAxisSum);
+        check_split(NeuraAxisMean);
+        check_split(NeuraAxisProduct);
+        check_split(NeuraAxisMax);
+    }
+
+    #[test]
+    fn test_element_wise_shape() {
+        assert_eq!(
+            NeuraAxisSum
+                .shape(&[NeuraShape::Matrix(2, 3), NeuraShape::Matrix(2, 3)])
+                .unwrap(),
+            NeuraShape::Matrix(2, 3)
+        );
+        assert!(matches!(
+            NeuraAxisMax.shape(&[NeuraShape::Vector(3), NeuraShape::Vector(2)]),
+            Err(NeuraAxisErr::ConflictingShape(
+                NeuraShape::Vector(3),
+                NeuraShape::Vector(2)
+            ))
+        ));
+        assert!(matches!(
+            NeuraAxisProduct.shape(&[]),
+            Err(NeuraAxisErr::NoInput)
+        ));
+    }
+}
@@ -0,0 +1,354 @@
+use std::{fs::File, io::Read, path::Path};
+
+use nalgebra::{DVector, Scalar};
+use num::Float;
+
+use super::NeuraDataset;
+use crate::err::NeuraLoadErr;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum LabelColumn {
+    Last,
+    Index(usize),
+    Name(String),
+}
+
+/// Loads a dataset from a CSV file, where each row contains the features of an input and its label.
+///
+/// By default, the first row is treated as a header, the label is in the last column and is parsed as a number.
+/// If `one_hot` is set, then the labels are instead treated as categories and one-hot encoded.
+///
+/// ```no_run
+/// use neuramethyst::dataset::NeuraCsvLoader;
+///
+/// let iris = NeuraCsvLoader::new()
+///     .label_column_name("species")
+///     .one_hot(true)
+///     .load::<f32>("data/iris.csv")
+///     .unwrap();
+///
+/// println!("Classes: {:?}", iris.classes);
+/// ```
+#[derive(Clone, Debug)]
+pub struct NeuraCsvLoader {
+    delimiter: u8,
+    has_header: bool,
+    label_column: LabelColumn,
+    one_hot: bool,
+}
+
+/// A dataset loaded by `NeuraCsvLoader`.
+#[derive(Clone, Debug)]
+pub struct NeuraCsvDataset<F: Scalar> {
+    pub samples: Vec<(DVector<F>, DVector<F>)>,
+
+    /// The names of the feature columns, if the file has a header
+    pub feature_names: Option<Vec<String>>,
+
+    /// The categories of the labels if `one_hot` was set; the label `classes[i]` is encoded as the `i`-th unit vector
+    pub classes: Option<Vec<String>>,
+}
+
+impl Default for NeuraCsvLoader {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            has_header: true,
+            label_column: LabelColumn::Last,
+            one_hot: false,
+        }
+    }
+}
+
+impl NeuraCsvLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Whether or not the first row of the file is a header; defaults to `true`
+    pub fn header(mut self, has_header: bool) -> Self {
+        self.has_header = has_header;
+        self
+    }
+
+    /// Sets the index of the label column
+    pub fn label_column(mut self, index: usize) -> Self {
+        self.label_column = LabelColumn::Index(index);
+        self
+    }
+
+    /// Sets the name of the label column, as found in the header
+    pub fn label_column_name(mut self, name: impl Into<String>) -> Self {
+        self.label_column = LabelColumn::Name(name.into());
+        self
+    }
+
+    /// Whether or not to one-hot encode the labels; defaults to `false`.
+    ///
+    /// The categories are sorted, numerically if they are all numbers and lexicographically otherwise.
+    pub fn one_hot(mut self, one_hot: bool) -> Self {
+        self.one_hot = one_hot;
+        self
+    }
+
+    pub fn load<F: Float + Scalar>(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<NeuraCsvDataset<F>, NeuraLoadErr> {
+        self.read(File::open(path)?)
+    }
+
+    pub fn read<F: Float + Scalar>(
+        &self,
+        reader: impl Read,
+    ) -> Result<NeuraCsvDataset<F>, NeuraLoadErr> {
+        let mut reader = ::csv::ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .has_headers(self.has_header)
+            .flexible(true)
+            .trim(::csv::Trim::All)
+            .from_reader(reader);
+
+        let header = if self.has_header {
+            Some(
+                reader
+                    .headers()
+                    .map_err(csv_err)?
+                    .iter()
+                    .map(String::from)
+                    .collect::<Vec<_>>(),
+            )
+        } else {
+            None
+        };
+
+        let mut rows = Vec::new();
+        for record in reader.records() {
+            let record = record.map_err(csv_err)?;
+            let line = record
+                .position()
+                .map(|pos| pos.line() as usize)
+                .unwrap_or(0);
+            rows.push((line, record));
+        }
+
+        let width = match (&header, rows.first()) {
+            (Some(header), _) => header.len(),
+            (None, Some((_, record))) => record.len(),
+            (None, None) => 0,
+        };
+
+        let label_index = match &self.label_column {
+            LabelColumn::Last => width.checked_sub(1),
+            LabelColumn::Index(index) => Some(*index).filter(|&index| index < width),
+            LabelColumn::Name(name) => header
+                .as_ref()
+                .and_then(|header| header.iter().position(|column| column == name)),
+        }
+        .ok_or_else(|| NeuraLoadErr::MissingColumn(format!("{:?}", self.label_column)))?;
+
+        for (line, record) in rows.iter() {
+            if record.len() != width {
+                return Err(NeuraLoadErr::RowLength {
+                    line: *line,
+                    expected: width,
+                    got: record.len(),
+                });
+            }
+        }
+
+        let classes = if self.one_hot {
+            let mut classes: Vec<String> = rows
+                .iter()
+                .map(|(_, record)| record[label_index].to_string())
+                .collect();
+            sort_classes(&mut classes);
+            classes.dedup();
+            Some(classes)
+        } else {
+            None
+        };
+
+        let mut samples = Vec::with_capacity(rows.len());
+        for (line, record) in rows.iter() {
+            let mut features = Vec::with_capacity(width - 1);
+
+            for (column, value) in record.iter().enumerate() {
+                if column != label_index {
+                    features.push(parse_value(value, *line, column)?);
+                }
+            }
+
+            let value = &record[label_index];
+            let label =
+                match &classes {
+                    Some(classes) => {
+                        let index = classes.iter().position(|class| class == value).unwrap();
+                        DVector::from_fn(classes.len(), |i, _| {
+                            if i == index {
+                                F::one()
+                            } else {
+                                F::zero()
+                            }
+                        })
+                    }
+                    None => DVector::from_element(1, parse_value(value, *line, label_index)?),
+                };
+
+            samples.push((DVector::from_vec(features), label));
+        }
+
+        let feature_names = header.map(|mut header| {
+            header.remove(label_index);
+            header
+        });
+
+        Ok(NeuraCsvDataset {
+            samples,
+            feature_names,
+            classes,
+        })
+    }
+}
+
+fn csv_err(err: ::csv::Error) -> NeuraLoadErr {
+    if err.is_io_error() {
+        match err.into_kind() {
+            ::csv::ErrorKind::Io(err) => NeuraLoadErr::from(err),
+            _ => unreachable!(),
+        }
+    } else {
+        NeuraLoadErr::Csv(err.to_string())
+    }
+}
+
+fn parse_value<F: Float>(value: &str, line: usize, column: usize) -> Result<F, NeuraLoadErr> {
+    value
+        .parse::<f64>()
+        .ok()
+        .and_then(F::from)
+        .ok_or_else(|| NeuraLoadErr::Parse {
+            line,
+            column,
+            value: value.to_string(),
+        })
+}
+
+fn sort_classes(classes: &mut [String]) {
+    if classes.iter().all(|class| class.parse::<f64>().is_ok()) {
+        classes.sort_by(|a, b| {
+            let a: f64 = a.parse().unwrap();
+            let b: f64 = b.parse().unwrap();
+            a.total_cmp(&b)
+        });
+    } else {
+        classes.sort();
+    }
+}
+
+impl<F: Scalar> NeuraDataset for NeuraCsvDataset<F> {
+    type Input = DVector<F>;
+    type Target = DVector<F>;
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    #[inline(always)]
+    fn pair(&self, index: usize) -> Option<(DVector<F>, DVector<F>)> {
+        self.samples.get(index).cloned()
+    }
+}
+
+impl<F: Scalar> IntoIterator for NeuraCsvDataset<F> {
+    type Item = (DVector<F>, DVector<F>);
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.samples.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nalgebra::dvector;
+
+    use super::*;
+
+    #[test]
+    fn test_csv_numeric() {
+        let data = "a,b,y\n1,2,3.5\n-1, 0.5 ,0\n";
+        let dataset = NeuraCsvLoader::new().read::<f64>(data.as_bytes()).unwrap();
+
+        assert_eq!(dataset.feature_names, Some(vec!["a".into(), "b".into()]));
+        assert_eq!(dataset.classes, None);
+        assert_eq!(
+            dataset.samples,
+            vec![
+                (dvector![1.0, 2.0], dvector![3.5]),
+                (dvector![-1.0, 0.5], dvector![0.0])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_csv_one_hot() {
+        let data = "setosa;5.1;3.5\nvirginica;6.3;2.9\nsetosa;4.9;3.0\nversicolor;5.5;2.3\n";
+        let dataset = NeuraCsvLoader::new()
+            .header(false)
+            .delimiter(b';')
+            .label_column(0)
+            .one_hot(true)
+            .read::<f32>(data.as_bytes())
+            .unwrap();
+
+        assert_eq!(
+            dataset.classes,
+            Some(vec![
+                "setosa".into(),
+                "versicolor".into(),
+                "virginica".into()
+            ])
+        );
+        assert_eq!(dataset.len(), 4);
+        assert_eq!(
+            dataset.pair(1),
+            Some((dvector![6.3, 2.9], dvector![0.0, 0.0, 1.0]))
+        );
+    }
+
+    #[test]
+    fn test_csv_errors() {
+        let loader = NeuraCsvLoader::new();
+
+        assert!(matches!(
+            loader.read::<f32>("a,b\n1,x\n".as_bytes()),
+            Err(NeuraLoadErr::Parse {
+                line: 2,
+                column: 1,
+                ..
+            })
+        ));
+        assert!(matches!(
+            loader.read::<f32>("a,b\n1,2,3\n".as_bytes()),
+            Err(NeuraLoadErr::RowLength {
+                line: 2,
+                expected: 2,
+                got: 3
+            })
+        ));
+        assert!(matches!(
+            loader
+                .clone()
+                .label_column_name("c")
+                .read::<f32>("a,b\n1,2\n".as_bytes()),
+            Err(NeuraLoadErr::MissingColumn(_))
+        ));
+    }
+}
@@ -0,0 +1,298 @@
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+};
+
+use nalgebra::{DVector, Scalar};
+use num::Float;
+
+use crate::err::NeuraLoadErr;
+
+/// The type of the values stored in an IDX file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NeuraIdxType {
+    U8,
+    I8,
+    I16,
+    I32,
+    F32,
+    F64,
+}
+
+impl NeuraIdxType {
+    fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0x08 => Some(Self::U8),
+            0x09 => Some(Self::I8),
+            0x0B => Some(Self::I16),
+            0x0C => Some(Self::I32),
+            0x0D => Some(Self::F32),
+            0x0E => Some(Self::F64),
+            _ => None,
+        }
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Self::U8 | Self::I8 => 1,
+            Self::I16 => 2,
+            Self::I32 | Self::F32 => 4,
+            Self::F64 => 8,
+        }
+    }
+
+    fn decode(self, bytes: &[u8]) -> f64 {
+        match self {
+            Self::U8 => bytes[0] as f64,
+            Self::I8 => bytes[0] as i8 as f64,
+            Self::I16 => i16::from_be_bytes([bytes[0], bytes[1]]) as f64,
+            Self::I32 => i32::from_be_bytes(bytes.try_into().unwrap()) as f64,
+            Self::F32 => f32::from_be_bytes(bytes.try_into().unwrap()) as f64,
+            Self::F64 => f64::from_be_bytes(bytes.try_into().unwrap()),
+        }
+    }
+}
+
+/// The content of an IDX file, the binary format used by the MNIST and Fashion-MNIST datasets.
+///
+/// The first dimension indexes the items of the file (eg. images), and the remaining dimensions are flattened.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NeuraIdxFile<F> {
+    pub data_type: NeuraIdxType,
+    pub dimensions: Vec<usize>,
+    pub data: Vec<F>,
+}
+
+impl<F: Float + Scalar> NeuraIdxFile<F> {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, NeuraLoadErr> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    pub fn read(mut reader: impl Read) -> Result<Self, NeuraLoadErr> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+
+        if magic[0] != 0 || magic[1] != 0 {
+            return Err(NeuraLoadErr::InvalidMagic(u32::from_be_bytes(magic)));
+        }
+        let data_type =
+            NeuraIdxType::from_code(magic[2]).ok_or(NeuraLoadErr::InvalidDataType(magic[2]))?;
+
+        let mut dimensions = Vec::with_capacity(magic[3] as usize);
+        for _ in 0..magic[3] {
+            let mut dimension = [0u8; 4];
+            reader.read_exact(&mut dimension)?;
+            dimensions.push(u32::from_be_bytes(dimension) as usize);
+        }
+
+        let byte_length = dimensions
+            .iter()
+            .try_fold(1usize, |length, &dimension| length.checked_mul(dimension))
+            .and_then(|length| length.checked_mul(data_type.size()))
+            .ok_or_else(|| NeuraLoadErr::InvalidDimensions(dimensions.clone()))?;
+
+        // The buffer grows as the data is read, so that a corrupted header cannot trigger a huge allocation
+        let mut bytes = Vec::new();
+        reader.take(byte_length as u64).read_to_end(&mut bytes)?;
+        if bytes.len() != byte_length {
+            return Err(NeuraLoadErr::UnexpectedEof);
+        }
+
+        let data = bytes
+            .chunks_exact(data_type.size())
+            .map(|chunk| F::from(data_type.decode(chunk)).unwrap())
+            .collect();
+
+        Ok(Self {
+            data_type,
+            dimensions,
+            data,
+        })
+    }
+
+    /// Returns the number of items in the file
+    pub fn len(&self) -> usize {
+        self.dimensions.first().copied().unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns each item of the file as a flattened vector
+    pub fn into_vectors(self) -> Vec<DVector<F>> {
+        let item_size: usize = self.dimensions.iter().skip(1).product();
+
+        if item_size == 0 {
+            return vec![DVector::zeros(0); self.len()];
+        }
+
+        self.data
+            .chunks_exact(item_size)
+            .map(DVector::from_column_slice)
+            .collect()
+    }
+}
+
+/// A labelled dataset, as returned by `load_idx`
+pub type NeuraIdxDataset<F> = Vec<(DVector<F>, DVector<F>)>;
+
+/// Loads a labelled dataset from a pair of IDX files, like the ones of MNIST:
+/// - the inputs are flattened, and if they are stored as unsigned bytes, then they are scaled down to `[0; 1]`
+/// - the labels are one-hot encoded, with as many categories as the largest label plus one;
+///   labels that aren't non-negative integers are rejected
+pub fn load_idx<F: Float + Scalar>(
+    inputs_path: impl AsRef<Path>,
+    labels_path: impl AsRef<Path>,
+) -> Result<NeuraIdxDataset<F>, NeuraLoadErr> {
+    idx_dataset(
+        NeuraIdxFile::load(inputs_path)?,
+        NeuraIdxFile::load(labels_path)?,
+    )
+}
+
+/// Combines an IDX file of inputs and an IDX file of labels into a dataset, see `load_idx`.
+pub fn idx_dataset<F: Float + Scalar>(
+    inputs: NeuraIdxFile<F>,
+    labels: NeuraIdxFile<F>,
+) -> Result<NeuraIdxDataset<F>, NeuraLoadErr> {
+    if inputs.len() != labels.len() {
+        return Err(NeuraLoadErr::LengthMismatch {
+            inputs: inputs.len(),
+            labels: labels.len(),
+        });
+    }
+
+    let scale = if inputs.data_type == NeuraIdxType::U8 {
+        F::from(255.0).unwrap()
+    } else {
+        F::one()
+    };
+
+    let labels = labels
+        .data
+        .iter()
+        .enumerate()
+        .map(|(index, &label)| {
+            if label >= F::zero() && label.fract() == F::zero() {
+                label.to_usize().ok_or(NeuraLoadErr::InvalidLabel(index))
+            } else {
+                Err(NeuraLoadErr::InvalidLabel(index))
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let categories = labels.iter().max().map(|max| max + 1).unwrap_or(0);
+
+    let labels = labels.into_iter().map(|label| {
+        DVector::from_fn(
+            categories,
+            |i, _| {
+                if i == label {
+                    F::one()
+                } else {
+                    F::zero()
+                }
+            },
+        )
+    });
+
+    Ok(inputs
+        .into_vectors()
+        .into_iter()
+        .map(|input| input.map(|x| x / scale))
+        .zip(labels)
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use nalgebra::dvector;
+
+    use super::*;
+
+    fn idx_bytes(data_type: u8, dimensions: &[u32], data: &[u8]) -> Vec<u8> {
+        let mut res = vec![0, 0, data_type, dimensions.len() as u8];
+        for dimension in dimensions {
+            res.extend_from_slice(&dimension.to_be_bytes());
+        }
+        res.extend_from_slice(data);
+        res
+    }
+
+    #[test]
+    fn test_idx_read() {
+        let bytes = idx_bytes(0x08, &[2, 2, 2], &[0, 51, 102, 255, 1, 2, 3, 4]);
+        let file = NeuraIdxFile::<f64>::read(bytes.as_slice()).unwrap();
+
+        assert_eq!(file.data_type, NeuraIdxType::U8);
+        assert_eq!(file.dimensions, vec![2, 2, 2]);
+        assert_eq!(file.len(), 2);
+
+        let mut data = Vec::new();
+        for x in [1.5f32, -2.0] {
+            data.extend_from_slice(&x.to_be_bytes());
+        }
+        let file = NeuraIdxFile::<f64>::read(idx_bytes(0x0D, &[2], &data).as_slice()).unwrap();
+        assert_eq!(file.data, vec![1.5, -2.0]);
+
+        assert!(matches!(
+            NeuraIdxFile::<f64>::read(idx_bytes(0x08, &[3], &[1, 2]).as_slice()),
+            Err(NeuraLoadErr::UnexpectedEof)
+        ));
+        assert!(matches!(
+            NeuraIdxFile::<f64>::read(idx_bytes(0x07, &[1], &[1]).as_slice()),
+            Err(NeuraLoadErr::InvalidDataType(0x07))
+        ));
+        assert!(matches!(
+            NeuraIdxFile::<f64>::read([0x12, 0x34, 0x08, 0x01].as_slice()),
+            Err(NeuraLoadErr::InvalidMagic(0x12340801))
+        ));
+    }
+
+    #[test]
+    fn test_idx_invalid_dimensions() {
+        // The header alone must not cause an overflow or a huge allocation
+        let dimensions = [u32::MAX; 4];
+        assert!(matches!(
+            NeuraIdxFile::<f64>::read(idx_bytes(0x0E, &dimensions, &[]).as_slice()),
+            Err(NeuraLoadErr::InvalidDimensions(_))
+        ));
+
+        assert!(matches!(
+            NeuraIdxFile::<f64>::read(idx_bytes(0x08, &[u32::MAX, u32::MAX], &[1, 2]).as_slice()),
+            Err(NeuraLoadErr::UnexpectedEof)
+        ));
+    }
+
+    #[test]
+    fn test_idx_dataset() {
+        let inputs = idx_bytes(0x08, &[2, 2, 2], &[0, 51, 102, 255, 255, 0, 0, 0]);
+        let labels = idx_bytes(0x08, &[2], &[2, 0]);
+
+        let dataset = idx_dataset::<f64>(
+            NeuraIdxFile::read(inputs.as_slice()).unwrap(),
+            NeuraIdxFile::read(labels.as_slice()).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            dataset,
+            vec![
+                (dvector![0.0, 0.2, 0.4, 1.0], dvector![0.0, 0.0, 1.0]),
+                (dvector![1.0, 0.0, 0.0, 0.0], dvector![1.0, 0.0, 0.0]),
+            ]
+        );
+
+        // Negative labels are rejected rather than turned into zero vectors
+        let labels = idx_bytes(0x09, &[2], &[1, (-1i8) as u8]);
+        assert!(matches!(
+            idx_dataset::<f64>(
+                NeuraIdxFile::read(inputs.as_slice()).unwrap(),
+                NeuraIdxFile::read(labels.as_slice()).unwrap(),
+            ),
+            Err(NeuraLoadErr::InvalidLabel(1))
+        ));
+    }
+}
@@ -0,0 +1,350 @@
+//! Datasets of `(input, target)` pairs, with support for shuffling, batching and splitting,
+//! and loaders for the CSV and IDX file formats.
+
+use std::{collections::HashMap, hash::Hash};
+
+use rand::{seq::SliceRandom, Rng};
+
+mod csv;
+pub use self::csv::*;
+
+mod idx;
+pub use idx::*;
+
+/// A finite, indexable collection of `(input, target)` pairs.
+///
+/// This trait is implemented for slices, arrays and vectors of pairs, and for `NeuraSubset`,
+/// which is returned when splitting a dataset.
+///
+/// Shuffling uses the random number generator passed as argument;
+/// use `rng::NeuraRng` together with `rng::seed` or any seeded generator for reproducible epochs.
+pub trait NeuraDataset {
+    type Input;
+    type Target;
+
+    /// Returns the number of pairs in the dataset
+    fn len(&self) -> usize;
+
+    #[inline(always)]
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a copy of the pair at `index`, or `None` if `index >= self.len()`
+    fn pair(&self, index: usize) -> Option<(Self::Input, Self::Target)>;
+
+    /// Iterates over the dataset, in order
+    fn pairs(&self) -> NeuraDatasetIter<'_, Self> {
+        NeuraDatasetIter::new(self, (0..self.len()).collect())
+    }
+
+    /// Iterates over the dataset once, in an order shuffled with `rng`
+    fn epoch(&self, rng: &mut impl Rng) -> NeuraDatasetIter<'_, Self> {
+        NeuraDatasetIter::new(self, shuffled_indices(self.len(), rng))
+    }
+
+    /// Iterates over the dataset once, in an order shuffled with `rng`, grouping the pairs in batches of `batch_size`.
+    /// The last batch may be smaller than `batch_size`.
+    fn batches(&self, batch_size: usize, rng: &mut impl Rng) -> NeuraBatches<'_, Self> {
+        assert!(batch_size > 0, "batch_size must be greater than zero");
+
+        NeuraBatches {
+            iter: self.epoch(rng),
+            batch_size,
+        }
+    }
+
+    /// Returns a view of the dataset containing only the pairs at `indices`
+    fn subset(&self, indices: Vec<usize>) -> NeuraSubset<'_, Self> {
+        assert!(
+            indices.iter().all(|&index| index < self.len()),
+            "Subset indices out of bounds"
+        );
+
+        NeuraSubset {
+            dataset: self,
+            indices,
+        }
+    }
+
+    /// Randomly splits the dataset into a training set, containing `ratio` of the pairs, and a validation set.
+    fn random_split(
+        &self,
+        ratio: f64,
+        rng: &mut impl Rng,
+    ) -> (NeuraSubset<'_, Self>, NeuraSubset<'_, Self>) {
+        assert!((0.0..=1.0).contains(&ratio), "ratio must be within [0; 1]");
+
+        let mut indices = shuffled_indices(self.len(), rng);
+        let validation = indices.split_off((self.len() as f64 * ratio).round() as usize);
+
+        (self.subset(indices), self.subset(validation))
+    }
+
+    /// Randomly splits the dataset into a training set and a validation set,
+    /// such that each class (as returned by `class`) appears in both with the same proportions.
+    /// Within each class, `ratio` of the pairs are put in the training set.
+    fn stratified_split<Class: Eq + Hash>(
+        &self,
+        ratio: f64,
+        class: impl Fn(&Self::Target) -> Class,
+        rng: &mut impl Rng,
+    ) -> (NeuraSubset<'_, Self>, NeuraSubset<'_, Self>) {
+        assert!((0.0..=1.0).contains(&ratio), "ratio must be within [0; 1]");
+
+        // Group the indices by class, keeping the classes in order of first appearance
+        let mut class_indices: HashMap<Class, usize> = HashMap::new();
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+
+        for (index, (_, target)) in self.pairs().enumerate() {
+            let group = *class_indices.entry(class(&target)).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[group].push(index);
+        }
+
+        let mut training = Vec::with_capacity(self.len());
+        let mut validation = Vec::with_capacity(self.len());
+
+        for mut group in groups {
+            group.shuffle(rng);
+            let split_index = (group.len() as f64 * ratio).round() as usize;
+            validation.extend_from_slice(&group[split_index..]);
+            group.truncate(split_index);
+            training.append(&mut group);
+        }
+
+        training.shuffle(rng);
+        validation.shuffle(rng);
+
+        (self.subset(training), self.subset(validation))
+    }
+
+    /// Collects the dataset into a vector of pairs
+    fn to_pairs(&self) -> Vec<(Self::Input, Self::Target)> {
+        self.pairs().collect()
+    }
+}
+
+fn shuffled_indices(length: usize, rng: &mut impl Rng) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..length).collect();
+    indices.shuffle(rng);
+    indices
+}
+
+impl<Input: Clone, Target: Clone> NeuraDataset for [(Input, Target)] {
+    type Input = Input;
+    type Target = Target;
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        <[(Input, Target)]>::len(self)
+    }
+
+    #[inline(always)]
+    fn pair(&self, index: usize) -> Option<(Input, Target)> {
+        <[(Input, Target)]>::get(self, index).cloned()
+    }
+}
+
+impl<Input: Clone, Target: Clone, const N: usize> NeuraDataset for [(Input, Target); N] {
+    type Input = Input;
+    type Target = Target;
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        N
+    }
+
+    #[inline(always)]
+    fn pair(&self, index: usize) -> Option<(Input, Target)> {
+        self.as_slice().get(index).cloned()
+    }
+}
+
+impl<Input: Clone, Target: Clone> NeuraDataset for Vec<(Input, Target)> {
+    type Input = Input;
+    type Target = Target;
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    #[inline(always)]
+    fn pair(&self, index: usize) -> Option<(Input, Target)> {
+        self.as_slice().get(index).cloned()
+    }
+}
+
+impl<D: NeuraDataset + ?Sized> NeuraDataset for &D {
+    type Input = D::Input;
+    type Target = D::Target;
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+
+    #[inline(always)]
+    fn pair(&self, index: usize) -> Option<(D::Input, D::Target)> {
+        (**self).pair(index)
+    }
+}
+
+/// A view over a subset of a dataset, see `NeuraDataset::subset`.
+#[derive(Debug)]
+pub struct NeuraSubset<'a, D: ?Sized> {
+    dataset: &'a D,
+    indices: Vec<usize>,
+}
+
+impl<'a, D: ?Sized> Clone for NeuraSubset<'a, D> {
+    fn clone(&self) -> Self {
+        Self {
+            dataset: self.dataset,
+            indices: self.indices.clone(),
+        }
+    }
+}
+
+impl<'a, D: ?Sized> NeuraSubset<'a, D> {
+    /// Returns the indices of the pairs of this subset, within the original dataset
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+}
+
+impl<'a, D: NeuraDataset + ?Sized> NeuraDataset for NeuraSubset<'a, D> {
+    type Input = D::Input;
+    type Target = D::Target;
+
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    #[inline(always)]
+    fn pair(&self, index: usize) -> Option<(D::Input, D::Target)> {
+        self.indices
+            .get(index)
+            .and_then(|&index| self.dataset.pair(index))
+    }
+}
+
+/// An iterator over the pairs of a dataset, see `NeuraDataset::pairs` and `NeuraDataset::epoch`.
+pub struct NeuraDatasetIter<'a, D: ?Sized> {
+    dataset: &'a D,
+    indices: Vec<usize>,
+    position: usize,
+}
+
+impl<'a, D: ?Sized> NeuraDatasetIter<'a, D> {
+    fn new(dataset: &'a D, indices: Vec<usize>) -> Self {
+        Self {
+            dataset,
+            indices,
+            position: 0,
+        }
+    }
+}
+
+impl<'a, D: NeuraDataset + ?Sized> Iterator for NeuraDatasetIter<'a, D> {
+    type Item = (D::Input, D::Target);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = *self.indices.get(self.position)?;
+        self.position += 1;
+
+        self.dataset.pair(index)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.indices.len() - self.position;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, D: NeuraDataset + ?Sized> ExactSizeIterator for NeuraDatasetIter<'a, D> {}
+
+/// An iterator over the batches of a dataset, see `NeuraDataset::batches`.
+pub struct NeuraBatches<'a, D: ?Sized> {
+    iter: NeuraDatasetIter<'a, D>,
+    batch_size: usize,
+}
+
+impl<'a, D: NeuraDataset + ?Sized> Iterator for NeuraBatches<'a, D> {
+    type Item = Vec<(D::Input, D::Target)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let batch: Vec<_> = (&mut self.iter).take(self.batch_size).collect();
+
+        if batch.is_empty() {
+            None
+        } else {
+            Some(batch)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn dataset() -> Vec<(usize, usize)> {
+        (0..100).map(|x| (x, x % 4)).collect()
+    }
+
+    #[test]
+    fn test_batches() {
+        let dataset = dataset();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let batches: Vec<_> = dataset.batches(30, &mut rng).collect();
+        assert_eq!(
+            batches.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![30, 30, 30, 10]
+        );
+
+        let mut inputs: Vec<_> = batches.into_iter().flatten().map(|(x, _)| x).collect();
+        assert_ne!(inputs, (0..100).collect::<Vec<_>>());
+        inputs.sort();
+        assert_eq!(inputs, (0..100).collect::<Vec<_>>());
+
+        // The same seed yields the same epochs
+        let first: Vec<_> = dataset.epoch(&mut StdRng::seed_from_u64(1)).collect();
+        let second: Vec<_> = dataset.epoch(&mut StdRng::seed_from_u64(1)).collect();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_split() {
+        let dataset = dataset();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let (training, validation) = dataset.random_split(0.8, &mut rng);
+        assert_eq!(training.len(), 80);
+        assert_eq!(validation.len(), 20);
+
+        let mut inputs: Vec<_> = training
+            .pairs()
+            .chain(validation.pairs())
+            .map(|(x, _)| x)
+            .collect();
+        inputs.sort();
+        assert_eq!(inputs, (0..100).collect::<Vec<_>>());
+
+        let (training, validation) = dataset.stratified_split(0.8, |&class| class, &mut rng);
+        for class in 0..4 {
+            assert_eq!(training.pairs().filter(|(_, c)| *c == class).count(), 20);
+            assert_eq!(validation.pairs().filter(|(_, c)| *c == class).count(), 5);
+        }
+
+        // Subsets can be split further
+        let (training, _) = training.random_split(0.5, &mut rng);
+        assert_eq!(training.len(), 40);
+    }
+}
@@ -1,143 +1,149 @@
-#![allow(unused_variables)]
+use num::Float;
 
 use super::NeuraDerivable;
 
-macro_rules! impl_derivable {
-    ( $type_f32:ty, $type_f64:ty, $self:ident, $variable:ident, $eval:expr, $derivate:expr $(; $variance_hint:expr, $bias_hint:expr )? ) => {
-        impl NeuraDerivable<f32> for $type_f32 {
-            #[inline(always)]
-            fn eval($self: &Self, $variable: f32) -> f32 {
-                $eval
-            }
-
-            #[inline(always)]
-            fn derivate($self: &Self, $variable: f32) -> f32 {
-                $derivate
-            }
-
-            $(
-                #[inline(always)]
-                fn variance_hint($self: &Self) -> f64 {
-                    $variance_hint
-                }
-
-                #[inline(always)]
-                fn bias_hint($self: &Self) -> f64 {
-                    $bias_hint
-                }
-            )?
-        }
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Relu;
 
-        impl NeuraDerivable<f64> for $type_f64 {
-            #[inline(always)]
-            fn eval($self: &Self, $variable: f64) -> f64 {
-                $eval
-            }
-
-            #[inline(always)]
-            fn derivate($self: &Self, $variable: f64) -> f64 {
-                $derivate
-            }
-
-            $(
-                #[inline(always)]
-                fn variance_hint($self: &Self) -> f64 {
-                    $variance_hint
-                }
-
-                #[inline(always)]
-                fn bias_hint($self: &Self) -> f64 {
-                    $bias_hint
-                }
-            )?
-        }
-    };
+impl<F: Float> NeuraDerivable<F> for Relu {
+    #[inline(always)]
+    fn eval(&self, x: F) -> F {
+        x.max(F::zero())
+    }
 
-    ( $type:ty, $variable:ident, $eval:expr, $derivate:expr $(; $variance_hint:expr, $bias_hint:expr )? ) => {
-        impl_derivable!($type, $type, self, $variable, $eval, $derivate $(; $variance_hint, $bias_hint)?);
-    };
-}
+    #[inline(always)]
+    fn derivate(&self, x: F) -> F {
+        if x > F::zero() {
+            F::one()
+        } else {
+            F::zero()
+        }
+    }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub struct Relu;
+    #[inline(always)]
+    fn variance_hint(&self) -> f64 {
+        2.0
+    }
 
-impl_derivable!(Relu, x, x.max(0.0), {
-    if x > 0.0 {
-        1.0
-    } else {
-        0.0
+    #[inline(always)]
+    fn bias_hint(&self) -> f64 {
+        0.1
     }
-}; 2.0, 0.1);
+}
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct LeakyRelu<F>(pub F);
 
-impl_derivable!(
-    LeakyRelu<f32>,
-    LeakyRelu<f64>,
-    self,
-    x,
-    {
-        if x > 0.0 {
+impl<F: Float> NeuraDerivable<F> for LeakyRelu<F> {
+    #[inline(always)]
+    fn eval(&self, x: F) -> F {
+        if x > F::zero() {
             x
         } else {
             self.0 * x
         }
-    },
-    {
-        if x > 0.0 {
-            1.0
+    }
+
+    #[inline(always)]
+    fn derivate(&self, x: F) -> F {
+        if x > F::zero() {
+            F::one()
         } else {
             self.0
         }
-    };
-    2.0, 0.1
-);
+    }
+
+    #[inline(always)]
+    fn variance_hint(&self) -> f64 {
+        2.0
+    }
+
+    #[inline(always)]
+    fn bias_hint(&self) -> f64 {
+        0.1
+    }
+}
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Tanh;
 
-impl_derivable!(Tanh, x, x.tanh(), {
-    let y = x.tanh();
-    1.0 - y * y
-});
+impl<F: Float> NeuraDerivable<F> for Tanh {
+    #[inline(always)]
+    fn eval(&self, x: F) -> F {
+        x.tanh()
+    }
+
+    #[inline(always)]
+    fn derivate(&self, x: F) -> F {
+        let y = x.tanh();
+        F::one() - y * y
+    }
+}
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Linear;
 
-impl_derivable!(Linear, x, x, 1.0);
+impl<F: Float> NeuraDerivable<F> for Linear {
+    #[inline(always)]
+    fn eval(&self, x: F) -> F {
+        x
+    }
+
+    #[inline(always)]
+    fn derivate(&self, _x: F) -> F {
+        F::one()
+    }
+}
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Logistic;
 
-impl_derivable!(Logistic, x, {
-    if x < 0.0 {
-        let x2 = x.exp();
-        x2 / (1.0 + x2)
-    } else {
-        1.0 / (1.0 + (-x).exp())
+impl<F: Float> NeuraDerivable<F> for Logistic {
+    #[inline(always)]
+    fn eval(&self, x: F) -> F {
+        if x < F::zero() {
+            let x2 = x.exp();
+            x2 / (F::one() + x2)
+        } else {
+            F::one() / (F::one() + (-x).exp())
+        }
     }
-}, {
-    if x.abs() > 50.0 {
+
+    #[inline(always)]
+    fn derivate(&self, x: F) -> F {
+        if x.abs() > F::from(50.0).unwrap() {
+            F::zero()
+        } else {
+            let y = self.eval(x);
+            y * (F::one() - y)
+        }
+    }
+
+    #[inline(always)]
+    fn variance_hint(&self) -> f64 {
+        3.2 // ~= pi^2 / 3
+    }
+
+    #[inline(always)]
+    fn bias_hint(&self) -> f64 {
         0.0
-    } else {
-        let y = Logistic.eval(x);
-        y * (1.0 - y)
     }
-}; 3.2, 0.0); // 3.2 ~= pi^2 / 3
+}
 
+/// The swish activation function, defined as `x * Act(x)`; `Swish(Logistic)` corresponds to the SiLU function.
 #[derive(Clone, Copy, Debug, PartialEq)]
-pub struct Swish<F>(pub F);
+pub struct Swish<Act>(pub Act);
 
-impl<F: NeuraDerivable<f32>> NeuraDerivable<f32> for Swish<F> {
-    fn eval(&self, input: f32) -> f32 {
+impl<F: Float, Act: NeuraDerivable<F>> NeuraDerivable<F> for Swish<Act> {
+    #[inline(always)]
+    fn eval(&self, input: F) -> F {
         input * self.0.eval(input)
     }
 
-    fn derivate(&self, at: f32) -> f32 {
-        let result = self.0.eval(at);
-        let swish_result = at * result;
-        swish_result + result * (1.0 - swish_result)
+    #[inline(always)]
+    fn derivate(&self, at: F) -> F {
+        // d/dx (x * Act(x)) = Act(x) + x * Act'(x)
+        self.0.eval(at) + at * self.0.derivate(at)
     }
 
     fn bias_hint(&self) -> f64 {
@@ -148,3 +154,128 @@ impl<F: NeuraDerivable<f32>> NeuraDerivable<f32> for Swish<F> {
         self.0.variance_hint()
     }
 }
+
+/// Multiplies the output of an activation function by a constant factor, ie. `factor * Act(x)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Scaled<F, Act>(pub F, pub Act);
+
+impl<F: Float, Act: NeuraDerivable<F>> NeuraDerivable<F> for Scaled<F, Act> {
+    #[inline(always)]
+    fn eval(&self, input: F) -> F {
+        self.0 * self.1.eval(input)
+    }
+
+    #[inline(always)]
+    fn derivate(&self, at: F) -> F {
+        self.0 * self.1.derivate(at)
+    }
+
+    fn bias_hint(&self) -> f64 {
+        self.1.bias_hint()
+    }
+
+    fn variance_hint(&self) -> f64 {
+        self.1.variance_hint()
+    }
+}
+
+/// The sum of two activation functions, ie. `Left(x) + Right(x)`.
+///
+/// The initialization hints are the average of both activation functions' hints.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sum<Left, Right>(pub Left, pub Right);
+
+impl<F: Float, Left: NeuraDerivable<F>, Right: NeuraDerivable<F>> NeuraDerivable<F>
+    for Sum<Left, Right>
+{
+    #[inline(always)]
+    fn eval(&self, input: F) -> F {
+        self.0.eval(input) + self.1.eval(input)
+    }
+
+    #[inline(always)]
+    fn derivate(&self, at: F) -> F {
+        self.0.derivate(at) + self.1.derivate(at)
+    }
+
+    fn bias_hint(&self) -> f64 {
+        (self.0.bias_hint() + self.1.bias_hint()) / 2.0
+    }
+
+    fn variance_hint(&self) -> f64 {
+        (self.0.variance_hint() + self.1.variance_hint()) / 2.0
+    }
+}
+
+/// The product of two activation functions, ie. `Left(x) * Right(x)`.
+///
+/// The initialization hints are the average of both activation functions' hints.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Product<Left, Right>(pub Left, pub Right);
+
+impl<F: Float, Left: NeuraDerivable<F>, Right: NeuraDerivable<F>> NeuraDerivable<F>
+    for Product<Left, Right>
+{
+    #[inline(always)]
+    fn eval(&self, input: F) -> F {
+        self.0.eval(input) * self.1.eval(input)
+    }
+
+    #[inline(always)]
+    fn derivate(&self, at: F) -> F {
+        self.0.derivate(at) * self.1.eval(at) + self.0.eval(at) * self.1.derivate(at)
+    }
+
+    fn bias_hint(&self) -> f64 {
+        (self.0.bias_hint() + self.1.bias_hint()) / 2.0
+    }
+
+    fn variance_hint(&self) -> f64 {
+        (self.0.variance_hint() + self.1.variance_hint()) / 2.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nalgebra::dvector;
+
+    use super::*;
+    use crate::{assert_approx, prelude::*};
+
+    fn check_derivative<Act: NeuraDerivable<f64>>(activation: Act) {
+        const H: f64 = 0.000001;
+
+        for x in [-2.3, -0.7, 0.4, 1.1, 3.5] {
+            let expected = (activation.eval(x + H) - activation.eval(x - H)) / (2.0 * H);
+            assert_approx!(expected, activation.derivate(x), 0.0001);
+        }
+    }
+
+    #[test]
+    fn test_composite_derivatives() {
+        check_derivative(Swish(Logistic));
+        check_derivative(Swish(Tanh));
+        check_derivative(Scaled(1.7, Tanh));
+        check_derivative(Sum(Tanh, Linear));
+        check_derivative(Product(Logistic, Tanh));
+        check_derivative(Product(Swish(Logistic), Scaled(0.5, Tanh)));
+    }
+
+    #[test]
+    fn test_swish_f32_f64() {
+        let x32: f32 = Swish(Logistic).eval(1.5);
+        let x64: f64 = Swish(Logistic).eval(1.5);
+
+        assert_approx!(x32 as f64, x64, 0.00001);
+        assert_approx!(x64, 1.5 / (1.0 + (-1.5f64).exp()), 0.00001);
+
+        let network = neura_sequential![
+            neura_layer!("dense", 3, f64).activation(Swish(Logistic)),
+            neura_layer!("dense", 1, f64).activation(Scaled(2.0, Sum(Tanh, Linear))),
+        ]
+        .construct(NeuraShape::Vector(2))
+        .unwrap();
+
+        assert!(network.eval(&dvector![0.5, -0.5])[0].is_finite());
+    }
+}
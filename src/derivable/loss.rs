@@ -1,7 +1,9 @@
 use nalgebra::DVector;
+use ndarray::{Array, Dimension};
 use num::Float;
 
 use super::NeuraLoss;
+use crate::algebra::NeuraTensor;
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Euclidean;
@@ -40,6 +42,63 @@ impl<F: Float + std::fmt::Debug + 'static> NeuraLoss<DVector<F>> for Euclidean {
     }
 }
 
+impl<F: Float + std::fmt::Debug + 'static, D: Dimension> NeuraLoss<Array<F, D>> for Euclidean {
+    type Target = Array<F, D>;
+    type Output = F;
+
+    #[inline]
+    fn eval(&self, target: &Array<F, D>, actual: &Array<F, D>) -> F {
+        assert_eq!(target.shape(), actual.shape());
+
+        let sum_squared = target
+            .iter()
+            .zip(actual.iter())
+            .fold(F::zero(), |sum, (&target, &actual)| {
+                sum + (target - actual) * (target - actual)
+            });
+
+        sum_squared * F::from(0.5).unwrap()
+    }
+
+    #[inline]
+    fn nabla(&self, target: &Array<F, D>, actual: &Array<F, D>) -> Array<F, D> {
+        assert_eq!(
+            target.shape(),
+            actual.shape(),
+            "target value differs in shape with network output"
+        );
+
+        // ∂E(y)/∂yᵢ = yᵢ - yᵢ'
+        let mut res = actual.clone();
+        res.zip_mut_with(target, |actual, &target| *actual = *actual - target);
+
+        res
+    }
+}
+
+impl<F: Float + std::fmt::Debug + 'static> NeuraLoss<NeuraTensor<F>> for Euclidean {
+    type Target = NeuraTensor<F>;
+    type Output = F;
+
+    #[inline]
+    fn eval(&self, target: &NeuraTensor<F>, actual: &NeuraTensor<F>) -> F {
+        actual.assert_shape(target.shape());
+
+        self.eval(target.as_vector(), actual.as_vector())
+    }
+
+    #[inline]
+    fn nabla(&self, target: &NeuraTensor<F>, actual: &NeuraTensor<F>) -> NeuraTensor<F> {
+        actual.assert_shape(target.shape());
+
+        NeuraTensor::from_vector(
+            self.nabla(target.as_vector(), actual.as_vector()),
+            actual.shape(),
+        )
+        .unwrap()
+    }
+}
+
 /// The cross-entropy loss function, defined as `L(y, ŷ) = -Σᵢ(yᵢ*ln(ŷᵢ))`.
 ///
 /// This version of the cross-entropy function does not make assumptions about the target vector being one-hot encoded.
@@ -95,3 +154,36 @@ impl<F: Float + std::fmt::Debug + 'static> NeuraLoss<DVector<F>> for CrossEntrop
 
 // TODO: a one-hot encoded, CrossEntropy + Softmax loss function?
 // It would be a lot more efficient than the current method
+
+/// Multiplies the gradient of `Loss` by `scale`, leaving its value untouched.
+///
+/// This is used for mixed-precision training (see `NeuraMixedPrecisionDense`): small gradients would otherwise
+/// underflow when backpropagated in half precision. The layers are then responsible for dividing their gradient by `scale`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LossScaling<Loss> {
+    pub loss: Loss,
+    pub scale: f64,
+}
+
+impl<Loss> LossScaling<Loss> {
+    pub fn new(loss: Loss, scale: f64) -> Self {
+        Self { loss, scale }
+    }
+}
+
+impl<F: Float + std::fmt::Debug + 'static, Loss: NeuraLoss<DVector<F>>> NeuraLoss<DVector<F>>
+    for LossScaling<Loss>
+{
+    type Target = Loss::Target;
+    type Output = Loss::Output;
+
+    fn eval(&self, target: &Self::Target, actual: &DVector<F>) -> Self::Output {
+        self.loss.eval(target, actual)
+    }
+
+    fn nabla(&self, target: &Self::Target, actual: &DVector<F>) -> DVector<F> {
+        let scale = F::from(self.scale).unwrap();
+
+        self.loss.nabla(target, actual).map(|x| x * scale)
+    }
+}
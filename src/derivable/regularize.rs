@@ -132,3 +132,68 @@ impl NeuraDerivable<f32> for NeuraElastic<f32> {
         NeuraL1(self.l1).derivate(at) + NeuraL2(self.l2).derivate(at)
     }
 }
+
+/// Implements the regularization functions for the types of the `half` crate
+#[cfg(feature = "half")]
+macro_rules! half_regularization {
+    ( $type:ty ) => {
+        impl NeuraDerivable<$type> for NeuraL0 {
+            #[inline(always)]
+            fn eval(&self, _input: $type) -> $type {
+                <$type>::ZERO
+            }
+
+            #[inline(always)]
+            fn derivate(&self, _at: $type) -> $type {
+                <$type>::ZERO
+            }
+        }
+
+        impl NeuraDerivable<$type> for NeuraL1<$type> {
+            #[inline(always)]
+            fn eval(&self, input: $type) -> $type {
+                self.0 * num::Float::abs(input)
+            }
+
+            #[inline(always)]
+            fn derivate(&self, at: $type) -> $type {
+                if at > <$type>::ZERO {
+                    self.0
+                } else if at < <$type>::ZERO {
+                    -self.0
+                } else {
+                    <$type>::ZERO
+                }
+            }
+        }
+
+        impl NeuraDerivable<$type> for NeuraL2<$type> {
+            #[inline(always)]
+            fn eval(&self, input: $type) -> $type {
+                (<$type>::from_f32(0.5) * self.0) * (input * input)
+            }
+
+            #[inline(always)]
+            fn derivate(&self, at: $type) -> $type {
+                self.0 * at
+            }
+        }
+
+        impl NeuraDerivable<$type> for NeuraElastic<$type> {
+            #[inline(always)]
+            fn eval(&self, input: $type) -> $type {
+                NeuraL1(self.l1).eval(input) + NeuraL2(self.l2).eval(input)
+            }
+
+            #[inline(always)]
+            fn derivate(&self, at: $type) -> $type {
+                NeuraL1(self.l1).derivate(at) + NeuraL2(self.l2).derivate(at)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "half")]
+half_regularization!(half::f16);
+#[cfg(feature = "half")]
+half_regularization!(half::bf16);
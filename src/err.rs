@@ -72,6 +72,20 @@ pub enum NeuraAxisErr {
     NoInput,
     ConflictingShape(NeuraShape, NeuraShape),
     InvalidAmount(usize, usize, Option<usize>),
+    /// The axis along which the inputs should be combined is out of bounds for the given shape
+    InvalidAxis(usize, NeuraShape),
+}
+
+/// Error type returned by the checked conversions of `NeuraTensor`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NeuraTensorErr {
+    /// The number of entries does not match the size of the shape
+    SizeMismatch { shape: NeuraShape, len: usize },
+    /// The tensor does not have the expected shape
+    ShapeMismatch {
+        expected: NeuraShape,
+        got: NeuraShape,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -100,3 +114,50 @@ pub enum NeuraGraphErr {
     LayerErr(String),
     Cyclic,
 }
+
+/// Error type returned by the dataset loaders, see `dataset::csv` and `dataset::idx`
+#[derive(Debug)]
+pub enum NeuraLoadErr {
+    Io(std::io::Error),
+    /// The file could not be decoded as a CSV file
+    Csv(String),
+    /// A value could not be parsed as a number
+    Parse {
+        line: usize,
+        column: usize,
+        value: String,
+    },
+    /// A row has a different number of columns than the first row
+    RowLength {
+        line: usize,
+        expected: usize,
+        got: usize,
+    },
+    /// The label column could not be found
+    MissingColumn(String),
+    /// The magic number of an IDX file is invalid
+    InvalidMagic(u32),
+    /// The data type of an IDX file is unknown
+    InvalidDataType(u8),
+    /// The dimensions of an IDX file describe more data than can be addressed
+    InvalidDimensions(Vec<usize>),
+    /// The file ended before all of the data was read
+    UnexpectedEof,
+    /// The number of inputs and the number of labels differ
+    LengthMismatch {
+        inputs: usize,
+        labels: usize,
+    },
+    /// The label at the given index is not a non-negative integer
+    InvalidLabel(usize),
+}
+
+impl From<std::io::Error> for NeuraLoadErr {
+    fn from(err: std::io::Error) -> Self {
+        if err.kind() == std::io::ErrorKind::UnexpectedEof {
+            Self::UnexpectedEof
+        } else {
+            Self::Io(err)
+        }
+    }
+}
@@ -0,0 +1,396 @@
+use num::ToPrimitive;
+
+use crate::{algebra::NeuraVectorSpace, derivable::NeuraLoss, layer::*, network::*};
+
+/// The default step used for the finite differences, in `gradient_check` and `gradient_check_layer`.
+pub const DEFAULT_STEP: f64 = 1e-6;
+
+/// The result of a gradient check, see `gradient_check`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NeuraGradientCheck {
+    /// One entry per layer, in the order in which the layers are traversed by the network.
+    pub layers: Vec<NeuraGradientCheckEntry>,
+}
+
+/// The result of a gradient check for a single layer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NeuraGradientCheckEntry {
+    /// The type name of the layer
+    pub layer: &'static str,
+    /// The number of parameters of the layer that were perturbed
+    pub parameters: usize,
+    /// The maximum relative error between `get_gradient` and the finite-difference gradient of the parameters
+    pub parameter_error: f64,
+    /// The maximum relative error between `backprop_layer` and the finite-difference gradient of the input
+    pub input_error: f64,
+}
+
+impl NeuraGradientCheck {
+    /// Returns the maximum relative error across all layers, for both the parameters and the inputs.
+    pub fn max_error(&self) -> f64 {
+        self.layers
+            .iter()
+            .map(NeuraGradientCheckEntry::max_error)
+            .fold(0.0, f64::max)
+    }
+}
+
+impl NeuraGradientCheckEntry {
+    pub fn max_error(&self) -> f64 {
+        self.parameter_error.max(self.input_error)
+    }
+}
+
+/// Compares the gradients computed by each layer of `network` against gradients obtained by finite differences.
+///
+/// Each parameter of each layer (as visited by `NeuraVectorSpace::map_entries`) and each entry of each layer's input
+/// is perturbed by `±step`, and the resulting change in the loss is compared to what `get_gradient` and `backprop_layer` return.
+/// Parameters are perturbed with `NeuraLayerBase::offset_parameters`, so pruned weights, norm constraints
+/// and learning rate multipliers don't affect the result.
+/// Nodes without a layer, like the end of a `NeuraSequential`, are not included in the report.
+///
+/// The check is best done on `f64` networks, as the finite differences are too imprecise with `f32`.
+/// Stochastic layers, like `NeuraDropoutLayer`, should be put in evaluation mode beforehand.
+/// `NeuraMixedPrecisionDense` divides its gradients by its `loss_scale`, so it should be checked with a `loss_scale` of 1.
+///
+/// ```
+/// use neuramethyst::{prelude::*, derivable::loss::Euclidean, gradient_solver::gradient_check};
+/// use nalgebra::dvector;
+///
+/// let network = neura_sequential![
+///     neura_layer!("dense", 4, f64),
+///     neura_layer!("normalize"),
+///     neura_layer!("dense", 2, f64),
+/// ]
+/// .construct(NeuraShape::Vector(3))
+/// .unwrap();
+///
+/// let check = gradient_check(&network, &dvector![0.2, -0.4, 0.9], &dvector![1.0, 0.0], Euclidean, 1e-6);
+/// assert!(check.max_error() < 1e-4, "{:#?}", check);
+/// ```
+pub fn gradient_check<Input, Network, Loss>(
+    network: &Network,
+    input: &Input,
+    target: &Loss::Target,
+    loss: Loss,
+    step: f64,
+) -> NeuraGradientCheck
+where
+    Network: NeuraLayer<Input>,
+    Loss: NeuraLoss<Network::Output>,
+    for<'a> (&'a Loss, &'a Loss::Target, f64): GradientCheckRecurse<Input, Network>,
+{
+    let (_, layers) = (&loss, target, step).recurse(network, input);
+
+    NeuraGradientCheck { layers }
+}
+
+/// Compares the gradients computed by `layer` against gradients obtained by finite differences,
+/// using `loss` directly on the output of `layer`. See `gradient_check` for more information.
+pub fn gradient_check_layer<Input, Layer, Loss>(
+    layer: &Layer,
+    input: &Input,
+    target: &Loss::Target,
+    loss: Loss,
+    step: f64,
+) -> NeuraGradientCheck
+where
+    Input: NeuraVectorSpace + Clone,
+    Layer: NeuraLayer<Input>,
+    Loss: NeuraLoss<Layer::Output>,
+    Loss::Output: ToPrimitive,
+{
+    let output = layer.eval(input);
+    let epsilon = loss.nabla(target, &output);
+
+    let entry = check_layer(layer, input, &epsilon, step, |output| {
+        loss.eval(target, output).to_f64().unwrap()
+    });
+
+    NeuraGradientCheck {
+        layers: vec![entry],
+    }
+}
+
+fn check_layer<Input, Layer>(
+    layer: &Layer,
+    input: &Input,
+    epsilon: &Layer::Output,
+    step: f64,
+    loss_at: impl Fn(&Layer::Output) -> f64,
+) -> NeuraGradientCheckEntry
+where
+    Input: NeuraVectorSpace + Clone,
+    Layer: NeuraLayer<Input>,
+{
+    let (_, intermediary) = layer.eval_training(input);
+
+    // Parameters, perturbed through `offset_parameters` so that masks, norm constraints
+    // and learning rate multipliers don't get in the way
+    let gradient = entries(layer.get_gradient(input, &intermediary, epsilon));
+    let mut parameter_error: f64 = 0.0;
+    let mut delta = layer.default_gradient();
+
+    for (index, &expected) in gradient.iter().enumerate() {
+        set_one_hot(&mut delta, index, step);
+        let mut layer_plus = layer.clone();
+        layer_plus.offset_parameters(&delta);
+
+        set_one_hot(&mut delta, index, -step);
+        let mut layer_minus = layer.clone();
+        layer_minus.offset_parameters(&delta);
+
+        let actual =
+            (loss_at(&layer_plus.eval(input)) - loss_at(&layer_minus.eval(input))) / (2.0 * step);
+
+        parameter_error = parameter_error.max(relative_error(expected, actual));
+    }
+
+    // Input
+    let input_gradient = entries(layer.backprop_layer(input, &intermediary, epsilon));
+    let mut input_error: f64 = 0.0;
+    let input_entries = entries(input.clone());
+    let mut input_plus = input.clone();
+    let mut input_minus = input.clone();
+
+    for (index, &expected) in input_gradient.iter().enumerate() {
+        write_offset(&mut input_plus, &input_entries, index, step);
+        write_offset(&mut input_minus, &input_entries, index, -step);
+
+        let actual =
+            (loss_at(&layer.eval(&input_plus)) - loss_at(&layer.eval(&input_minus))) / (2.0 * step);
+
+        input_error = input_error.max(relative_error(expected, actual));
+    }
+
+    NeuraGradientCheckEntry {
+        layer: std::any::type_name::<Layer>(),
+        parameters: gradient.len(),
+        parameter_error,
+        input_error,
+    }
+}
+
+fn entries<V: NeuraVectorSpace>(mut vector: V) -> Vec<f64> {
+    let mut res = Vec::new();
+
+    vector.map_entries(&mut |x| {
+        res.push(x);
+        x
+    });
+
+    res
+}
+
+/// Sets the entry at `index` of `vector` to `value` and every other entry to zero, in a single pass
+fn set_one_hot<V: NeuraVectorSpace>(vector: &mut V, index: usize, value: f64) {
+    let mut current = 0;
+
+    vector.map_entries(&mut |_| {
+        current += 1;
+        if current - 1 == index {
+            value
+        } else {
+            0.0
+        }
+    });
+}
+
+/// Writes `values` into the entries of `vector`, adding `offset` to the entry at `index`, in a single pass
+fn write_offset<V: NeuraVectorSpace>(vector: &mut V, values: &[f64], index: usize, offset: f64) {
+    let mut current = 0;
+
+    vector.map_entries(&mut |_| {
+        current += 1;
+        if current - 1 == index {
+            values[current - 1] + offset
+        } else {
+            values[current - 1]
+        }
+    });
+}
+
+fn relative_error(expected: f64, actual: f64) -> f64 {
+    (expected - actual).abs() / expected.abs().max(actual.abs()).max(1e-8)
+}
+
+/// Recursively traverses a network, checking each layer's gradient along the way.
+/// Returns the outgoing epsilon value and the list of checked layers.
+pub trait GradientCheckRecurse<Input, Network> {
+    fn recurse(&self, network: &Network, input: &Input) -> (Input, Vec<NeuraGradientCheckEntry>);
+}
+
+impl<Input, Loss: NeuraLoss<Input>> GradientCheckRecurse<Input, ()>
+    for (&Loss, &Loss::Target, f64)
+{
+    fn recurse(&self, _network: &(), input: &Input) -> (Input, Vec<NeuraGradientCheckEntry>) {
+        (self.0.nabla(self.1, input), Vec::new())
+    }
+}
+
+impl<
+        Input: Clone,
+        Network: NeuraNetworkRec + NeuraNetwork<Input> + NeuraLayer<Input>,
+        Loss,
+        Target,
+    > GradientCheckRecurse<Input, Network> for (&Loss, &Target, f64)
+where
+    // Verify that we can traverse recursively
+    for<'a> (&'a Loss, &'a Target, f64):
+        GradientCheckRecurse<Network::NodeOutput, Network::NextNode>,
+    // Verify that the current layer implements the right traits
+    Network::Layer: NeuraLayer<Network::LayerInput>,
+    <Network::Layer as NeuraLayer<Network::LayerInput>>::Output: Clone,
+    Network::LayerInput: NeuraVectorSpace,
+    Network::NextNode: NeuraLayer<Network::NodeOutput>,
+    // Verify that the loss can be computed from the output of the next node
+    Loss:
+        NeuraLoss<<Network::NextNode as NeuraLayer<Network::NodeOutput>>::Output, Target = Target>,
+    Loss::Output: ToPrimitive,
+{
+    fn recurse(&self, network: &Network, input: &Input) -> (Input, Vec<NeuraGradientCheckEntry>) {
+        let (loss, target, step) = *self;
+        let layer = network.get_layer();
+
+        let layer_input = network.map_input(input);
+        let layer_output = layer.eval(layer_input.as_ref());
+        let output = network.map_output(input, &layer_output);
+
+        // Recurse
+        let (epsilon_in, mut entries) = self.recurse(network.get_next(), output.as_ref());
+
+        let layer_epsilon_in = network.map_gradient_in(input, &epsilon_in);
+        let (_, layer_intermediary) = layer.eval_training(layer_input.as_ref());
+        let layer_epsilon_out =
+            layer.backprop_layer(&layer_input, &layer_intermediary, &layer_epsilon_in);
+        let epsilon_out = network.map_gradient_out(input, &epsilon_in, &layer_epsilon_out);
+
+        if std::any::TypeId::of::<Network::Layer>() != std::any::TypeId::of::<()>() {
+            let entry = check_layer(
+                layer,
+                layer_input.as_ref(),
+                &layer_epsilon_in,
+                step,
+                |layer_output| {
+                    let output = network.map_output(input, layer_output);
+                    let final_output = network.get_next().eval(output.as_ref());
+                    loss.eval(target, &final_output).to_f64().unwrap()
+                },
+            );
+
+            entries.insert(0, entry);
+        }
+
+        (epsilon_out.into_owned(), entries)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nalgebra::dvector;
+
+    use super::*;
+    use crate::{
+        derivable::{
+            activation::{Logistic, Tanh},
+            loss::Euclidean,
+        },
+        layer::prune::NeuraPrunable,
+        prelude::*,
+        utils::uniform_vector,
+    };
+
+    #[test]
+    fn test_gradient_check_sequential() {
+        let network = neura_sequential![
+            neura_layer!("dense", 5, f64).activation(Tanh),
+            neura_layer!("dense", 4, f64).activation(Logistic),
+            neura_layer!("softmax"),
+        ]
+        .construct(NeuraShape::Vector(3))
+        .unwrap();
+
+        let check = gradient_check(
+            &network,
+            &uniform_vector(3),
+            &dvector![0.0, 1.0, 0.0, 0.0],
+            Euclidean,
+            DEFAULT_STEP,
+        );
+
+        assert_eq!(check.layers.len(), 3);
+        assert_eq!(check.layers[0].parameters, 5 * 3 + 5);
+        assert_eq!(check.layers[1].parameters, 4 * 5 + 4);
+        assert_eq!(check.layers[2].parameters, 0);
+        assert!(check.max_error() < 1e-4, "{:#?}", check);
+    }
+
+    #[test]
+    fn test_gradient_check_normalize() {
+        let layer = neura_layer!("normalize")
+            .construct(NeuraShape::Vector(4))
+            .unwrap();
+
+        let check = gradient_check_layer(
+            &layer,
+            &dvector![0.3, -1.2, 0.7, 2.1],
+            &dvector![1.0, 0.0, 0.5, -0.5],
+            Euclidean,
+            DEFAULT_STEP,
+        );
+
+        assert!(check.max_error() < 1e-4, "{:#?}", check);
+    }
+
+    #[test]
+    fn test_gradient_check_constrained_layers() {
+        let mut pruned = neura_layer!("dense", 4, f64)
+            .max_norm(0.5)
+            .activation(Tanh)
+            .construct(NeuraShape::Vector(3))
+            .unwrap();
+        pruned.prune_below(0.2);
+
+        let network = neura_sequential![
+            pruned,
+            neura_layer!("dense", 2, f64)
+                .construct(NeuraShape::Vector(4))
+                .unwrap()
+                .scale_learning_rate(0.1),
+        ];
+
+        let check = gradient_check(
+            &network,
+            &uniform_vector(3),
+            &dvector![0.5, -0.5],
+            Euclidean,
+            DEFAULT_STEP,
+        );
+
+        assert_eq!(check.layers.len(), 2);
+        assert!(check.max_error() < 1e-4, "{:#?}", check);
+    }
+
+    #[test]
+    fn test_gradient_check_residual() {
+        let network = neura_residual![
+            <= 0, 2;
+            neura_layer!("dense", 4, f64).activation(Tanh);
+            neura_layer!("dense", 3, f64).activation(Tanh);
+            neura_layer!("dense", 2, f64)
+        ]
+        .construct(NeuraShape::Vector(3))
+        .unwrap();
+
+        let check = gradient_check(
+            &network,
+            &uniform_vector(3),
+            &dvector![0.5, -0.5],
+            Euclidean,
+            DEFAULT_STEP,
+        );
+
+        assert_eq!(check.layers.len(), 3);
+        assert!(check.max_error() < 1e-4, "{:#?}", check);
+    }
+}
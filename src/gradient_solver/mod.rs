@@ -4,6 +4,9 @@ pub use backprop::NeuraBackprop;
 mod forward_forward;
 pub use forward_forward::NeuraForwardForward;
 
+mod gradient_check;
+pub use gradient_check::*;
+
 use crate::layer::NeuraLayerBase;
 
 pub trait NeuraGradientSolver<Input, Target, Trainable: NeuraLayerBase> {
@@ -0,0 +1,48 @@
+use super::*;
+
+/// A layer wrapper that feeds `ndarray` arrays to the wrappee, without copying them into `nalgebra` types.
+/// The gradient of the wrappee stays the same.
+///
+/// Only layers with an `ndarray` implementation can be wrapped: `NeuraDenseLayer` (with `Array1` inputs),
+/// and `NeuraSoftmaxLayer`, `NeuraNormalizeLayer` and `NeuraDropoutLayer` (with arrays of any dimension).
+/// Keeping it a separate type lets the layers themselves only implement `NeuraLayer<DVector<F>>`,
+/// so that their input type can still be inferred.
+#[derive(Clone, Debug)]
+pub struct NeuraArrayLayer<Layer> {
+    layer: Layer,
+}
+
+impl<Layer> NeuraArrayLayer<Layer> {
+    pub fn new(layer: Layer) -> Self {
+        Self { layer }
+    }
+
+    pub fn get(&self) -> &Layer {
+        &self.layer
+    }
+
+    pub fn get_mut(&mut self) -> &mut Layer {
+        &mut self.layer
+    }
+
+    pub fn into_inner(self) -> Layer {
+        self.layer
+    }
+}
+
+impl<Layer: NeuraPartialLayer> NeuraPartialLayer for NeuraArrayLayer<Layer> {
+    type Constructed = NeuraArrayLayer<Layer::Constructed>;
+    type Err = Layer::Err;
+
+    fn construct(self, input_shape: NeuraShape) -> Result<Self::Constructed, Self::Err> {
+        Ok(NeuraArrayLayer {
+            layer: self.layer.construct(input_shape)?,
+        })
+    }
+}
+
+impl<Layer: NeuraLayerBase> NeuraLayerBase for NeuraArrayLayer<Layer> {
+    type Gradient = Layer::Gradient;
+
+    delegate_layer_base!(layer);
+}
@@ -1,25 +1,45 @@
 use std::marker::PhantomData;
 
 use nalgebra::{DMatrix, DVector, Scalar};
+use ndarray::{Array1, ArrayView1, ArrayView2, ShapeBuilder};
 use num::{traits::NumAssignOps, Float};
-use rand::Rng;
+use rand::{Rng, RngCore};
 
 use crate::{derivable::NeuraDerivable, err::NeuraDimensionsMismatch};
 
+use super::array::NeuraArrayLayer;
+use super::init::{Hinted, NeuraBiasInitializer, NeuraInitializer};
+use super::lora::NeuraLoraDense;
+use super::prune::NeuraPrunable;
 use super::*;
 
 #[derive(Clone, Debug)]
 pub struct NeuraDenseLayer<F: Float, Act: NeuraDerivable<F>, Reg: NeuraDerivable<F>> {
     pub weights: DMatrix<F>,
     pub bias: DVector<F>,
-    activation: Act,
-    regularization: Reg,
+    pub(crate) activation: Act,
+    pub(crate) regularization: Reg,
+
+    /// Whether or not `regularization` and weight decay should also be applied to the bias, defaults to `false`
+    pub(crate) regularize_bias: bool,
+
+    /// If set, the weights of each neuron are rescaled after every call to `apply_gradient`,
+    /// such that their L2 norm does not exceed `max_norm`
+    pub(crate) max_norm: Option<f64>,
+
+    /// If set, the weights for which the mask is `false` have been pruned: they are kept at zero by `apply_gradient`,
+    /// see `NeuraPrunable`
+    pub(crate) mask: Option<DMatrix<bool>>,
 }
 
 #[derive(Clone, Debug)]
-pub struct NeuraDenseLayerPartial<F, Act, Reg, R: Rng> {
+pub struct NeuraDenseLayerPartial<F, Act, Reg, R: Rng, Init = Hinted, BiasInit = Hinted> {
     activation: Act,
     regularization: Reg,
+    initializer: Init,
+    bias_initializer: BiasInit,
+    regularize_bias: bool,
+    max_norm: Option<f64>,
     output_size: usize,
     rng: R,
     phantom: PhantomData<F>,
@@ -41,6 +61,9 @@ impl<F: Float + std::fmt::Debug + 'static, Act: NeuraDerivable<F>, Reg: NeuraDer
             bias,
             activation,
             regularization,
+            regularize_bias: false,
+            max_norm: None,
+            mask: None,
         }
     }
 
@@ -52,33 +75,38 @@ impl<F: Float + std::fmt::Debug + 'static, Act: NeuraDerivable<F>, Reg: NeuraDer
         regularization: Reg,
     ) -> Self
     where
+        F: Scalar,
         rand_distr::StandardNormal: rand_distr::Distribution<F>,
     {
-        let stddev = activation.variance_hint() * 2.0 / (input_size as f64 + output_size as f64);
-        let stddev = F::from(stddev).unwrap_or_else(|| {
-            panic!(
-                "Couldn't convert stddev ({}) to type {}",
-                stddev,
-                stringify!(F)
-            );
-        });
-        let bias = F::from(activation.bias_hint()).unwrap_or_else(|| {
-            panic!(
-                "Couldn't convert bias ({}) to type {}",
-                activation.bias_hint(),
-                stringify!(F)
-            );
-        });
-
-        let distribution = rand_distr::Normal::new(F::zero(), stddev)
-            .expect("Couldn't create normal distribution");
-
-        Self {
-            weights: DMatrix::from_distribution(output_size, input_size, &distribution, rng),
-            bias: DVector::from_element(output_size, bias),
+        Self::from_initializer(
+            input_size,
+            output_size,
+            rng,
             activation,
             regularization,
-        }
+            &Hinted,
+            &Hinted,
+        )
+    }
+
+    /// Creates a new layer, whose weights and bias are initialized using `initializer` and `bias_initializer`
+    pub fn from_initializer(
+        input_size: usize,
+        output_size: usize,
+        rng: &mut impl Rng,
+        activation: Act,
+        regularization: Reg,
+        initializer: &impl NeuraInitializer<F>,
+        bias_initializer: &impl NeuraBiasInitializer<F>,
+    ) -> Self
+    where
+        F: Scalar,
+    {
+        let weights =
+            initializer.init_weights(input_size, output_size, activation.variance_hint(), rng);
+        let bias = bias_initializer.init_bias(output_size, activation.bias_hint(), rng);
+
+        Self::new(weights, bias, activation, regularization)
     }
 
     pub fn new_partial<R: Rng>(
@@ -90,6 +118,10 @@ impl<F: Float + std::fmt::Debug + 'static, Act: NeuraDerivable<F>, Reg: NeuraDer
         NeuraDenseLayerPartial {
             activation,
             regularization,
+            initializer: Hinted,
+            bias_initializer: Hinted,
+            regularize_bias: false,
+            max_norm: None,
             output_size,
             rng,
             phantom: PhantomData,
@@ -99,13 +131,89 @@ impl<F: Float + std::fmt::Debug + 'static, Act: NeuraDerivable<F>, Reg: NeuraDer
     pub fn input_len(&self) -> usize {
         self.weights.shape().1
     }
+
+    /// Sets whether or not the regularization and weight decay should also be applied to the bias
+    pub fn regularize_bias(mut self, regularize_bias: bool) -> Self {
+        self.regularize_bias = regularize_bias;
+        self
+    }
+
+    /// Constrains the L2 norm of the weights of each neuron to not exceed `max_norm`
+    pub fn max_norm(mut self, max_norm: f64) -> Self {
+        self.max_norm = Some(max_norm);
+        self
+    }
+
+    /// Freezes the layer and wraps it in a low-rank adapter, see `NeuraLoraDense`
+    pub fn lora(self, rank: usize, alpha: f64, rng: &mut dyn RngCore) -> NeuraLoraDense<F, Act, Reg>
+    where
+        F: Scalar + NumAssignOps,
+        rand_distr::StandardNormal: rand_distr::Distribution<F>,
+    {
+        NeuraLoraDense::new(self, rank, alpha, rng)
+    }
+
+    /// Returns a view of the weights as an `ndarray` matrix, without copying them
+    pub fn weights_view(&self) -> ArrayView2<'_, F> {
+        // nalgebra stores its matrices in column-major order
+        ArrayView2::from_shape(
+            (self.weights.nrows(), self.weights.ncols()).f(),
+            self.weights.as_slice(),
+        )
+        .unwrap()
+    }
+
+    /// Returns the pruning mask of the layer, if any weight has been pruned: pruned weights are marked as `false`
+    pub fn mask(&self) -> Option<&DMatrix<bool>> {
+        self.mask.as_ref()
+    }
+
+    pub(crate) fn apply_mask(&mut self)
+    where
+        F: Scalar,
+    {
+        let Some(mask) = &self.mask else {
+            return;
+        };
+
+        self.weights.zip_apply(mask, |weight, kept| {
+            if !kept {
+                *weight = F::zero();
+            }
+        });
+    }
+
+    fn apply_max_norm(&mut self)
+    where
+        F: Scalar + NumAssignOps,
+    {
+        let Some(max_norm) = self.max_norm else {
+            return;
+        };
+        let max_norm = F::from(max_norm).unwrap();
+
+        for mut row in self.weights.row_iter_mut() {
+            let norm = row.iter().fold(F::zero(), |sum, &x| sum + x * x).sqrt();
+
+            if norm > max_norm {
+                row *= max_norm / norm;
+            }
+        }
+    }
 }
 
-impl<F, Act, Reg, R: Rng> NeuraDenseLayerPartial<F, Act, Reg, R> {
-    pub fn activation<Act2>(self, activation: Act2) -> NeuraDenseLayerPartial<F, Act2, Reg, R> {
+impl<F, Act, Reg, R: Rng, Init, BiasInit> NeuraDenseLayerPartial<F, Act, Reg, R, Init, BiasInit> {
+    pub fn activation<Act2>(
+        self,
+        activation: Act2,
+    ) -> NeuraDenseLayerPartial<F, Act2, Reg, R, Init, BiasInit> {
         NeuraDenseLayerPartial {
             activation,
             regularization: self.regularization,
+            initializer: self.initializer,
+            bias_initializer: self.bias_initializer,
+            regularize_bias: self.regularize_bias,
+            max_norm: self.max_norm,
             output_size: self.output_size,
             rng: self.rng,
             phantom: PhantomData,
@@ -115,15 +223,69 @@ impl<F, Act, Reg, R: Rng> NeuraDenseLayerPartial<F, Act, Reg, R> {
     pub fn regularization<Reg2>(
         self,
         regularization: Reg2,
-    ) -> NeuraDenseLayerPartial<F, Act, Reg2, R> {
+    ) -> NeuraDenseLayerPartial<F, Act, Reg2, R, Init, BiasInit> {
         NeuraDenseLayerPartial {
             activation: self.activation,
             regularization,
+            initializer: self.initializer,
+            bias_initializer: self.bias_initializer,
+            regularize_bias: self.regularize_bias,
+            max_norm: self.max_norm,
+            output_size: self.output_size,
+            rng: self.rng,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Sets the strategy used to initialize the weights of the layer, see the `layer::init` module.
+    /// Defaults to `init::Hinted`.
+    pub fn initializer<Init2>(
+        self,
+        initializer: Init2,
+    ) -> NeuraDenseLayerPartial<F, Act, Reg, R, Init2, BiasInit> {
+        NeuraDenseLayerPartial {
+            activation: self.activation,
+            regularization: self.regularization,
+            initializer,
+            bias_initializer: self.bias_initializer,
+            regularize_bias: self.regularize_bias,
+            max_norm: self.max_norm,
             output_size: self.output_size,
             rng: self.rng,
             phantom: PhantomData,
         }
     }
+
+    /// Sets the strategy used to initialize the bias of the layer, see the `layer::init` module.
+    /// Defaults to `init::Hinted`.
+    pub fn bias_initializer<BiasInit2>(
+        self,
+        bias_initializer: BiasInit2,
+    ) -> NeuraDenseLayerPartial<F, Act, Reg, R, Init, BiasInit2> {
+        NeuraDenseLayerPartial {
+            activation: self.activation,
+            regularization: self.regularization,
+            initializer: self.initializer,
+            bias_initializer,
+            regularize_bias: self.regularize_bias,
+            max_norm: self.max_norm,
+            output_size: self.output_size,
+            rng: self.rng,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Sets whether or not the regularization and weight decay should also be applied to the bias
+    pub fn regularize_bias(mut self, regularize_bias: bool) -> Self {
+        self.regularize_bias = regularize_bias;
+        self
+    }
+
+    /// Constrains the L2 norm of the weights of each neuron to not exceed `max_norm`
+    pub fn max_norm(mut self, max_norm: f64) -> Self {
+        self.max_norm = Some(max_norm);
+        self
+    }
 }
 
 impl<
@@ -131,22 +293,28 @@ impl<
         Act: NeuraDerivable<F> + Clone + std::fmt::Debug + 'static,
         Reg: NeuraDerivable<F> + Clone + std::fmt::Debug + 'static,
         R: Rng,
-    > NeuraPartialLayer for NeuraDenseLayerPartial<F, Act, Reg, R>
-where
-    rand_distr::StandardNormal: rand_distr::Distribution<F>,
+        Init: NeuraInitializer<F>,
+        BiasInit: NeuraBiasInitializer<F>,
+    > NeuraPartialLayer for NeuraDenseLayerPartial<F, Act, Reg, R, Init, BiasInit>
 {
     type Constructed = NeuraDenseLayer<F, Act, Reg>;
     type Err = ();
 
     fn construct(self, input_shape: NeuraShape) -> Result<Self::Constructed, Self::Err> {
         let mut rng = self.rng;
-        Ok(NeuraDenseLayer::from_rng(
+        let mut layer = NeuraDenseLayer::from_initializer(
             input_shape.size(),
             self.output_size,
             &mut rng,
             self.activation,
             self.regularization,
-        ))
+            &self.initializer,
+            &self.bias_initializer,
+        );
+        layer.regularize_bias = self.regularize_bias;
+        layer.max_norm = self.max_norm;
+
+        Ok(layer)
     }
 }
 
@@ -187,16 +355,73 @@ where
     fn apply_gradient(&mut self, gradient: &Self::Gradient) {
         self.weights += &gradient.0;
         self.bias += &gradient.1;
+        self.apply_mask();
+        self.apply_max_norm();
+    }
+
+    fn offset_parameters(&mut self, offset: &Self::Gradient) {
+        self.weights += &offset.0;
+        self.bias += &offset.1;
+    }
+
+    fn decay_layer(&mut self, factor: f64) {
+        let multiplier = F::one() - F::from(factor).unwrap();
+
+        self.weights *= multiplier;
+        if self.regularize_bias {
+            self.bias *= multiplier;
+        }
     }
 
     fn output_shape(&self) -> NeuraShape {
         NeuraShape::Vector(self.weights.shape().0)
     }
 
+    fn accept_visitor(&self, input_shapes: &[NeuraShape], visitor: &mut dyn NeuraLayerVisitor) {
+        // The input shape of a dense layer can be deduced from its weights
+        let input_shape = [NeuraShape::Vector(self.weights.ncols())];
+        let input_shapes = if input_shapes.is_empty() {
+            &input_shape
+        } else {
+            input_shapes
+        };
+
+        visitor.visit(self, &NeuraVisitContext::new(input_shapes));
+    }
+
+    fn accept_visitor_mut(
+        &mut self,
+        input_shapes: &[NeuraShape],
+        visitor: &mut dyn NeuraLayerVisitorMut,
+    ) {
+        let input_shape = [NeuraShape::Vector(self.weights.ncols())];
+        let input_shapes = if input_shapes.is_empty() {
+            &input_shape
+        } else {
+            input_shapes
+        };
+
+        visitor.visit_mut(self, &NeuraVisitContext::new(input_shapes));
+    }
+
+    fn prunable(&self) -> Option<&dyn NeuraPrunable> {
+        Some(self)
+    }
+
+    fn prunable_mut(&mut self) -> Option<&mut dyn NeuraPrunable> {
+        Some(self)
+    }
+
     fn regularize_layer(&self) -> Self::Gradient {
+        let bias_gradient = if self.regularize_bias {
+            self.bias.map(|x| self.regularization.derivate(x))
+        } else {
+            DVector::zeros(self.bias.shape().0)
+        };
+
         (
             self.weights.map(|x| self.regularization.derivate(x)),
-            DVector::zeros(self.bias.shape().0),
+            bias_gradient,
         )
     }
 }
@@ -257,3 +482,61 @@ where
         self.weights.tr_mul(&delta)
     }
 }
+
+/// Allows `Array1` inputs to be fed to the layer through `NeuraArrayLayer`, without converting them to `DVector`.
+/// The gradient of the layer stays the same.
+impl<F: Float + NumAssignOps + Scalar + Send, Act: NeuraDerivable<F>, Reg: NeuraDerivable<F>>
+    NeuraLayer<Array1<F>> for NeuraArrayLayer<NeuraDenseLayer<F, Act, Reg>>
+where
+    NeuraDenseLayer<F, Act, Reg>: Clone + std::fmt::Debug + 'static,
+{
+    type Output = Array1<F>;
+    type IntermediaryRepr = Array1<F>; // pre-activation values
+
+    fn eval_training(&self, input: &Array1<F>) -> (Self::Output, Self::IntermediaryRepr) {
+        let layer = self.get();
+        let mut evaluated = layer.weights_view().dot(input);
+        evaluated.zip_mut_with(&ArrayView1::from(layer.bias.as_slice()), |x, &bias| {
+            *x += bias
+        });
+        let output = evaluated.mapv(|x| layer.activation.eval(x));
+
+        (output, evaluated)
+    }
+
+    fn get_gradient(
+        &self,
+        input: &Array1<F>,
+        evaluated: &Self::IntermediaryRepr,
+        epsilon: &Self::Output,
+    ) -> Self::Gradient {
+        let activation = &self.get().activation;
+        let delta = DVector::from_iterator(
+            epsilon.len(),
+            epsilon
+                .iter()
+                .zip(evaluated.iter())
+                .map(|(&epsilon, &evaluated)| epsilon * activation.derivate(evaluated)),
+        );
+
+        let weights_gradient =
+            DMatrix::from_fn(delta.len(), input.len(), |i, j| delta[i] * input[j]);
+
+        (weights_gradient, delta)
+    }
+
+    fn backprop_layer(
+        &self,
+        _input: &Array1<F>,
+        evaluated: &Self::IntermediaryRepr,
+        epsilon: &Self::Output,
+    ) -> Array1<F> {
+        let layer = self.get();
+        let mut delta = epsilon.clone();
+        delta.zip_mut_with(evaluated, |delta, &evaluated| {
+            *delta *= layer.activation.derivate(evaluated)
+        });
+
+        layer.weights_view().t().dot(&delta)
+    }
+}
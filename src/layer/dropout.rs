@@ -1,5 +1,7 @@
+use super::array::NeuraArrayLayer;
 use super::*;
 use nalgebra::DVector;
+use ndarray::{Array, Dimension};
 use num::Float;
 use rand::Rng;
 
@@ -23,13 +25,29 @@ impl<R: Rng> NeuraDropoutLayer<R> {
         }
     }
 
-    fn apply_dropout<F: Float>(&self, vector: &mut DVector<F>) {
+    /// Applies the dropout mask to `values`, which must be visited in the same order as the entries of a `DVector`.
+    ///
+    /// Panics if there are not as many values as entries in the mask.
+    fn apply_dropout<'a, F: Float + 'a, I>(&self, values: I)
+    where
+        I: IntoIterator<Item = &'a mut F>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let values = values.into_iter();
+        assert_eq!(
+            values.len(),
+            self.mask.len(),
+            "Input of length {} does not match the dropout mask of length {}",
+            values.len(),
+            self.mask.len()
+        );
+
         let multiplier = F::from(self.multiplier).unwrap();
-        for (index, &dropout) in self.mask.iter().enumerate() {
+        for (value, &dropout) in values.zip(self.mask.iter()) {
             if dropout {
-                vector[index] = F::zero();
+                *value = F::zero();
             } else {
-                vector[index] = vector[index] * multiplier;
+                *value = *value * multiplier;
             }
         }
     }
@@ -91,7 +109,7 @@ impl<R: Rng + Clone + std::fmt::Debug + 'static, F: Float> NeuraLayer<DVector<F>
 
     fn eval_training(&self, input: &DVector<F>) -> (Self::Output, Self::IntermediaryRepr) {
         let mut output = input.clone();
-        self.apply_dropout(&mut output);
+        self.apply_dropout(output.iter_mut());
         (output, ())
     }
 
@@ -103,7 +121,34 @@ impl<R: Rng + Clone + std::fmt::Debug + 'static, F: Float> NeuraLayer<DVector<F>
     ) -> DVector<F> {
         let mut epsilon = epsilon.clone();
 
-        self.apply_dropout(&mut epsilon);
+        self.apply_dropout(epsilon.iter_mut());
+
+        epsilon
+    }
+}
+
+impl<R: Rng + Clone + std::fmt::Debug + 'static, F: Float, D: Dimension + 'static>
+    NeuraLayer<Array<F, D>> for NeuraArrayLayer<NeuraDropoutLayer<R>>
+{
+    type Output = Array<F, D>;
+
+    type IntermediaryRepr = ();
+
+    fn eval_training(&self, input: &Array<F, D>) -> (Self::Output, Self::IntermediaryRepr) {
+        let mut output = input.clone();
+        self.get().apply_dropout(output.iter_mut());
+        (output, ())
+    }
+
+    fn backprop_layer(
+        &self,
+        _input: &Array<F, D>,
+        _intermediary: &Self::IntermediaryRepr,
+        epsilon: &Self::Output,
+    ) -> Array<F, D> {
+        let mut epsilon = epsilon.clone();
+
+        self.get().apply_dropout(epsilon.iter_mut());
 
         epsilon
     }
@@ -125,4 +170,14 @@ mod test {
             assert!(!layer.multiplier.is_nan());
         }
     }
+
+    #[test]
+    #[should_panic(expected = "does not match the dropout mask")]
+    fn test_dropout_ndarray_mismatch() {
+        let layer = NeuraDropoutLayer::new(0.5, rand::thread_rng())
+            .construct(NeuraShape::Vector(3))
+            .unwrap();
+
+        NeuraArrayLayer::new(layer).eval(&ndarray::arr1(&[1.0, 2.0, 3.0, 4.0]));
+    }
 }
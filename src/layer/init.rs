@@ -0,0 +1,405 @@
+//! Weight and bias initialization strategies for `NeuraDenseLayer`,
+//! selectable using `NeuraDenseLayerPartial::initializer` and `NeuraDenseLayerPartial::bias_initializer`.
+
+use nalgebra::{DMatrix, DVector, RealField, Scalar};
+use num::Float;
+use rand::RngCore;
+use rand_distr::{Distribution, Normal, StandardNormal, Uniform};
+
+/// Initializes the weights of a layer.
+pub trait NeuraInitializer<F> {
+    /// Should return a matrix with `fan_out` rows and `fan_in` columns.
+    ///
+    /// `variance_hint` is the value returned by `NeuraDerivable::variance_hint` for the layer's activation function.
+    fn init_weights(
+        &self,
+        fan_in: usize,
+        fan_out: usize,
+        variance_hint: f64,
+        rng: &mut dyn RngCore,
+    ) -> DMatrix<F>;
+}
+
+/// Initializes the bias of a layer.
+pub trait NeuraBiasInitializer<F> {
+    /// Should return a vector of length `fan_out`.
+    ///
+    /// `bias_hint` is the value returned by `NeuraDerivable::bias_hint` for the layer's activation function.
+    fn init_bias(&self, fan_out: usize, bias_hint: f64, rng: &mut dyn RngCore) -> DVector<F>;
+}
+
+fn normal<F: Float + Scalar>(
+    fan_in: usize,
+    fan_out: usize,
+    stddev: f64,
+    rng: &mut dyn RngCore,
+) -> DMatrix<F>
+where
+    StandardNormal: Distribution<F>,
+{
+    let distribution = Normal::new(F::zero(), F::from(stddev).unwrap())
+        .expect("Couldn't create normal distribution");
+
+    DMatrix::from_distribution(fan_out, fan_in, &distribution, rng)
+}
+
+fn uniform<F: Float + Scalar + rand_distr::uniform::SampleUniform>(
+    fan_in: usize,
+    fan_out: usize,
+    limit: f64,
+    rng: &mut dyn RngCore,
+) -> DMatrix<F> {
+    let limit = F::from(limit).unwrap();
+    let distribution = Uniform::new_inclusive(-limit, limit);
+
+    DMatrix::from_distribution(fan_out, fan_in, &distribution, rng)
+}
+
+/// The default initializer, which relies on the hints given by the activation function:
+/// weights are drawn from `N(0, variance_hint * 2 / (fan_in + fan_out))`, and the bias is set to `bias_hint`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hinted;
+
+impl<F: Float + Scalar> NeuraInitializer<F> for Hinted
+where
+    StandardNormal: Distribution<F>,
+{
+    fn init_weights(
+        &self,
+        fan_in: usize,
+        fan_out: usize,
+        variance_hint: f64,
+        rng: &mut dyn RngCore,
+    ) -> DMatrix<F> {
+        let variance = variance_hint * 2.0 / (fan_in as f64 + fan_out as f64);
+        normal(fan_in, fan_out, variance.sqrt(), rng)
+    }
+}
+
+impl<F: Float + Scalar> NeuraBiasInitializer<F> for Hinted {
+    fn init_bias(&self, fan_out: usize, bias_hint: f64, _rng: &mut dyn RngCore) -> DVector<F> {
+        let bias = F::from(bias_hint).unwrap_or_else(|| {
+            panic!(
+                "Couldn't convert bias ({}) to type {}",
+                bias_hint,
+                std::any::type_name::<F>()
+            );
+        });
+
+        DVector::from_element(fan_out, bias)
+    }
+}
+
+/// Xavier/Glorot uniform initialization: weights are drawn from `U(-l, l)`, with `l = sqrt(6 / (fan_in + fan_out))`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct XavierUniform;
+
+impl<F: Float + Scalar + rand_distr::uniform::SampleUniform> NeuraInitializer<F> for XavierUniform {
+    fn init_weights(
+        &self,
+        fan_in: usize,
+        fan_out: usize,
+        _variance_hint: f64,
+        rng: &mut dyn RngCore,
+    ) -> DMatrix<F> {
+        let limit = (6.0 / (fan_in as f64 + fan_out as f64)).sqrt();
+        uniform(fan_in, fan_out, limit, rng)
+    }
+}
+
+/// Xavier/Glorot normal initialization: weights are drawn from `N(0, 2 / (fan_in + fan_out))`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct XavierNormal;
+
+impl<F: Float + Scalar> NeuraInitializer<F> for XavierNormal
+where
+    StandardNormal: Distribution<F>,
+{
+    fn init_weights(
+        &self,
+        fan_in: usize,
+        fan_out: usize,
+        _variance_hint: f64,
+        rng: &mut dyn RngCore,
+    ) -> DMatrix<F> {
+        let stddev = (2.0 / (fan_in as f64 + fan_out as f64)).sqrt();
+        normal(fan_in, fan_out, stddev, rng)
+    }
+}
+
+/// He/Kaiming normal initialization, suited for `Relu`-like activations: weights are drawn from `N(0, 2 / fan_in)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HeNormal;
+
+impl<F: Float + Scalar> NeuraInitializer<F> for HeNormal
+where
+    StandardNormal: Distribution<F>,
+{
+    fn init_weights(
+        &self,
+        fan_in: usize,
+        fan_out: usize,
+        _variance_hint: f64,
+        rng: &mut dyn RngCore,
+    ) -> DMatrix<F> {
+        let stddev = (2.0 / fan_in as f64).sqrt();
+        normal(fan_in, fan_out, stddev, rng)
+    }
+}
+
+/// He/Kaiming uniform initialization: weights are drawn from `U(-l, l)`, with `l = sqrt(6 / fan_in)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HeUniform;
+
+impl<F: Float + Scalar + rand_distr::uniform::SampleUniform> NeuraInitializer<F> for HeUniform {
+    fn init_weights(
+        &self,
+        fan_in: usize,
+        fan_out: usize,
+        _variance_hint: f64,
+        rng: &mut dyn RngCore,
+    ) -> DMatrix<F> {
+        let limit = (6.0 / fan_in as f64).sqrt();
+        uniform(fan_in, fan_out, limit, rng)
+    }
+}
+
+/// LeCun normal initialization, suited for `Tanh`-like activations: weights are drawn from `N(0, 1 / fan_in)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LeCunNormal;
+
+impl<F: Float + Scalar> NeuraInitializer<F> for LeCunNormal
+where
+    StandardNormal: Distribution<F>,
+{
+    fn init_weights(
+        &self,
+        fan_in: usize,
+        fan_out: usize,
+        _variance_hint: f64,
+        rng: &mut dyn RngCore,
+    ) -> DMatrix<F> {
+        let stddev = (1.0 / fan_in as f64).sqrt();
+        normal(fan_in, fan_out, stddev, rng)
+    }
+}
+
+/// Orthogonal initialization: the weight matrix is a (semi-)orthogonal matrix multiplied by a gain factor.
+///
+/// If `fan_out <= fan_in`, then the rows of the matrix are orthonormal, otherwise its columns are.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Orthogonal(pub f64);
+
+impl Default for Orthogonal {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+impl<F: Float + RealField> NeuraInitializer<F> for Orthogonal
+where
+    StandardNormal: Distribution<F>,
+{
+    fn init_weights(
+        &self,
+        fan_in: usize,
+        fan_out: usize,
+        _variance_hint: f64,
+        rng: &mut dyn RngCore,
+    ) -> DMatrix<F> {
+        // The QR decomposition is computed on a matrix with at least as many rows as columns
+        let transpose = fan_out < fan_in;
+        let gaussian: DMatrix<F> = if transpose {
+            normal(fan_out, fan_in, 1.0, rng)
+        } else {
+            normal(fan_in, fan_out, 1.0, rng)
+        };
+
+        let qr = gaussian.qr();
+        let mut q = qr.q();
+        let r = qr.r();
+
+        // Make the decomposition unique, so that the resulting distribution is uniform
+        for (j, mut column) in q.column_iter_mut().enumerate() {
+            if r[(j, j)] < F::zero() {
+                column.neg_mut();
+            }
+        }
+
+        q *= <F as num::NumCast>::from(self.0).unwrap();
+
+        if transpose {
+            q.transpose()
+        } else {
+            q
+        }
+    }
+}
+
+/// Sets every weight or bias to the same value.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Constant<F>(pub F);
+
+impl<F: Scalar + Copy> NeuraInitializer<F> for Constant<F> {
+    fn init_weights(
+        &self,
+        fan_in: usize,
+        fan_out: usize,
+        _variance_hint: f64,
+        _rng: &mut dyn RngCore,
+    ) -> DMatrix<F> {
+        DMatrix::from_element(fan_out, fan_in, self.0)
+    }
+}
+
+impl<F: Scalar + Copy> NeuraBiasInitializer<F> for Constant<F> {
+    fn init_bias(&self, fan_out: usize, _bias_hint: f64, _rng: &mut dyn RngCore) -> DVector<F> {
+        DVector::from_element(fan_out, self.0)
+    }
+}
+
+/// Sets every weight or bias to zero.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Zeros;
+
+impl<F: Float + Scalar> NeuraInitializer<F> for Zeros {
+    fn init_weights(
+        &self,
+        fan_in: usize,
+        fan_out: usize,
+        _variance_hint: f64,
+        _rng: &mut dyn RngCore,
+    ) -> DMatrix<F> {
+        DMatrix::from_element(fan_out, fan_in, F::zero())
+    }
+}
+
+impl<F: Float + Scalar> NeuraBiasInitializer<F> for Zeros {
+    fn init_bias(&self, fan_out: usize, _bias_hint: f64, _rng: &mut dyn RngCore) -> DVector<F> {
+        DVector::from_element(fan_out, F::zero())
+    }
+}
+
+/// Initializes the weights or bias using a closure:
+/// - as a weight initializer, the closure is called with `(row, column, rng)` for each weight,
+///   rows ranging from `0` to `fan_out` and columns from `0` to `fan_in`
+/// - as a bias initializer, the closure is called with `(row, rng)` for each bias
+#[derive(Clone, Copy)]
+pub struct Custom<Fun>(pub Fun);
+
+impl<Fun> std::fmt::Debug for Custom<Fun> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Custom({})", std::any::type_name::<Fun>())
+    }
+}
+
+impl<F: Scalar, Fun: Fn(usize, usize, &mut dyn RngCore) -> F> NeuraInitializer<F> for Custom<Fun> {
+    fn init_weights(
+        &self,
+        fan_in: usize,
+        fan_out: usize,
+        _variance_hint: f64,
+        rng: &mut dyn RngCore,
+    ) -> DMatrix<F> {
+        DMatrix::from_fn(fan_out, fan_in, |row, column| (self.0)(row, column, rng))
+    }
+}
+
+impl<F: Scalar, Fun: Fn(usize, &mut dyn RngCore) -> F> NeuraBiasInitializer<F> for Custom<Fun> {
+    fn init_bias(&self, fan_out: usize, _bias_hint: f64, rng: &mut dyn RngCore) -> DVector<F> {
+        DVector::from_fn(fan_out, |row, _| (self.0)(row, rng))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{derivable::activation::Linear, prelude::*};
+
+    #[test]
+    fn test_hinted_variance() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let weights: DMatrix<f64> = Hinted.init_weights(200, 200, 1.0, &mut rng);
+        let variance = weights.iter().map(|x| x * x).sum::<f64>() / weights.len() as f64;
+
+        assert!((variance - 0.005).abs() < 0.0005);
+    }
+
+    #[test]
+    fn test_dense_partial_initializer() {
+        let layer = neura_layer!("dense", 3, f64)
+            .activation(Linear)
+            .initializer(Zeros)
+            .bias_initializer(Constant(0.5))
+            .construct(NeuraShape::Vector(2))
+            .unwrap();
+
+        assert_eq!(layer.weights, DMatrix::zeros(3, 2));
+        assert_eq!(layer.bias, DVector::from_element(3, 0.5));
+
+        let layer = neura_layer!("dense", 4)
+            .initializer(Orthogonal::default())
+            .construct(NeuraShape::Vector(4))
+            .unwrap();
+
+        assert_relative_eq!(
+            &layer.weights * layer.weights.transpose(),
+            DMatrix::identity(4, 4),
+            epsilon = 0.0001
+        );
+    }
+
+    #[test]
+    fn test_orthogonal() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        for (fan_in, fan_out) in [(4, 4), (6, 3), (3, 6)] {
+            let weights: DMatrix<f64> =
+                Orthogonal(2.0).init_weights(fan_in, fan_out, 1.0, &mut rng);
+            assert_eq!(weights.shape(), (fan_out, fan_in));
+
+            let product = if fan_out <= fan_in {
+                &weights * weights.transpose()
+            } else {
+                weights.transpose() * &weights
+            };
+            let size = fan_in.min(fan_out);
+
+            assert_relative_eq!(
+                product,
+                DMatrix::identity(size, size) * 4.0,
+                epsilon = 0.000001
+            );
+        }
+    }
+
+    #[test]
+    fn test_uniform_bounds() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let weights: DMatrix<f32> = XavierUniform.init_weights(10, 14, 1.0, &mut rng);
+        let limit = (6.0f32 / 24.0).sqrt();
+
+        assert_eq!(weights.shape(), (14, 10));
+        assert!(weights.iter().all(|x| x.abs() <= limit));
+
+        let weights: DMatrix<f32> = HeUniform.init_weights(6, 2, 1.0, &mut rng);
+        assert!(weights.iter().all(|x| x.abs() <= 1.0));
+    }
+
+    #[test]
+    fn test_custom() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let weights: DMatrix<f64> =
+            Custom(|row, column, _: &mut dyn RngCore| (row * 10 + column) as f64)
+                .init_weights(3, 2, 1.0, &mut rng);
+        assert_eq!(weights, nalgebra::dmatrix![0.0, 1.0, 2.0; 10.0, 11.0, 12.0]);
+
+        let bias: DVector<f64> =
+            Custom(|row, _: &mut dyn RngCore| row as f64).init_bias(3, 0.0, &mut rng);
+        assert_eq!(bias, nalgebra::dvector![0.0, 1.0, 2.0]);
+    }
+}
@@ -0,0 +1,129 @@
+use super::*;
+
+/// A layer wrapper that multiplies the learning rate of the wrappee by `multiplier`:
+/// gradients are scaled by `multiplier` in `apply_gradient`, and so is the factor passed to `decay_layer`.
+///
+/// A multiplier of `0.0` behaves like `NeuraLockLayer`, although the gradient of the wrappee is still computed.
+#[derive(Clone, Debug)]
+pub struct NeuraLearningRateLayer<Layer> {
+    layer: Layer,
+    pub multiplier: f64,
+}
+
+impl<Layer> NeuraLearningRateLayer<Layer> {
+    pub fn new(layer: Layer, multiplier: f64) -> Self {
+        Self { layer, multiplier }
+    }
+
+    pub fn get(&self) -> &Layer {
+        &self.layer
+    }
+
+    pub fn get_mut(&mut self) -> &mut Layer {
+        &mut self.layer
+    }
+
+    pub fn into_inner(self) -> Layer {
+        self.layer
+    }
+}
+
+impl<Layer: NeuraPartialLayer> NeuraPartialLayer for NeuraLearningRateLayer<Layer> {
+    type Constructed = NeuraLearningRateLayer<Layer::Constructed>;
+    type Err = Layer::Err;
+
+    fn construct(self, input_shape: NeuraShape) -> Result<Self::Constructed, Self::Err> {
+        Ok(NeuraLearningRateLayer {
+            layer: self.layer.construct(input_shape)?,
+            multiplier: self.multiplier,
+        })
+    }
+}
+
+impl<Layer: NeuraLayerBase> NeuraLayerBase for NeuraLearningRateLayer<Layer> {
+    type Gradient = Layer::Gradient;
+
+    delegate_layer_base!(
+        layer;
+        output_shape,
+        default_gradient,
+        offset_parameters,
+        prepare_layer,
+        regularize_layer,
+        clip_layer_gradient,
+        accept_visitor,
+        accept_visitor_mut,
+        prunable,
+        prunable_mut
+    );
+
+    fn apply_gradient(&mut self, gradient: &Self::Gradient) {
+        let mut scaled = self.layer.default_gradient();
+        scaled.add_assign(gradient);
+        scaled.mul_assign(self.multiplier);
+
+        self.layer.apply_gradient(&scaled);
+    }
+
+    fn decay_layer(&mut self, factor: f64) {
+        self.layer.decay_layer(factor * self.multiplier);
+    }
+}
+
+impl<Input, Layer: NeuraLayer<Input>> NeuraLayer<Input> for NeuraLearningRateLayer<Layer> {
+    type Output = Layer::Output;
+    type IntermediaryRepr = Layer::IntermediaryRepr;
+
+    fn eval(&self, input: &Input) -> Self::Output {
+        self.layer.eval(input)
+    }
+
+    fn eval_training(&self, input: &Input) -> (Self::Output, Self::IntermediaryRepr) {
+        self.layer.eval_training(input)
+    }
+
+    fn get_gradient(
+        &self,
+        input: &Input,
+        intermediary: &Self::IntermediaryRepr,
+        epsilon: &Self::Output,
+    ) -> Self::Gradient {
+        self.layer.get_gradient(input, intermediary, epsilon)
+    }
+
+    fn backprop_layer(
+        &self,
+        input: &Input,
+        intermediary: &Self::IntermediaryRepr,
+        epsilon: &Self::Output,
+    ) -> Input {
+        self.layer.backprop_layer(input, intermediary, epsilon)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::{dmatrix, dvector};
+
+    use super::*;
+    use crate::{
+        derivable::{activation::Linear, regularize::NeuraL0},
+        layer::dense::NeuraDenseLayer,
+    };
+
+    #[test]
+    fn test_learning_rate_layer() {
+        let mut layer = NeuraDenseLayer::new(dmatrix![1.0, 1.0], dvector![0.0], Linear, NeuraL0)
+            .scale_learning_rate(0.1);
+
+        let mut gradient = layer.default_gradient();
+        gradient.0.fill(1.0);
+        gradient.1.fill(2.0);
+        layer.apply_gradient(&gradient);
+
+        assert_relative_eq!(layer.get().weights, dmatrix![1.1, 1.1]);
+        assert_relative_eq!(layer.get().bias, dvector![0.2]);
+        assert_relative_eq!(layer.eval(&dvector![1.0, 2.0]), dvector![3.5]);
+    }
+}
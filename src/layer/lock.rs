@@ -1,4 +1,5 @@
 use super::*;
+use crate::network::{NeuraLockedVisitor, NeuraLockedVisitorMut};
 
 /// A layer wrapper that disables any kind of training for the wrappee:
 /// traits like NeuraTrainableLayerBackprop will still work as-is,
@@ -38,6 +39,20 @@ impl<Layer: NeuraLayerBase> NeuraLayerBase for NeuraLockLayer<Layer> {
     fn prepare_layer(&mut self, is_training: bool) {
         self.layer.prepare_layer(is_training);
     }
+
+    fn accept_visitor(&self, input_shapes: &[NeuraShape], visitor: &mut dyn NeuraLayerVisitor) {
+        self.layer
+            .accept_visitor(input_shapes, &mut NeuraLockedVisitor(visitor));
+    }
+
+    fn accept_visitor_mut(
+        &mut self,
+        input_shapes: &[NeuraShape],
+        visitor: &mut dyn NeuraLayerVisitorMut,
+    ) {
+        self.layer
+            .accept_visitor_mut(input_shapes, &mut NeuraLockedVisitorMut(visitor));
+    }
 }
 
 impl<Input, Layer: NeuraLayer<Input>> NeuraLayer<Input> for NeuraLockLayer<Layer> {
@@ -62,3 +77,161 @@ impl<Input, Layer: NeuraLayer<Input>> NeuraLayer<Input> for NeuraLockLayer<Layer
         self.layer.backprop_layer(input, intermediary, epsilon)
     }
 }
+
+/// A layer wrapper that can be locked and unlocked at runtime, see `NeuraSelectiveLock`.
+///
+/// While locked, the wrappee is still evaluated and backpropagated through,
+/// but its gradient is neither computed nor applied, and it isn't decayed or pruned.
+#[derive(Clone, Debug)]
+pub struct NeuraSelectiveLockLayer<Layer> {
+    layer: Layer,
+    locked: bool,
+}
+
+impl<Layer> NeuraSelectiveLockLayer<Layer> {
+    /// Wraps `layer`, which starts out unlocked
+    pub fn new(layer: Layer) -> Self {
+        Self {
+            layer,
+            locked: false,
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    pub fn set_locked(&mut self, locked: bool) {
+        self.locked = locked;
+    }
+
+    pub fn get(&self) -> &Layer {
+        &self.layer
+    }
+
+    pub fn get_mut(&mut self) -> &mut Layer {
+        &mut self.layer
+    }
+
+    pub fn into_inner(self) -> Layer {
+        self.layer
+    }
+}
+
+impl<Layer: NeuraPartialLayer> NeuraPartialLayer for NeuraSelectiveLockLayer<Layer> {
+    type Constructed = NeuraSelectiveLockLayer<Layer::Constructed>;
+    type Err = Layer::Err;
+
+    fn construct(self, input_shape: NeuraShape) -> Result<Self::Constructed, Self::Err> {
+        Ok(NeuraSelectiveLockLayer {
+            layer: self.layer.construct(input_shape)?,
+            locked: self.locked,
+        })
+    }
+}
+
+impl<Layer: NeuraLayerBase> NeuraLayerBase for NeuraSelectiveLockLayer<Layer> {
+    type Gradient = Layer::Gradient;
+
+    delegate_layer_base!(layer; output_shape, default_gradient, prepare_layer, clip_layer_gradient);
+
+    fn apply_gradient(&mut self, gradient: &Self::Gradient) {
+        if !self.locked {
+            self.layer.apply_gradient(gradient);
+        }
+    }
+
+    fn offset_parameters(&mut self, offset: &Self::Gradient) {
+        if !self.locked {
+            self.layer.offset_parameters(offset);
+        }
+    }
+
+    fn regularize_layer(&self) -> Self::Gradient {
+        if self.locked {
+            self.layer.default_gradient()
+        } else {
+            self.layer.regularize_layer()
+        }
+    }
+
+    fn decay_layer(&mut self, factor: f64) {
+        if !self.locked {
+            self.layer.decay_layer(factor);
+        }
+    }
+
+    fn accept_visitor(&self, input_shapes: &[NeuraShape], visitor: &mut dyn NeuraLayerVisitor) {
+        if self.locked {
+            self.layer
+                .accept_visitor(input_shapes, &mut NeuraLockedVisitor(visitor));
+        } else {
+            self.layer.accept_visitor(input_shapes, visitor);
+        }
+    }
+
+    fn accept_visitor_mut(
+        &mut self,
+        input_shapes: &[NeuraShape],
+        visitor: &mut dyn NeuraLayerVisitorMut,
+    ) {
+        if self.locked {
+            self.layer
+                .accept_visitor_mut(input_shapes, &mut NeuraLockedVisitorMut(visitor));
+        } else {
+            self.layer.accept_visitor_mut(input_shapes, visitor);
+        }
+    }
+
+    fn prunable(&self) -> Option<&dyn prune::NeuraPrunable> {
+        if self.locked {
+            None
+        } else {
+            self.layer.prunable()
+        }
+    }
+
+    fn prunable_mut(&mut self) -> Option<&mut dyn prune::NeuraPrunable> {
+        if self.locked {
+            None
+        } else {
+            self.layer.prunable_mut()
+        }
+    }
+}
+
+impl<Input, Layer: NeuraLayer<Input>> NeuraLayer<Input> for NeuraSelectiveLockLayer<Layer> {
+    type Output = Layer::Output;
+    type IntermediaryRepr = Layer::IntermediaryRepr;
+
+    fn eval(&self, input: &Input) -> Self::Output {
+        self.layer.eval(input)
+    }
+
+    fn eval_training(&self, input: &Input) -> (Self::Output, Self::IntermediaryRepr) {
+        self.layer.eval_training(input)
+    }
+
+    fn get_gradient(
+        &self,
+        input: &Input,
+        intermediary: &Self::IntermediaryRepr,
+        epsilon: &Self::Output,
+    ) -> Self::Gradient {
+        // The gradient of a locked layer would be discarded by `apply_gradient`, so it isn't computed
+        if self.locked {
+            self.layer.default_gradient()
+        } else {
+            self.layer.get_gradient(input, intermediary, epsilon)
+        }
+    }
+
+    fn backprop_layer(
+        &self,
+        input: &Input,
+        intermediary: &Self::IntermediaryRepr,
+        epsilon: &Self::Output,
+    ) -> Input {
+        self.layer.backprop_layer(input, intermediary, epsilon)
+    }
+}
@@ -0,0 +1,426 @@
+use nalgebra::{DMatrix, DVector, Scalar};
+use num::{traits::NumAssignOps, Float};
+use rand::RngCore;
+use rand_distr::{Distribution, StandardNormal};
+
+use crate::{derivable::NeuraDerivable, err::NeuraDimensionsMismatch};
+
+use super::{
+    dense::NeuraDenseLayer,
+    init::{LeCunNormal, NeuraInitializer},
+    *,
+};
+
+/// A low-rank adapter (LoRA) around a pretrained `NeuraDenseLayer`.
+///
+/// The weights and bias of the base layer are frozen, and the layer instead learns an update `B·A` of rank `rank`,
+/// such that it computes `activation((W + alpha / rank · B·A) · x + bias)`.
+/// `A` is initialized randomly and `B` to zero, so that the adapter starts off as an identity.
+/// If the base layer was pruned, its mask is also applied to `B·A`, so that pruned weights stay at zero.
+///
+/// The gradient of the layer only contains the adapter parameters, as `(A, B)`;
+/// once trained, the adapter can be folded back into the base layer with `merge`.
+#[derive(Clone, Debug)]
+pub struct NeuraLoraDense<F: Float, Act: NeuraDerivable<F>, Reg: NeuraDerivable<F>> {
+    base: NeuraDenseLayer<F, Act, Reg>,
+
+    /// The down-projection of the adapter, with `rank` rows and as many columns as inputs
+    pub a: DMatrix<F>,
+
+    /// The up-projection of the adapter, with as many rows as outputs and `rank` columns
+    pub b: DMatrix<F>,
+
+    alpha: f64,
+}
+
+impl<F: Float + Scalar + NumAssignOps, Act: NeuraDerivable<F>, Reg: NeuraDerivable<F>>
+    NeuraLoraDense<F, Act, Reg>
+{
+    /// Wraps `base` in an adapter of rank `rank`, whose update is scaled by `alpha / rank`
+    pub fn new(
+        base: NeuraDenseLayer<F, Act, Reg>,
+        rank: usize,
+        alpha: f64,
+        rng: &mut dyn RngCore,
+    ) -> Self
+    where
+        StandardNormal: Distribution<F>,
+    {
+        assert!(rank > 0, "NeuraLoraDense needs a rank of at least 1");
+
+        let (outputs, inputs) = base.weights.shape();
+        let a = LeCunNormal.init_weights(inputs, rank, 1.0, rng);
+        let b = DMatrix::zeros(outputs, rank);
+
+        Self { base, a, b, alpha }
+    }
+
+    pub fn base(&self) -> &NeuraDenseLayer<F, Act, Reg> {
+        &self.base
+    }
+
+    pub fn rank(&self) -> usize {
+        self.a.nrows()
+    }
+
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    /// The factor by which `B·A` is multiplied, `alpha / rank`
+    pub fn scale(&self) -> F {
+        F::from(self.alpha / self.rank() as f64).unwrap()
+    }
+
+    /// Returns the effective weights of the layer, `W + alpha / rank · B·A`, with `B·A` masked like the base layer
+    pub fn merged_weights(&self) -> DMatrix<F> {
+        let update = self.masked_update().unwrap_or_else(|| &self.b * &self.a);
+
+        &self.base.weights + update * self.scale()
+    }
+
+    /// Folds the adapter into the base layer, returning a plain `NeuraDenseLayer` computing the same function.
+    /// The pruning mask of the base layer is kept.
+    pub fn merge(self) -> NeuraDenseLayer<F, Act, Reg> {
+        let weights = self.merged_weights();
+        let mut base = self.base;
+        base.weights = weights;
+
+        base
+    }
+
+    /// Discards the adapter, returning the base layer untouched
+    pub fn into_base(self) -> NeuraDenseLayer<F, Act, Reg> {
+        self.base
+    }
+
+    /// Returns `B·A` with the pruning mask of the base layer applied, or `None` if the base layer isn't pruned.
+    /// Without a mask, the update is kept factored, to avoid computing `B·A`.
+    fn masked_update(&self) -> Option<DMatrix<F>> {
+        let mask = self.base.mask()?;
+        let mut update = &self.b * &self.a;
+        apply_mask(&mut update, mask);
+
+        Some(update)
+    }
+}
+
+impl<F: Float + Scalar + Send + NumAssignOps, Act: NeuraDerivable<F>, Reg: NeuraDerivable<F>>
+    NeuraPartialLayer for NeuraLoraDense<F, Act, Reg>
+where
+    Self: Clone + std::fmt::Debug + 'static,
+{
+    type Constructed = Self;
+    type Err = NeuraDimensionsMismatch;
+
+    fn construct(self, input_shape: NeuraShape) -> Result<Self::Constructed, Self::Err> {
+        if input_shape.size() != self.a.ncols() {
+            return Err(NeuraDimensionsMismatch {
+                existing: self.a.ncols(),
+                new: input_shape,
+            });
+        }
+
+        Ok(self)
+    }
+}
+
+impl<F: Float + NumAssignOps + Scalar + Send, Act: NeuraDerivable<F>, Reg: NeuraDerivable<F>>
+    NeuraLayerBase for NeuraLoraDense<F, Act, Reg>
+where
+    Self: Clone + std::fmt::Debug + 'static,
+{
+    type Gradient = (DMatrix<F>, DMatrix<F>);
+
+    fn default_gradient(&self) -> Self::Gradient {
+        (
+            DMatrix::zeros(self.a.nrows(), self.a.ncols()),
+            DMatrix::zeros(self.b.nrows(), self.b.ncols()),
+        )
+    }
+
+    fn apply_gradient(&mut self, gradient: &Self::Gradient) {
+        self.a += &gradient.0;
+        self.b += &gradient.1;
+    }
+
+    fn decay_layer(&mut self, factor: f64) {
+        let multiplier = F::one() - F::from(factor).unwrap();
+
+        self.a *= multiplier;
+        self.b *= multiplier;
+    }
+
+    fn output_shape(&self) -> NeuraShape {
+        NeuraShape::Vector(self.b.nrows())
+    }
+
+    fn regularize_layer(&self) -> Self::Gradient {
+        let regularization = &self.base.regularization;
+
+        (
+            self.a.map(|x| regularization.derivate(x)),
+            self.b.map(|x| regularization.derivate(x)),
+        )
+    }
+
+    fn accept_visitor(&self, input_shapes: &[NeuraShape], visitor: &mut dyn NeuraLayerVisitor) {
+        let input_shape = [NeuraShape::Vector(self.a.ncols())];
+        let input_shapes = if input_shapes.is_empty() {
+            &input_shape
+        } else {
+            input_shapes
+        };
+
+        visitor.visit(self, &NeuraVisitContext::new(input_shapes));
+    }
+
+    fn accept_visitor_mut(
+        &mut self,
+        input_shapes: &[NeuraShape],
+        visitor: &mut dyn NeuraLayerVisitorMut,
+    ) {
+        let input_shape = [NeuraShape::Vector(self.a.ncols())];
+        let input_shapes = if input_shapes.is_empty() {
+            &input_shape
+        } else {
+            input_shapes
+        };
+
+        visitor.visit_mut(self, &NeuraVisitContext::new(input_shapes));
+    }
+}
+
+/// Zeroes the entries of `matrix` that are pruned in `mask`
+fn apply_mask<F: Float + Scalar>(matrix: &mut DMatrix<F>, mask: &DMatrix<bool>) {
+    matrix.zip_apply(mask, |x, kept| {
+        if !kept {
+            *x = F::zero();
+        }
+    });
+}
+
+/// The intermediary values of `NeuraLoraDense`
+#[derive(Clone, Debug)]
+pub struct NeuraLoraIntermediary<F: Scalar> {
+    /// The pre-activation values
+    evaluated: DVector<F>,
+    /// The input projected by `A`
+    projected: DVector<F>,
+}
+
+impl<F: Float + NumAssignOps + Scalar + Send, Act: NeuraDerivable<F>, Reg: NeuraDerivable<F>>
+    NeuraLoraDense<F, Act, Reg>
+{
+    fn delta(&self, evaluated: &DVector<F>, epsilon: &DVector<F>) -> DVector<F> {
+        let mut delta = epsilon.clone();
+
+        for i in 0..delta.len() {
+            delta[i] *= self.base.activation.derivate(evaluated[i]);
+        }
+
+        delta
+    }
+}
+
+impl<F: Float + NumAssignOps + Scalar + Send, Act: NeuraDerivable<F>, Reg: NeuraDerivable<F>>
+    NeuraLayer<DVector<F>> for NeuraLoraDense<F, Act, Reg>
+where
+    Self: Clone + std::fmt::Debug + 'static,
+{
+    type Output = DVector<F>;
+    type IntermediaryRepr = NeuraLoraIntermediary<F>;
+
+    fn eval_training(&self, input: &DVector<F>) -> (Self::Output, Self::IntermediaryRepr) {
+        let projected = &self.a * input;
+        let update = match self.masked_update() {
+            Some(update) => update * input,
+            None => &self.b * &projected,
+        };
+        let evaluated = &self.base.weights * input + &self.base.bias + update * self.scale();
+        let output = evaluated.map(|x| self.base.activation.eval(x));
+
+        (
+            output,
+            NeuraLoraIntermediary {
+                evaluated,
+                projected,
+            },
+        )
+    }
+
+    fn get_gradient(
+        &self,
+        input: &DVector<F>,
+        intermediary: &Self::IntermediaryRepr,
+        epsilon: &Self::Output,
+    ) -> Self::Gradient {
+        let delta = self.delta(&intermediary.evaluated, epsilon) * self.scale();
+
+        if let Some(mask) = self.base.mask() {
+            // The gradient of `B·A` is `delta · inputᵀ`, masked like the base layer
+            let mut update_gradient = &delta * input.transpose();
+            apply_mask(&mut update_gradient, mask);
+
+            return (
+                self.b.tr_mul(&update_gradient),
+                update_gradient * self.a.transpose(),
+            );
+        }
+
+        let b_gradient = &delta * intermediary.projected.transpose();
+        let a_gradient = self.b.tr_mul(&delta) * input.transpose();
+
+        (a_gradient, b_gradient)
+    }
+
+    fn backprop_layer(
+        &self,
+        _input: &DVector<F>,
+        intermediary: &Self::IntermediaryRepr,
+        epsilon: &Self::Output,
+    ) -> DVector<F> {
+        let delta = self.delta(&intermediary.evaluated, epsilon);
+        let update = match self.masked_update() {
+            Some(update) => update.tr_mul(&delta),
+            None => self.a.tr_mul(&self.b.tr_mul(&delta)),
+        };
+
+        self.base.weights.tr_mul(&delta) + update * self.scale()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::{dmatrix, dvector};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{
+        derivable::{activation::Tanh, loss::Euclidean, regularize::NeuraL0},
+        gradient_solver::{gradient_check_layer, DEFAULT_STEP},
+        layer::prune::NeuraPrunable,
+        utils::uniform_vector,
+    };
+
+    fn lora() -> NeuraLoraDense<f64, Tanh, NeuraL0> {
+        let base = NeuraDenseLayer::new(
+            dmatrix![0.5, -0.25, 1.0; 0.0, 0.75, -0.5],
+            dvector![0.1, -0.2],
+            Tanh,
+            NeuraL0,
+        );
+        let mut lora = NeuraLoraDense::new(base, 2, 4.0, &mut StdRng::seed_from_u64(0));
+        lora.b = dmatrix![0.3, -0.1; 0.2, 0.4];
+
+        lora
+    }
+
+    #[test]
+    fn test_lora_starts_as_base() {
+        let base = lora().into_base();
+        let lora = NeuraLoraDense::new(base.clone(), 1, 1.0, &mut StdRng::seed_from_u64(0));
+        let input = uniform_vector(3);
+
+        assert_eq!(lora.eval(&input), base.eval(&input));
+        assert_eq!(
+            lora.default_gradient().0.len() + lora.default_gradient().1.len(),
+            3 + 2
+        );
+    }
+
+    #[test]
+    fn test_lora_merge() {
+        let lora = lora();
+        let input = dvector![0.2, -0.4, 0.7];
+
+        let output = lora.eval(&input);
+        let merged = lora.merge();
+
+        assert_relative_eq!(merged.eval(&input), output, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_lora_merge_pruned() {
+        let mut lora = lora();
+        lora.base.prune_below(0.3);
+        let mask = lora.base().mask().cloned();
+        assert!(mask.is_some());
+
+        let input = dvector![0.2, -0.4, 0.7];
+        let output = lora.eval(&input);
+        let merged = lora.merge();
+
+        assert_eq!(merged.mask(), mask.as_ref());
+        assert_eq!(merged.weights[(0, 1)], 0.0);
+        assert_eq!(merged.weights[(1, 0)], 0.0);
+        assert_ne!(merged.weights[(0, 0)], 0.0);
+        assert_relative_eq!(merged.eval(&input), output, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_lora_gradient_pruned() {
+        let mut lora = lora();
+        lora.base.prune_below(0.3);
+
+        let check = gradient_check_layer(
+            &lora,
+            &dvector![0.2, -0.4, 0.7],
+            &dvector![0.5, -0.5],
+            Euclidean,
+            DEFAULT_STEP,
+        );
+
+        assert!(check.max_error() < 1e-4, "{:#?}", check);
+    }
+
+    #[test]
+    fn test_lora_gradient() {
+        // Compare the gradient of `sum(output)` with finite differences
+        let lora = lora();
+        let input = dvector![0.2, -0.4, 0.7];
+        let epsilon = dvector![1.0, 1.0];
+
+        let (_, intermediary) = lora.eval_training(&input);
+        let (a_gradient, b_gradient) = lora.get_gradient(&input, &intermediary, &epsilon);
+        let input_gradient = lora.backprop_layer(&input, &intermediary, &epsilon);
+
+        let h = 1e-6;
+        let finite_difference = |lora: &NeuraLoraDense<f64, Tanh, NeuraL0>,
+                                 input: &DVector<f64>| {
+            lora.eval(input).sum()
+        };
+        let reference = finite_difference(&lora, &input);
+
+        for i in 0..lora.a.len() {
+            let mut shifted = lora.clone();
+            shifted.a[i] += h;
+            assert_relative_eq!(
+                (finite_difference(&shifted, &input) - reference) / h,
+                a_gradient[i],
+                epsilon = 1e-4
+            );
+        }
+
+        for i in 0..lora.b.len() {
+            let mut shifted = lora.clone();
+            shifted.b[i] += h;
+            assert_relative_eq!(
+                (finite_difference(&shifted, &input) - reference) / h,
+                b_gradient[i],
+                epsilon = 1e-4
+            );
+        }
+
+        for i in 0..input.len() {
+            let mut shifted = input.clone();
+            shifted[i] += h;
+            assert_relative_eq!(
+                (finite_difference(&lora, &shifted) - reference) / h,
+                input_gradient[i],
+                epsilon = 1e-4
+            );
+        }
+    }
+}
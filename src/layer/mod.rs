@@ -1,60 +1,285 @@
-use crate::algebra::NeuraVectorSpace;
+use crate::{
+    algebra::NeuraVectorSpace,
+    network::{
+        NeuraLayerInfo, NeuraLayerVisitor, NeuraLayerVisitorMut, NeuraSummary, NeuraVisitContext,
+    },
+};
+
+use self::{
+    learning_rate::NeuraLearningRateLayer,
+    lock::NeuraLockLayer,
+    prune::{sparsity_threshold, NeuraPrunable, NeuraPruneVisitor},
+};
+
+/// Implements methods of `NeuraLayerBase` by forwarding them to the layer stored in `self.$field`,
+/// for layer wrappers sharing the `Gradient` type of their wrappee.
+///
+/// `delegate_layer_base!(layer)` forwards every method, while `delegate_layer_base!(layer; output_shape, ...)`
+/// only forwards the listed ones, leaving the wrapper to implement the others.
+macro_rules! delegate_layer_base {
+    ($field:ident) => {
+        delegate_layer_base!(
+            $field;
+            output_shape,
+            default_gradient,
+            apply_gradient,
+            offset_parameters,
+            prepare_layer,
+            regularize_layer,
+            decay_layer,
+            clip_layer_gradient,
+            accept_visitor,
+            accept_visitor_mut,
+            prunable,
+            prunable_mut
+        );
+    };
+    ($field:ident; $( $method:ident ),+ $(,)?) => {
+        $( delegate_layer_base!(@method $field, $method); )+
+    };
 
-use self::lock::NeuraLockLayer;
+    (@method $field:ident, output_shape) => {
+        fn output_shape(&self) -> $crate::layer::NeuraShape {
+            self.$field.output_shape()
+        }
+    };
+    (@method $field:ident, default_gradient) => {
+        fn default_gradient(&self) -> Self::Gradient {
+            self.$field.default_gradient()
+        }
+    };
+    (@method $field:ident, apply_gradient) => {
+        fn apply_gradient(&mut self, gradient: &Self::Gradient) {
+            self.$field.apply_gradient(gradient);
+        }
+    };
+    (@method $field:ident, offset_parameters) => {
+        fn offset_parameters(&mut self, offset: &Self::Gradient) {
+            self.$field.offset_parameters(offset);
+        }
+    };
+    (@method $field:ident, prepare_layer) => {
+        fn prepare_layer(&mut self, is_training: bool) {
+            self.$field.prepare_layer(is_training);
+        }
+    };
+    (@method $field:ident, regularize_layer) => {
+        fn regularize_layer(&self) -> Self::Gradient {
+            self.$field.regularize_layer()
+        }
+    };
+    (@method $field:ident, decay_layer) => {
+        fn decay_layer(&mut self, factor: f64) {
+            self.$field.decay_layer(factor);
+        }
+    };
+    (@method $field:ident, clip_layer_gradient) => {
+        fn clip_layer_gradient(&self, gradient: &mut Self::Gradient, max_norm: f64) {
+            self.$field.clip_layer_gradient(gradient, max_norm);
+        }
+    };
+    (@method $field:ident, accept_visitor) => {
+        fn accept_visitor(
+            &self,
+            input_shapes: &[$crate::layer::NeuraShape],
+            visitor: &mut dyn $crate::network::NeuraLayerVisitor,
+        ) {
+            self.$field.accept_visitor(input_shapes, visitor);
+        }
+    };
+    (@method $field:ident, accept_visitor_mut) => {
+        fn accept_visitor_mut(
+            &mut self,
+            input_shapes: &[$crate::layer::NeuraShape],
+            visitor: &mut dyn $crate::network::NeuraLayerVisitorMut,
+        ) {
+            self.$field.accept_visitor_mut(input_shapes, visitor);
+        }
+    };
+    (@method $field:ident, prunable) => {
+        fn prunable(&self) -> Option<&dyn $crate::layer::prune::NeuraPrunable> {
+            self.$field.prunable()
+        }
+    };
+    (@method $field:ident, prunable_mut) => {
+        fn prunable_mut(&mut self) -> Option<&mut dyn $crate::layer::prune::NeuraPrunable> {
+            self.$field.prunable_mut()
+        }
+    };
+}
 
+pub mod array;
 pub mod dense;
 pub mod dropout;
+pub mod init;
 pub mod isolate;
+pub mod learning_rate;
 pub mod lock;
+pub mod lora;
 pub mod normalize;
+pub mod precision;
+pub mod prune;
+pub mod quantize;
 pub mod softmax;
-
-#[derive(Clone, Copy, PartialEq, Debug)]
+pub mod tensor;
+pub mod transform;
+
+/// The shape of the data flowing between layers, stored as a flat vector.
+///
+/// Entries are laid out in row-major order: the last dimension varies the fastest,
+/// so a `Tensor(rows, columns, channels)` is stored row by row, with its channels interleaved.
+///
+/// Shapes of up to three dimensions use the `Vector`, `Matrix` and `Tensor` variants, and `Nd` is reserved for higher ranks:
+/// `NeuraShape::new` takes care of picking the right variant.
+/// Shapes are compared by their dimensions regardless of the variant, and consumers should match on `dimensions()`
+/// rather than on the variants if they can receive a hand-built `Nd`.
+#[derive(Clone, Copy, Debug)]
 pub enum NeuraShape {
     Vector(usize),               // entries
     Matrix(usize, usize),        // rows, columns
     Tensor(usize, usize, usize), // rows, columns, channels
+    Nd(NeuraDims),               // arbitrary rank, eg. (batch, time, rows, columns, channels)
 }
 
 impl NeuraShape {
+    /// Creates a shape with the given dimensions, using the `Vector`, `Matrix` or `Tensor` variant when possible.
+    ///
+    /// Panics if there are no dimensions, or more than `NeuraDims::MAX_RANK` dimensions.
+    pub fn new(dimensions: &[usize]) -> Self {
+        match *dimensions {
+            [] => panic!("Shapes must have at least one dimension"),
+            [entries] => NeuraShape::Vector(entries),
+            [rows, columns] => NeuraShape::Matrix(rows, columns),
+            [rows, columns, channels] => NeuraShape::Tensor(rows, columns, channels),
+            _ => NeuraShape::Nd(NeuraDims::new(dimensions)),
+        }
+    }
+
+    /// Returns the list of dimensions of the shape
+    pub fn dimensions(&self) -> NeuraDims {
+        match *self {
+            NeuraShape::Vector(entries) => NeuraDims::new(&[entries]),
+            NeuraShape::Matrix(rows, columns) => NeuraDims::new(&[rows, columns]),
+            NeuraShape::Tensor(rows, columns, channels) => {
+                NeuraDims::new(&[rows, columns, channels])
+            }
+            NeuraShape::Nd(dims) => dims,
+        }
+    }
+
     pub fn size(&self) -> usize {
         match self {
             NeuraShape::Vector(entries) => *entries,
             NeuraShape::Matrix(rows, columns) => rows * columns,
             NeuraShape::Tensor(rows, columns, channels) => rows * columns * channels,
+            NeuraShape::Nd(dims) => dims.iter().product(),
         }
     }
 
     pub fn sub(&self, other: NeuraShape) -> Option<NeuraShape> {
-        use NeuraShape::*;
+        if !self.is_compatible(other) {
+            return None;
+        }
 
-        Some(match (other, self) {
-            (Vector(x1), Vector(x2)) => Vector(x2 - x1),
-            (Matrix(x1, y1), Matrix(x2, y2)) => Matrix(x2 - x1, y2 - y1),
-            (Tensor(x1, y1, z1), Tensor(x2, y2, z2)) => Tensor(x2 - x1, y2 - y1, z2 - z1),
+        let mut res = self.dimensions();
+        for (x2, x1) in res.iter_mut().zip(other.dimensions().iter()) {
+            *x2 -= x1;
+        }
 
-            _ => return None,
-        })
+        Some(NeuraShape::new(&res))
     }
 
     pub fn is_compatible(&self, other: NeuraShape) -> bool {
-        use NeuraShape::*;
-
-        matches!(
-            (self, other),
-            (Vector(_), Vector(_))
-                | (Matrix(_, _), Matrix(_, _))
-                | (Tensor(_, _, _), Tensor(_, _, _))
-        )
+        self.dims() == other.dims()
     }
 
+    /// Returns the number of dimensions (the rank) of the shape
     pub fn dims(&self) -> usize {
         match self {
             NeuraShape::Vector(_) => 1,
             NeuraShape::Matrix(_, _) => 2,
             NeuraShape::Tensor(_, _, _) => 3,
+            NeuraShape::Nd(dims) => dims.len(),
         }
     }
+
+    /// Returns the offset of the entry at `index` in the flat representation of the shape,
+    /// or `None` if `index` has the wrong number of dimensions or is out of bounds
+    pub fn offset(&self, index: &[usize]) -> Option<usize> {
+        let dimensions = self.dimensions();
+        if index.len() != dimensions.len() {
+            return None;
+        }
+
+        let mut offset = 0;
+        for (&i, &dimension) in index.iter().zip(dimensions.iter()) {
+            if i >= dimension {
+                return None;
+            }
+            offset = offset * dimension + i;
+        }
+
+        Some(offset)
+    }
+}
+
+impl PartialEq for NeuraShape {
+    fn eq(&self, other: &Self) -> bool {
+        self.dimensions() == other.dimensions()
+    }
+}
+
+impl Eq for NeuraShape {}
+
+/// The dimensions of a `NeuraShape::Nd`, stored inline so that `NeuraShape` stays `Copy`.
+/// Dereferences to the slice of dimensions.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NeuraDims {
+    dimensions: [usize; NeuraDims::MAX_RANK],
+    rank: usize,
+}
+
+impl NeuraDims {
+    /// The maximum number of dimensions of a shape, enough for (batch, time, rows, columns, channels)
+    pub const MAX_RANK: usize = 5;
+
+    /// Panics if there are more than `NeuraDims::MAX_RANK` dimensions
+    pub fn new(dimensions: &[usize]) -> Self {
+        assert!(
+            dimensions.len() <= Self::MAX_RANK,
+            "Shapes can have at most {} dimensions, got {:?}",
+            Self::MAX_RANK,
+            dimensions
+        );
+
+        let mut res = Self {
+            dimensions: [0; Self::MAX_RANK],
+            rank: dimensions.len(),
+        };
+        res.dimensions[..dimensions.len()].copy_from_slice(dimensions);
+
+        res
+    }
+}
+
+impl std::ops::Deref for NeuraDims {
+    type Target = [usize];
+
+    fn deref(&self) -> &[usize] {
+        &self.dimensions[..self.rank]
+    }
+}
+
+impl std::ops::DerefMut for NeuraDims {
+    fn deref_mut(&mut self) -> &mut [usize] {
+        &mut self.dimensions[..self.rank]
+    }
+}
+
+impl std::fmt::Debug for NeuraDims {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
 }
 
 impl From<usize> for NeuraShape {
@@ -75,6 +300,12 @@ impl From<(usize, usize, usize)> for NeuraShape {
     }
 }
 
+impl From<&[usize]> for NeuraShape {
+    fn from(dimensions: &[usize]) -> Self {
+        NeuraShape::new(dimensions)
+    }
+}
+
 pub trait NeuraLayerBase: std::fmt::Debug + Clone + 'static {
     /// What type the gradient of the layer is
     type Gradient: NeuraVectorSpace + Send + 'static;
@@ -92,6 +323,14 @@ pub trait NeuraLayerBase: std::fmt::Debug + Clone + 'static {
         // Noop
     }
 
+    /// Adds `offset` to the parameters of the layer as they are seen by `get_gradient`,
+    /// skipping whatever `apply_gradient` does on top of it (masks, norm constraints, learning rate multipliers, ...).
+    /// This is used by `gradient_check` to perturb the parameters; the default implementation calls `apply_gradient`.
+    #[inline(always)]
+    fn offset_parameters(&mut self, offset: &Self::Gradient) {
+        self.apply_gradient(offset);
+    }
+
     /// Arbitrary computation that can be executed at the start of an epoch
     #[allow(unused_variables)]
     #[inline(always)]
@@ -105,12 +344,123 @@ pub trait NeuraLayerBase: std::fmt::Debug + Clone + 'static {
         self.default_gradient()
     }
 
+    /// Multiplies the decayable parameters of the layer by `1 - factor`, used for decoupled weight decay.
+    /// The default implementation is a noop.
+    #[allow(unused_variables)]
+    #[inline(always)]
+    fn decay_layer(&mut self, factor: f64) {
+        // Noop
+    }
+
+    /// Rescales `gradient` so that the L2 norm of each layer's gradient does not exceed `max_norm`.
+    ///
+    /// The default implementation treats `gradient` as the gradient of a single layer;
+    /// networks should override it to call `clip_layer_gradient` on each of their layers.
+    #[inline(always)]
+    fn clip_layer_gradient(&self, gradient: &mut Self::Gradient, max_norm: f64) {
+        gradient.clip_norm(max_norm);
+    }
+
     fn lock_layer(self) -> NeuraLockLayer<Self>
     where
         Self: Sized,
     {
         NeuraLockLayer::new(self)
     }
+
+    /// Wraps the layer in a `NeuraLearningRateLayer`, multiplying its learning rate by `multiplier`
+    fn scale_learning_rate(self, multiplier: f64) -> NeuraLearningRateLayer<Self>
+    where
+        Self: Sized,
+    {
+        NeuraLearningRateLayer::new(self, multiplier)
+    }
+
+    /// Calls `visitor` on each layer, given the shapes of the inputs of the layer (empty if unknown).
+    ///
+    /// The default implementation visits `self` as a single layer;
+    /// networks should override it to call `accept_visitor` on each of their layers.
+    #[inline(always)]
+    fn accept_visitor(&self, input_shapes: &[NeuraShape], visitor: &mut dyn NeuraLayerVisitor) {
+        visitor.visit(self, &NeuraVisitContext::new(input_shapes));
+    }
+
+    /// Mutable counterpart of `accept_visitor`, layers overriding one of them should override both
+    #[inline(always)]
+    fn accept_visitor_mut(
+        &mut self,
+        input_shapes: &[NeuraShape],
+        visitor: &mut dyn NeuraLayerVisitorMut,
+    ) {
+        visitor.visit_mut(self, &NeuraVisitContext::new(input_shapes));
+    }
+
+    /// Calls `visitor` on each layer of the network, in order of evaluation
+    fn visit_layers(&self, visitor: &mut dyn NeuraLayerVisitor) {
+        self.accept_visitor(&[], visitor);
+    }
+
+    /// Calls `visitor` on each layer of the network, in order of evaluation, allowing it to modify them
+    fn visit_layers_mut(&mut self, visitor: &mut dyn NeuraLayerVisitorMut) {
+        self.accept_visitor_mut(&[], visitor);
+    }
+
+    /// Returns a table of the layers of the network, with their shapes and parameter counts
+    fn summary(&self) -> NeuraSummary {
+        let mut res = NeuraSummary::new();
+        self.visit_layers(&mut res);
+        res
+    }
+
+    /// Returns the layer as a `NeuraPrunable`, if its weights can be pruned; defaults to `None`.
+    /// Networks should not override this, as their layers are reached through `accept_visitor`.
+    #[inline(always)]
+    fn prunable(&self) -> Option<&dyn NeuraPrunable> {
+        None
+    }
+
+    /// Mutable counterpart of `prunable`, layers overriding one of them should override both
+    #[inline(always)]
+    fn prunable_mut(&mut self) -> Option<&mut dyn NeuraPrunable> {
+        None
+    }
+
+    /// Prunes the weights with the smallest magnitude across all the prunable layers of the network,
+    /// such that a fraction `sparsity` of their weights is zero. Locked layers are left untouched.
+    ///
+    /// Weights that were already pruned stay pruned, and weights tied in magnitude with the threshold are all pruned,
+    /// so the resulting sparsity may exceed `sparsity`.
+    fn prune_magnitude(&mut self, sparsity: f64) {
+        let mut magnitudes = Vec::new();
+        self.visit_layers(&mut |layer: &dyn NeuraLayerInfo, context: &NeuraVisitContext| {
+            if let (Some(layer), false) = (layer.as_prunable(), context.locked) {
+                magnitudes.extend(layer.weight_magnitudes());
+            }
+        });
+
+        if let Some(threshold) = sparsity_threshold(magnitudes, sparsity) {
+            self.visit_layers_mut(&mut NeuraPruneVisitor { threshold });
+        }
+    }
+
+    /// Returns the fraction of pruned weights among the prunable weights of the network.
+    /// Like in `prune_magnitude`, locked layers are not taken into account.
+    fn sparsity(&self) -> f64 {
+        let mut prunable = 0;
+        let mut pruned = 0;
+        self.visit_layers(&mut |layer: &dyn NeuraLayerInfo, context: &NeuraVisitContext| {
+            if let (Some(layer), false) = (layer.as_prunable(), context.locked) {
+                prunable += layer.prunable_count();
+                pruned += layer.pruned_count();
+            }
+        });
+
+        if prunable == 0 {
+            0.0
+        } else {
+            pruned as f64 / prunable as f64
+        }
+    }
 }
 
 pub trait NeuraLayer<Input>: NeuraLayerBase {
@@ -200,6 +550,20 @@ impl NeuraLayerBase for () {
     fn output_shape(&self) -> NeuraShape {
         panic!("() has no shape!");
     }
+
+    #[inline(always)]
+    fn accept_visitor(&self, _input_shapes: &[NeuraShape], _visitor: &mut dyn NeuraLayerVisitor) {
+        // Noop
+    }
+
+    #[inline(always)]
+    fn accept_visitor_mut(
+        &mut self,
+        _input_shapes: &[NeuraShape],
+        _visitor: &mut dyn NeuraLayerVisitorMut,
+    ) {
+        // Noop
+    }
 }
 
 impl<Input: Clone> NeuraLayer<Input> for () {
@@ -228,7 +592,7 @@ macro_rules! neura_layer {
         let res: $crate::layer::dense::NeuraDenseLayerPartial<$type, _, _, _> =
             $crate::layer::dense::NeuraDenseLayer::new_partial(
                 $output,
-                rand::thread_rng(),
+                $crate::rng::NeuraRng,
                 $crate::derivable::activation::LeakyRelu(0.1),
                 $crate::derivable::regularize::NeuraL0,
             );
@@ -239,7 +603,7 @@ macro_rules! neura_layer {
     };
 
     ( "dropout", $probability:expr ) => {
-        $crate::layer::dropout::NeuraDropoutLayer::new($probability, rand::thread_rng())
+        $crate::layer::dropout::NeuraDropoutLayer::new($probability, $crate::rng::NeuraRng)
     };
 
     ( "softmax" ) => {
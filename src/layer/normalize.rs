@@ -1,6 +1,8 @@
 use nalgebra::{DMatrix, DVector, Scalar};
+use ndarray::{Array, Array1, Array2, Dimension};
 use num::{traits::NumAssignOps, Float};
 
+use super::array::NeuraArrayLayer;
 use super::*;
 
 /// A layer that normalizes and centers its input, as follows:
@@ -71,9 +73,9 @@ impl<F: Float + Scalar + NumAssignOps> NeuraLayer<DVector<F>> for NeuraNormalize
 
         let mut jacobian_partial = &input_centered * input_centered.transpose();
         jacobian_partial /= -variance * (stddev * len);
-        // Apply the 1/σ * dμ/dx_i term
+        // Apply the -1/σ * dμ/dx_i term
         for value in jacobian_partial.iter_mut() {
-            *value += F::one() / (stddev * len);
+            *value -= F::one() / (stddev * len);
         }
 
         (input_centered / stddev, (jacobian_partial, stddev))
@@ -96,6 +98,57 @@ impl<F: Float + Scalar + NumAssignOps> NeuraLayer<DVector<F>> for NeuraNormalize
     }
 }
 
+/// Normalizes all the entries of the array together, regardless of its dimensions
+impl<F: Float + Scalar + NumAssignOps, D: Dimension + 'static> NeuraLayer<Array<F, D>>
+    for NeuraArrayLayer<NeuraNormalizeLayer>
+{
+    type IntermediaryRepr = (Array2<F>, F); // Partial jacobian matrix (without the kroenecker term) and stddev
+
+    type Output = Array<F, D>;
+
+    fn eval(&self, input: &Array<F, D>) -> Self::Output {
+        let (mean, variance, _) = mean_variance(input);
+        let stddev = F::sqrt(variance);
+
+        input.mapv(|item| (item - mean) / stddev)
+    }
+
+    fn eval_training(&self, input: &Array<F, D>) -> (Self::Output, Self::IntermediaryRepr) {
+        let (mean, variance, len) = mean_variance(input);
+        let stddev = F::sqrt(variance);
+        let input_centered = input.mapv(|x| x - mean);
+
+        // The jacobian is indexed by the entries of the array, in logical order
+        let flat_centered = Array1::from_iter(input_centered.iter().copied());
+        let count = flat_centered.len();
+        let mut jacobian_partial =
+            Array2::from_shape_fn((count, count), |(i, j)| flat_centered[i] * flat_centered[j]);
+        let divisor = -variance * (stddev * len);
+        // Apply the -1/σ * dμ/dx_i term
+        jacobian_partial.mapv_inplace(|value| value / divisor - F::one() / (stddev * len));
+
+        (
+            input_centered.mapv(|x| x / stddev),
+            (jacobian_partial, stddev),
+        )
+    }
+
+    fn backprop_layer(
+        &self,
+        _input: &Array<F, D>,
+        (jacobian_partial, stddev): &Self::IntermediaryRepr,
+        epsilon: &Self::Output,
+    ) -> Array<F, D> {
+        let flat_epsilon = Array1::from_iter(epsilon.iter().copied());
+        let mut epsilon_out = jacobian_partial.dot(&flat_epsilon);
+
+        // Apply the δ_{ik}/σ term
+        epsilon_out.zip_mut_with(&flat_epsilon, |out, &epsilon| *out += epsilon / *stddev);
+
+        Array::from_shape_vec(epsilon.raw_dim(), epsilon_out.into_raw_vec()).unwrap()
+    }
+}
+
 fn mean_variance<'a, F: Float + Scalar>(input: impl IntoIterator<Item = &'a F>) -> (F, F, F) {
     // Quickly compute mean and variance in one pass
     let mut count = 0;
@@ -0,0 +1,493 @@
+use nalgebra::{DMatrix, DVector, Scalar};
+use num::{traits::NumAssignOps, Float};
+
+use crate::derivable::NeuraDerivable;
+
+use super::{dense::NeuraDenseLayer, softmax::NeuraSoftmaxLayer, *};
+
+/// Converts a layer or a network to the floating-point type `F`, for instance to store a trained `f32` network as `f64`,
+/// or as `half::f16` or `half::bf16` with the `half` feature:
+///
+/// ```ignore
+/// let half_network = NeuraConvertPrecision::<f16>::convert_precision(&network);
+/// ```
+///
+/// Values that cannot be represented in `F` are converted to the closest value, which may be infinite.
+pub trait NeuraConvertPrecision<F> {
+    type Converted;
+
+    fn convert_precision(&self) -> Self::Converted;
+}
+
+fn convert<From: Float + Scalar, To: Float + Scalar, R: nalgebra::Dim, C: nalgebra::Dim>(
+    matrix: &nalgebra::OMatrix<From, R, C>,
+) -> nalgebra::OMatrix<To, R, C>
+where
+    nalgebra::DefaultAllocator:
+        nalgebra::allocator::Allocator<From, R, C> + nalgebra::allocator::Allocator<To, R, C>,
+{
+    matrix.map(|x| To::from(x).unwrap_or_else(|| To::nan()))
+}
+
+impl<
+        F: Float + Scalar,
+        F2: Float + Scalar,
+        Act: NeuraDerivable<F> + NeuraDerivable<F2> + Clone,
+        Reg: NeuraDerivable<F> + NeuraDerivable<F2> + Clone,
+    > NeuraConvertPrecision<F2> for NeuraDenseLayer<F, Act, Reg>
+{
+    type Converted = NeuraDenseLayer<F2, Act, Reg>;
+
+    fn convert_precision(&self) -> Self::Converted {
+        NeuraDenseLayer {
+            weights: convert(&self.weights),
+            bias: convert(&self.bias),
+            activation: self.activation.clone(),
+            regularization: self.regularization.clone(),
+            regularize_bias: self.regularize_bias,
+            max_norm: self.max_norm,
+            mask: self.mask.clone(),
+        }
+    }
+}
+
+impl<F> NeuraConvertPrecision<F> for NeuraSoftmaxLayer {
+    type Converted = Self;
+
+    fn convert_precision(&self) -> Self::Converted {
+        self.clone()
+    }
+}
+
+/// A dense layer trained in mixed precision: the weights are stored both as `f32` (the master weights)
+/// and as `H` (typically `half::f16` or `half::bf16`), which is used to evaluate and backpropagate through the layer.
+/// Gradients are converted back to `f32` before being accumulated and applied to the master weights,
+/// so that small updates aren't lost to rounding.
+///
+/// The layer takes and returns `f32` vectors, so it can be used with regular datasets and losses.
+/// To prevent small gradients from underflowing in half precision, the loss should be wrapped in a `LossScaling`
+/// with the same scale as `loss_scale`: the gradients of the layer are divided by `loss_scale` once converted to `f32`.
+/// Gradients that overflowed keep their infinite or NaN values, so that `NeuraBatchedTrainer` skips the whole step.
+///
+/// Every trainable layer of the network must then take the loss scale into account;
+/// use `NeuraMixedPrecision::mixed_precision` to convert a whole network.
+#[derive(Clone, Debug)]
+pub struct NeuraMixedPrecisionDense<H: Float, Act: NeuraDerivable<H>, Reg: NeuraDerivable<H>> {
+    layer: NeuraDenseLayer<H, Act, Reg>,
+    master_weights: DMatrix<f32>,
+    master_bias: DVector<f32>,
+
+    /// The factor by which the gradient of the loss was multiplied, see `LossScaling`
+    pub loss_scale: f32,
+}
+
+impl<H: Float + Scalar, Act: NeuraDerivable<H> + Clone, Reg: NeuraDerivable<H> + Clone>
+    NeuraMixedPrecisionDense<H, Act, Reg>
+{
+    pub fn new(layer: &NeuraDenseLayer<f32, Act, Reg>, loss_scale: f32) -> Self
+    where
+        Act: NeuraDerivable<f32>,
+        Reg: NeuraDerivable<f32>,
+    {
+        Self {
+            layer: layer.convert_precision(),
+            master_weights: layer.weights.clone(),
+            master_bias: layer.bias.clone(),
+            loss_scale,
+        }
+    }
+
+    /// The half-precision copy of the layer, as used for evaluation
+    pub fn half(&self) -> &NeuraDenseLayer<H, Act, Reg> {
+        &self.layer
+    }
+
+    pub fn master_weights(&self) -> &DMatrix<f32> {
+        &self.master_weights
+    }
+
+    pub fn master_bias(&self) -> &DVector<f32> {
+        &self.master_bias
+    }
+
+    /// Copies the master weights into the half-precision layer, keeping pruned weights at zero
+    fn sync_weights(&mut self) {
+        if let Some(mask) = &self.layer.mask {
+            self.master_weights.zip_apply(mask, |weight, kept| {
+                if !kept {
+                    *weight = 0.0;
+                }
+            });
+        }
+
+        self.layer.weights = convert(&self.master_weights);
+        self.layer.bias = convert(&self.master_bias);
+    }
+}
+
+impl<
+        F: Float + Scalar,
+        H: Float + Scalar,
+        Act: NeuraDerivable<H> + NeuraDerivable<F> + Clone,
+        Reg: NeuraDerivable<H> + NeuraDerivable<F> + Clone,
+    > NeuraConvertPrecision<F> for NeuraMixedPrecisionDense<H, Act, Reg>
+{
+    type Converted = NeuraDenseLayer<F, Act, Reg>;
+
+    /// Converts the master weights of the layer
+    fn convert_precision(&self) -> Self::Converted {
+        NeuraDenseLayer {
+            weights: convert(&self.master_weights),
+            bias: convert(&self.master_bias),
+            activation: self.layer.activation.clone(),
+            regularization: self.layer.regularization.clone(),
+            regularize_bias: self.layer.regularize_bias,
+            max_norm: self.layer.max_norm,
+            mask: self.layer.mask.clone(),
+        }
+    }
+}
+
+impl<
+        H: Float + Scalar + NumAssignOps + Send,
+        Act: NeuraDerivable<H> + Clone + std::fmt::Debug + 'static,
+        Reg: NeuraDerivable<H> + Clone + std::fmt::Debug + 'static,
+    > NeuraLayerBase for NeuraMixedPrecisionDense<H, Act, Reg>
+{
+    type Gradient = (DMatrix<f32>, DVector<f32>);
+
+    fn output_shape(&self) -> NeuraShape {
+        NeuraShape::Vector(self.master_weights.nrows())
+    }
+
+    fn default_gradient(&self) -> Self::Gradient {
+        (
+            DMatrix::zeros(self.master_weights.nrows(), self.master_weights.ncols()),
+            DVector::zeros(self.master_bias.len()),
+        )
+    }
+
+    fn apply_gradient(&mut self, gradient: &Self::Gradient) {
+        self.master_weights += &gradient.0;
+        self.master_bias += &gradient.1;
+        self.sync_weights();
+    }
+
+    /// Offsets the master weights, including the pruned ones, and copies them into the half-precision layer
+    fn offset_parameters(&mut self, offset: &Self::Gradient) {
+        self.master_weights += &offset.0;
+        self.master_bias += &offset.1;
+        self.layer.weights = convert(&self.master_weights);
+        self.layer.bias = convert(&self.master_bias);
+    }
+
+    fn decay_layer(&mut self, factor: f64) {
+        let multiplier = 1.0 - factor as f32;
+
+        self.master_weights *= multiplier;
+        if self.layer.regularize_bias {
+            self.master_bias *= multiplier;
+        }
+        self.sync_weights();
+    }
+
+    fn regularize_layer(&self) -> Self::Gradient {
+        let (weights, bias) = self.layer.regularize_layer();
+
+        (convert(&weights), convert(&bias))
+    }
+
+    fn prunable(&self) -> Option<&dyn prune::NeuraPrunable> {
+        Some(self)
+    }
+
+    fn prunable_mut(&mut self) -> Option<&mut dyn prune::NeuraPrunable> {
+        Some(self)
+    }
+}
+
+/// Prunes the master weights, and the half-precision weights along with them
+impl<
+        H: Float + Scalar + NumAssignOps + Send,
+        Act: NeuraDerivable<H> + Clone + std::fmt::Debug + 'static,
+        Reg: NeuraDerivable<H> + Clone + std::fmt::Debug + 'static,
+    > prune::NeuraPrunable for NeuraMixedPrecisionDense<H, Act, Reg>
+{
+    fn prunable_count(&self) -> usize {
+        self.master_weights.len()
+    }
+
+    fn pruned_count(&self) -> usize {
+        self.layer.pruned_count()
+    }
+
+    fn weight_magnitudes(&self) -> Vec<f64> {
+        self.master_weights
+            .iter()
+            .map(|weight| weight.abs() as f64)
+            .collect()
+    }
+
+    fn prune_below(&mut self, threshold: f64) {
+        let threshold = threshold as f32;
+        let mut mask = self.layer.mask.take().unwrap_or_else(|| {
+            DMatrix::from_element(self.master_weights.nrows(), self.master_weights.ncols(), true)
+        });
+
+        mask.zip_apply(&self.master_weights, |kept, weight| {
+            if weight.abs() <= threshold {
+                *kept = false;
+            }
+        });
+
+        self.layer.mask = Some(mask);
+        self.sync_weights();
+    }
+
+    fn clear_mask(&mut self) {
+        self.layer.clear_mask();
+    }
+
+    fn neuron_count(&self) -> usize {
+        self.master_weights.nrows()
+    }
+
+    fn neuron_norms(&self) -> Vec<f64> {
+        self.master_weights
+            .row_iter()
+            .map(|row| row.norm() as f64)
+            .collect()
+    }
+
+    fn retain_neurons(&mut self, neurons: &[usize]) {
+        self.layer.retain_neurons(neurons);
+        self.master_weights = self.master_weights.select_rows(neurons);
+        self.master_bias = self.master_bias.select_rows(neurons);
+    }
+
+    fn retain_inputs(&mut self, inputs: &[usize]) {
+        self.layer.retain_inputs(inputs);
+        self.master_weights = self.master_weights.select_columns(inputs);
+    }
+}
+
+/// The intermediary values of `NeuraMixedPrecisionDense`
+#[derive(Clone, Debug)]
+pub struct NeuraMixedPrecisionIntermediary<H: Scalar> {
+    /// The input, converted to `H`
+    input: DVector<H>,
+    /// The pre-activation values
+    evaluated: DVector<H>,
+}
+
+impl<
+        H: Float + Scalar + NumAssignOps + Send,
+        Act: NeuraDerivable<H> + Clone + std::fmt::Debug + 'static,
+        Reg: NeuraDerivable<H> + Clone + std::fmt::Debug + 'static,
+    > NeuraLayer<DVector<f32>> for NeuraMixedPrecisionDense<H, Act, Reg>
+{
+    type Output = DVector<f32>;
+    type IntermediaryRepr = NeuraMixedPrecisionIntermediary<H>;
+
+    fn eval_training(&self, input: &DVector<f32>) -> (Self::Output, Self::IntermediaryRepr) {
+        let input = convert(input);
+        let (output, evaluated) = self.layer.eval_training(&input);
+
+        (
+            convert(&output),
+            NeuraMixedPrecisionIntermediary { input, evaluated },
+        )
+    }
+
+    fn get_gradient(
+        &self,
+        _input: &DVector<f32>,
+        intermediary: &Self::IntermediaryRepr,
+        epsilon: &Self::Output,
+    ) -> Self::Gradient {
+        let (weights, bias) = self.layer.get_gradient(
+            &intermediary.input,
+            &intermediary.evaluated,
+            &convert(epsilon),
+        );
+
+        let mut gradient: Self::Gradient = (convert(&weights), convert(&bias));
+        gradient.0 /= self.loss_scale;
+        gradient.1 /= self.loss_scale;
+
+        gradient
+    }
+
+    fn backprop_layer(
+        &self,
+        _input: &DVector<f32>,
+        intermediary: &Self::IntermediaryRepr,
+        epsilon: &Self::Output,
+    ) -> DVector<f32> {
+        let epsilon = self.layer.backprop_layer(
+            &intermediary.input,
+            &intermediary.evaluated,
+            &convert(epsilon),
+        );
+
+        convert(&epsilon)
+    }
+}
+
+/// Converts a layer or a network to mixed-precision training with half-precision type `H`, see `NeuraMixedPrecisionDense`
+pub trait NeuraMixedPrecision<H> {
+    type Mixed;
+
+    fn mixed_precision(&self, loss_scale: f32) -> Self::Mixed;
+}
+
+impl<
+        H: Float + Scalar,
+        Act: NeuraDerivable<f32> + NeuraDerivable<H> + Clone,
+        Reg: NeuraDerivable<f32> + NeuraDerivable<H> + Clone,
+    > NeuraMixedPrecision<H> for NeuraDenseLayer<f32, Act, Reg>
+{
+    type Mixed = NeuraMixedPrecisionDense<H, Act, Reg>;
+
+    fn mixed_precision(&self, loss_scale: f32) -> Self::Mixed {
+        NeuraMixedPrecisionDense::new(self, loss_scale)
+    }
+}
+
+impl<H> NeuraMixedPrecision<H> for NeuraSoftmaxLayer {
+    type Mixed = Self;
+
+    fn mixed_precision(&self, _loss_scale: f32) -> Self::Mixed {
+        self.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nalgebra::{dmatrix, dvector};
+
+    use super::*;
+    use crate::derivable::{activation::Relu, regularize::NeuraL0};
+    use crate::layer::prune::NeuraPrunable;
+
+    #[test]
+    fn test_convert_precision() {
+        let layer = NeuraDenseLayer::new(
+            dmatrix![0.5f32, -0.25; 1.0 / 3.0, 2.0],
+            dvector![0.1, 0.0],
+            Relu,
+            NeuraL0,
+        );
+
+        let converted: NeuraDenseLayer<f64, _, _> = layer.convert_precision();
+        assert_eq!(converted.weights[(1, 0)], (1.0f32 / 3.0) as f64);
+        assert_eq!(converted.output_shape(), layer.output_shape());
+
+        let back: NeuraDenseLayer<f32, _, _> = converted.convert_precision();
+        assert_eq!(back.weights, layer.weights);
+        assert_eq!(back.bias, layer.bias);
+    }
+
+    #[test]
+    fn test_mixed_precision_prune() {
+        let layer = NeuraDenseLayer::new(
+            dmatrix![0.5f32, -0.1, 2.0; -0.3, 1.0, 0.05],
+            dvector![0.1, 0.2],
+            Relu,
+            NeuraL0,
+        );
+        let mut mixed = NeuraMixedPrecision::<f64>::mixed_precision(&layer, 1.0);
+
+        mixed.prune_magnitude(0.5);
+        assert_eq!(mixed.pruned_count(), 3);
+        assert_eq!(
+            mixed.master_weights(),
+            &dmatrix![0.5f32, 0.0, 2.0; 0.0, 1.0, 0.0]
+        );
+        assert_eq!(mixed.half().weights, dmatrix![0.5, 0.0, 2.0; 0.0, 1.0, 0.0]);
+
+        // Pruned weights stay at zero
+        let mut gradient = mixed.default_gradient();
+        gradient.0.fill(1.0);
+        mixed.apply_gradient(&gradient);
+        assert_eq!(
+            mixed.master_weights(),
+            &dmatrix![1.5f32, 0.0, 3.0; 0.0, 2.0, 0.0]
+        );
+
+        mixed.retain_neurons(&[1]);
+        mixed.retain_inputs(&[0, 1]);
+        assert_eq!(mixed.master_weights(), &dmatrix![0.0f32, 2.0]);
+        assert_eq!(mixed.master_bias(), &dvector![0.2f32]);
+        assert_eq!(mixed.half().weights, dmatrix![0.0, 2.0]);
+        assert_eq!(mixed.output_shape(), NeuraShape::Vector(1));
+    }
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn test_mixed_precision() {
+        use approx::assert_relative_eq;
+        use half::f16;
+
+        use crate::{
+            derivable::{
+                activation::Linear,
+                loss::{Euclidean, LossScaling},
+            },
+            gradient_solver::NeuraBackprop,
+            network::sequential::NeuraSequential,
+            neura_sequential,
+            train::NeuraBatchedTrainer,
+        };
+
+        let network = neura_sequential![NeuraDenseLayer::new(
+            dmatrix![0.5f32, -0.25],
+            dvector![0.0],
+            Linear,
+            NeuraL0
+        )];
+        let inputs = [(dvector![1.0, 2.0], dvector![0.001f32])];
+        let trainer = NeuraBatchedTrainer::new()
+            .learning_rate(0.01)
+            .batch_size(1)
+            .iterations(1);
+
+        let mut expected = network.clone();
+        trainer.train(
+            &NeuraBackprop::new(Euclidean),
+            &mut expected,
+            inputs.iter().cloned(),
+            &inputs,
+        );
+
+        let mut mixed = NeuraMixedPrecision::<f16>::mixed_precision(&network, 1024.0);
+        assert_eq!(
+            mixed.layer.half().weights,
+            dmatrix![f16::from_f32(0.5), f16::from_f32(-0.25)]
+        );
+        trainer.train(
+            &NeuraBackprop::new(LossScaling::new(Euclidean, 1024.0)),
+            &mut mixed,
+            inputs.iter().cloned(),
+            &inputs,
+        );
+
+        let trained: NeuraSequential<NeuraDenseLayer<f32, _, _>, _> = mixed.convert_precision();
+        assert_relative_eq!(
+            trained.layer.weights,
+            expected.layer.weights,
+            epsilon = 1e-4
+        );
+        assert_relative_eq!(trained.layer.bias, expected.layer.bias, epsilon = 1e-4);
+
+        // Overflowing gradients are skipped
+        let mut mixed = NeuraMixedPrecision::<f16>::mixed_precision(&network, 1e8);
+        trainer.train(
+            &NeuraBackprop::new(LossScaling::new(Euclidean, 1e8)),
+            &mut mixed,
+            inputs.iter().cloned(),
+            &inputs,
+        );
+        assert_eq!(mixed.layer.master_weights(), &network.layer.weights);
+    }
+}
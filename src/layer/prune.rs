@@ -0,0 +1,175 @@
+use nalgebra::{DMatrix, Scalar};
+use num::{traits::NumAssignOps, Float};
+
+use crate::{derivable::NeuraDerivable, network::NeuraLayerInfo};
+
+use super::{dense::NeuraDenseLayer, *};
+
+/// A layer whose weights can be pruned, as returned by `NeuraLayerBase::prunable`.
+///
+/// Pruned weights are set to zero and masked, so that they stay at zero during training.
+/// The neurons of the layer can also be removed altogether, which changes its output shape:
+/// see `NeuraPruneNeurons` to remove neurons from a network.
+pub trait NeuraPrunable {
+    /// The number of weights that can be pruned; biases are never pruned
+    fn prunable_count(&self) -> usize;
+
+    /// The number of weights that have been pruned
+    fn pruned_count(&self) -> usize;
+
+    /// Returns the absolute value of each prunable weight, pruned weights having a magnitude of zero
+    fn weight_magnitudes(&self) -> Vec<f64>;
+
+    /// Prunes the weights whose magnitude is lower than or equal to `threshold`
+    fn prune_below(&mut self, threshold: f64);
+
+    /// Clears the pruning mask, allowing pruned weights to be trained again; they remain zero until then
+    fn clear_mask(&mut self);
+
+    /// The number of neurons (outputs) of the layer
+    fn neuron_count(&self) -> usize;
+
+    /// Returns the L2 norm of the incoming weights of each neuron
+    fn neuron_norms(&self) -> Vec<f64>;
+
+    /// Removes every neuron whose index isn't in `neurons`, which must be sorted and not contain duplicates
+    fn retain_neurons(&mut self, neurons: &[usize]);
+
+    /// Removes every input whose index isn't in `inputs`, which must be sorted and not contain duplicates.
+    /// Used to remove the neurons of the previous layer.
+    fn retain_inputs(&mut self, inputs: &[usize]);
+}
+
+impl<F: Float + NumAssignOps + Scalar + Send, Act: NeuraDerivable<F>, Reg: NeuraDerivable<F>>
+    NeuraPrunable for NeuraDenseLayer<F, Act, Reg>
+where
+    Self: Clone + std::fmt::Debug + 'static,
+{
+    fn prunable_count(&self) -> usize {
+        self.weights.len()
+    }
+
+    fn pruned_count(&self) -> usize {
+        self.mask
+            .as_ref()
+            .map(|mask| mask.iter().filter(|kept| !**kept).count())
+            .unwrap_or(0)
+    }
+
+    fn weight_magnitudes(&self) -> Vec<f64> {
+        self.weights
+            .iter()
+            .map(|weight| weight.abs().to_f64().unwrap())
+            .collect()
+    }
+
+    fn prune_below(&mut self, threshold: f64) {
+        let threshold = F::from(threshold).unwrap();
+        let mut mask = self.mask.take().unwrap_or_else(|| {
+            DMatrix::from_element(self.weights.nrows(), self.weights.ncols(), true)
+        });
+
+        mask.zip_apply(&self.weights, |kept, weight| {
+            if weight.abs() <= threshold {
+                *kept = false;
+            }
+        });
+
+        self.mask = Some(mask);
+        self.apply_mask();
+    }
+
+    fn clear_mask(&mut self) {
+        self.mask = None;
+    }
+
+    fn neuron_count(&self) -> usize {
+        self.weights.nrows()
+    }
+
+    fn neuron_norms(&self) -> Vec<f64> {
+        self.weights
+            .row_iter()
+            .map(|row| {
+                let norm = row.iter().fold(F::zero(), |sum, &x| sum + x * x).sqrt();
+                norm.to_f64().unwrap()
+            })
+            .collect()
+    }
+
+    fn retain_neurons(&mut self, neurons: &[usize]) {
+        self.weights = self.weights.select_rows(neurons);
+        self.bias = self.bias.select_rows(neurons);
+        self.mask = self.mask.as_ref().map(|mask| mask.select_rows(neurons));
+    }
+
+    fn retain_inputs(&mut self, inputs: &[usize]) {
+        self.weights = self.weights.select_columns(inputs);
+        self.mask = self.mask.as_ref().map(|mask| mask.select_columns(inputs));
+    }
+}
+
+/// Prunes the weights of every visited layer whose magnitude is at most `threshold`, used by `NeuraLayerBase::prune_magnitude`
+pub(crate) struct NeuraPruneVisitor {
+    pub threshold: f64,
+}
+
+impl NeuraLayerVisitorMut for NeuraPruneVisitor {
+    fn visit_mut(&mut self, layer: &mut dyn NeuraLayerInfo, context: &NeuraVisitContext) {
+        if context.locked {
+            return;
+        }
+
+        if let Some(layer) = layer.as_prunable_mut() {
+            layer.prune_below(self.threshold);
+        }
+    }
+}
+
+/// Returns the magnitude under which weights must be pruned so that a fraction `sparsity` of `magnitudes` is pruned,
+/// or `None` if no weight needs to be pruned
+pub(crate) fn sparsity_threshold(mut magnitudes: Vec<f64>, sparsity: f64) -> Option<f64> {
+    let count = (sparsity.clamp(0.0, 1.0) * magnitudes.len() as f64).round() as usize;
+    if count == 0 {
+        return None;
+    }
+
+    let (_, threshold, _) = magnitudes.select_nth_unstable_by(count - 1, f64::total_cmp);
+
+    Some(*threshold)
+}
+
+#[cfg(test)]
+mod test {
+    use nalgebra::{dmatrix, dvector};
+
+    use super::*;
+    use crate::derivable::{activation::Linear, regularize::NeuraL0};
+
+    #[test]
+    fn test_prune_dense() {
+        let mut layer = NeuraDenseLayer::new(
+            dmatrix![0.5, -0.1, 2.0; -0.3, 1.0, 0.05],
+            dvector![0.0, 0.0],
+            Linear,
+            NeuraL0,
+        );
+
+        layer.prune_magnitude(0.5);
+        assert_eq!(layer.weights, dmatrix![0.5, 0.0, 2.0; 0.0, 1.0, 0.0]);
+        assert_eq!(layer.pruned_count(), 3);
+        assert_eq!(layer.sparsity(), 0.5);
+
+        // Pruned weights stay at zero
+        let mut gradient = layer.default_gradient();
+        gradient.0.fill(1.0);
+        layer.apply_gradient(&gradient);
+        assert_eq!(layer.weights, dmatrix![1.5, 0.0, 3.0; 0.0, 2.0, 0.0]);
+
+        layer.retain_neurons(&[1]);
+        layer.retain_inputs(&[0, 1]);
+        assert_eq!(layer.weights, dmatrix![0.0, 2.0]);
+        assert_eq!(layer.mask(), Some(&dmatrix![false, true]));
+        assert_eq!(layer.output_shape(), NeuraShape::Vector(1));
+    }
+}
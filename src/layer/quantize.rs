@@ -0,0 +1,260 @@
+use nalgebra::{DMatrix, DVector};
+
+use crate::derivable::NeuraDerivable;
+
+use super::{dense::NeuraDenseLayer, softmax::NeuraSoftmaxLayer, *};
+
+/// A layer that can be converted into an int8 inference-only layer, see `NeuraSequentialQuantize`
+pub trait NeuraQuantizable: NeuraLayer<DVector<f32>, Output = DVector<f32>> {
+    type Quantized: NeuraLayer<DVector<f32>, Output = DVector<f32>>;
+
+    /// Quantizes the layer, using `calibration` as a sample of its inputs to estimate their range
+    fn quantize_layer(&self, calibration: &[DVector<f32>]) -> Self::Quantized;
+}
+
+/// A dense layer meant for inference, with frozen int8 weights and inputs.
+///
+/// The weights of each neuron are quantized symmetrically, with their own scale (per-channel quantization),
+/// while the inputs are quantized asymmetrically, using the range observed during calibration.
+/// The products are accumulated as `i32`, then rescaled to `f32` before adding the bias and applying the activation,
+/// so the layer takes and returns `f32` vectors.
+///
+/// Inputs outside of the calibrated range are clamped to it.
+///
+/// The quantized weights cannot be trained, but the layer can still be backpropagated through,
+/// for instance to fine-tune the layers before it: the rounding of the weights and inputs is treated as the identity
+/// (straight-through estimator), so the epsilon is propagated using `dequantized_weights`.
+#[derive(Clone, Debug)]
+pub struct NeuraQuantizedDense<Act> {
+    weights: DMatrix<i8>,
+    weight_scales: DVector<f32>,
+    bias: DVector<f32>,
+
+    input_scale: f32,
+    input_zero_point: i8,
+
+    activation: Act,
+}
+
+impl<Act> NeuraQuantizedDense<Act> {
+    /// Quantizes `weights` per row, and the inputs of the layer over the range `[input_min, input_max]`
+    pub fn new(
+        weights: &DMatrix<f32>,
+        bias: DVector<f32>,
+        activation: Act,
+        input_min: f32,
+        input_max: f32,
+    ) -> Self {
+        assert_eq!(bias.len(), weights.nrows());
+
+        let weight_scales = DVector::from_iterator(
+            weights.nrows(),
+            weights.row_iter().map(|row| {
+                let max = row.iter().fold(0.0f32, |max, x| max.max(x.abs()));
+                if max > 0.0 {
+                    max / 127.0
+                } else {
+                    1.0
+                }
+            }),
+        );
+        let weights = DMatrix::from_fn(weights.nrows(), weights.ncols(), |i, j| {
+            quantize(weights[(i, j)] / weight_scales[i])
+        });
+
+        // The range must contain zero, so that it can be represented exactly
+        let input_min = input_min.min(0.0);
+        let input_max = input_max.max(0.0);
+        let input_scale = if input_max > input_min {
+            (input_max - input_min) / 255.0
+        } else {
+            1.0
+        };
+        let input_zero_point = quantize(-128.0 - input_min / input_scale);
+
+        Self {
+            weights,
+            weight_scales,
+            bias,
+            input_scale,
+            input_zero_point,
+            activation,
+        }
+    }
+
+    /// The quantized weights; the weights of neuron `i` are approximated by `weights[i] * weight_scales[i]`
+    pub fn weights(&self) -> &DMatrix<i8> {
+        &self.weights
+    }
+
+    pub fn weight_scales(&self) -> &DVector<f32> {
+        &self.weight_scales
+    }
+
+    pub fn bias(&self) -> &DVector<f32> {
+        &self.bias
+    }
+
+    /// Returns the scale and zero point of the inputs: an input `x` is quantized as `round(x / scale) + zero_point`
+    pub fn input_quantization(&self) -> (f32, i8) {
+        (self.input_scale, self.input_zero_point)
+    }
+
+    /// Returns an approximation of the original weights
+    pub fn dequantized_weights(&self) -> DMatrix<f32> {
+        DMatrix::from_fn(self.weights.nrows(), self.weights.ncols(), |i, j| {
+            self.weights[(i, j)] as f32 * self.weight_scales[i]
+        })
+    }
+}
+
+fn quantize(x: f32) -> i8 {
+    x.round().clamp(i8::MIN as f32, i8::MAX as f32) as i8
+}
+
+impl<Act: Clone + std::fmt::Debug + 'static> NeuraLayerBase for NeuraQuantizedDense<Act> {
+    type Gradient = ();
+
+    fn output_shape(&self) -> NeuraShape {
+        NeuraShape::Vector(self.weights.nrows())
+    }
+
+    fn default_gradient(&self) -> Self::Gradient {}
+}
+
+impl<Act: NeuraDerivable<f32>> NeuraLayer<DVector<f32>> for NeuraQuantizedDense<Act>
+where
+    Self: NeuraLayerBase<Gradient = ()>,
+{
+    type Output = DVector<f32>;
+    type IntermediaryRepr = DVector<f32>; // Before activation
+
+    fn eval_training(&self, input: &DVector<f32>) -> (Self::Output, Self::IntermediaryRepr) {
+        let zero_point = self.input_zero_point as i32;
+        let input: Vec<i32> = input
+            .iter()
+            .map(|&x| quantize(x / self.input_scale + zero_point as f32) as i32 - zero_point)
+            .collect();
+
+        let evaluated = DVector::from_fn(self.weights.nrows(), |i, _| {
+            let accumulator: i32 = self
+                .weights
+                .row(i)
+                .iter()
+                .zip(input.iter())
+                .map(|(&weight, &x)| weight as i32 * x)
+                .sum();
+
+            accumulator as f32 * self.weight_scales[i] * self.input_scale + self.bias[i]
+        });
+        let output = evaluated.map(|x| self.activation.eval(x));
+
+        (output, evaluated)
+    }
+
+    fn backprop_layer(
+        &self,
+        _input: &DVector<f32>,
+        evaluated: &Self::IntermediaryRepr,
+        epsilon: &Self::Output,
+    ) -> DVector<f32> {
+        // Compute delta from epsilon, with `self.activation'(input) ° epsilon = delta`
+        let mut delta = epsilon.clone();
+
+        for i in 0..delta.len() {
+            delta[i] *= self.activation.derivate(evaluated[i]);
+        }
+
+        // Straight-through estimator: the quantization of the weights and inputs is ignored
+        self.dequantized_weights().tr_mul(&delta)
+    }
+}
+
+impl<Act: NeuraDerivable<f32> + Clone + std::fmt::Debug + 'static, Reg: NeuraDerivable<f32>>
+    NeuraQuantizable for NeuraDenseLayer<f32, Act, Reg>
+where
+    Self: NeuraLayer<DVector<f32>, Output = DVector<f32>>,
+{
+    type Quantized = NeuraQuantizedDense<Act>;
+
+    fn quantize_layer(&self, calibration: &[DVector<f32>]) -> Self::Quantized {
+        let (input_min, input_max) = calibration
+            .iter()
+            .flat_map(|input| input.iter())
+            .fold((0.0f32, 0.0f32), |(min, max), &x| (min.min(x), max.max(x)));
+
+        NeuraQuantizedDense::new(
+            &self.weights,
+            self.bias.clone(),
+            self.activation.clone(),
+            input_min,
+            input_max,
+        )
+    }
+}
+
+impl NeuraQuantizable for NeuraSoftmaxLayer {
+    type Quantized = Self;
+
+    fn quantize_layer(&self, _calibration: &[DVector<f32>]) -> Self::Quantized {
+        self.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::{dmatrix, dvector};
+
+    use super::*;
+    use crate::derivable::{activation::Relu, regularize::NeuraL0};
+
+    #[test]
+    fn test_quantized_dense() {
+        let layer = NeuraDenseLayer::new(
+            dmatrix![0.5f32, -1.27, 0.0; 0.03, 0.04, -0.01],
+            dvector![0.1, -0.2],
+            Relu,
+            NeuraL0,
+        );
+        let calibration = [dvector![1.0, -2.0, 0.5], dvector![-1.0, 3.0, 0.0]];
+        let quantized = layer.quantize_layer(&calibration);
+
+        assert_eq!(quantized.weights().row(0), dmatrix![50i8, -127, 0]);
+        assert_eq!(quantized.weights().row(1), dmatrix![95i8, 127, -32]);
+        assert_relative_eq!(
+            quantized.dequantized_weights(),
+            layer.weights,
+            epsilon = 1e-3
+        );
+
+        let (scale, zero_point) = quantized.input_quantization();
+        assert_relative_eq!(scale, 5.0 / 255.0);
+        assert_eq!(zero_point, -26);
+
+        for input in calibration {
+            assert_relative_eq!(quantized.eval(&input), layer.eval(&input), epsilon = 0.02);
+        }
+    }
+
+    #[test]
+    fn test_quantized_dense_backprop() {
+        let layer = NeuraDenseLayer::new(
+            dmatrix![0.5f32, -1.27, 0.0; 0.03, 0.04, -0.01],
+            dvector![0.1, -0.2],
+            Relu,
+            NeuraL0,
+        );
+        let input = dvector![1.0, -2.0, 0.5];
+        let epsilon = dvector![0.3, -0.7];
+        let quantized = layer.quantize_layer(&[input.clone()]);
+
+        let (_, intermediary) = layer.eval_training(&input);
+        let expected = layer.backprop_layer(&input, &intermediary, &epsilon);
+
+        let (_, intermediary) = quantized.eval_training(&input);
+        let actual = quantized.backprop_layer(&input, &intermediary, &epsilon);
+
+        assert_relative_eq!(actual, expected, epsilon = 1e-3);
+    }
+}
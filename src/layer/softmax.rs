@@ -1,6 +1,8 @@
 use nalgebra::{DVector, Scalar};
+use ndarray::{Array, Dimension};
 use num::{traits::NumAssignOps, Float};
 
+use super::array::NeuraArrayLayer;
 use super::*;
 
 #[derive(Clone, Debug)]
@@ -90,6 +92,52 @@ impl<F: Float + Scalar + NumAssignOps> NeuraLayer<DVector<F>> for NeuraSoftmaxLa
     }
 }
 
+/// Applies the softmax over all the entries of the array, regardless of its dimensions
+impl<F: Float + Scalar + NumAssignOps, D: Dimension + 'static> NeuraLayer<Array<F, D>>
+    for NeuraArrayLayer<NeuraSoftmaxLayer>
+{
+    type Output = Array<F, D>;
+    type IntermediaryRepr = Self::Output; // Result of self.eval
+
+    fn eval(&self, input: &Array<F, D>) -> Self::Output {
+        let max = input
+            .iter()
+            .fold(F::zero(), |max, &item| if item > max { item } else { max });
+
+        let mut res = input.mapv(|item| (item - max).exp());
+        let sum = res.sum();
+        res.mapv_inplace(|item| item / sum);
+
+        res
+    }
+
+    fn eval_training(&self, input: &Array<F, D>) -> (Self::Output, Self::IntermediaryRepr) {
+        let res = self.eval(input);
+        (res.clone(), res)
+    }
+
+    fn backprop_layer(
+        &self,
+        _input: &Array<F, D>,
+        evaluated: &Self::IntermediaryRepr,
+        epsilon: &Self::Output,
+    ) -> Array<F, D> {
+        let mut epsilon = epsilon.clone();
+
+        // Compute $a_{l-1,i} ° \epsilon_{l,i}$
+        epsilon.zip_mut_with(evaluated, |epsilon, &evaluated| *epsilon *= evaluated);
+
+        // Compute $\sum_{k}{a_{l-1,k} \epsilon_{l,k}}$
+        let sum_diagonal_terms = epsilon.sum();
+
+        epsilon.zip_mut_with(evaluated, |epsilon, &evaluated| {
+            *epsilon -= evaluated * sum_diagonal_terms
+        });
+
+        epsilon
+    }
+}
+
 fn hadamard_product<F: Float + std::ops::MulAssign>(left: &mut DVector<F>, right: &DVector<F>) {
     for i in 0..left.len() {
         left[i] *= right[i];
@@ -98,12 +146,33 @@ fn hadamard_product<F: Float + std::ops::MulAssign>(left: &mut DVector<F>, right
 
 #[cfg(test)]
 mod test {
+    use approx::assert_relative_eq;
     use nalgebra::{dvector, DMatrix};
 
     use crate::utils::uniform_vector;
 
     use super::*;
 
+    #[test]
+    fn test_softmax_ndarray() {
+        let layer = NeuraSoftmaxLayer::new();
+        let input = dvector![1.0, 2.0, 8.0, -1.0];
+        let array_input = ndarray::arr2(&[[1.0, 2.0], [8.0, -1.0]]).into_dyn();
+        let epsilon = dvector![0.5, -1.0, 2.0, 0.25];
+        let array_epsilon = ndarray::arr2(&[[0.5, -1.0], [2.0, 0.25]]).into_dyn();
+
+        let evaluated = layer.eval(&input);
+        let array_layer = NeuraArrayLayer::new(layer.clone());
+        let array_evaluated = array_layer.eval(&array_input);
+        assert_eq!(array_evaluated.shape(), &[2, 2]);
+        assert_relative_eq!(evaluated.as_slice(), array_evaluated.as_slice().unwrap());
+
+        let backprop = layer.backprop_layer(&input, &evaluated, &epsilon);
+        let array_backprop =
+            array_layer.backprop_layer(&array_input, &array_evaluated, &array_epsilon);
+        assert_relative_eq!(backprop.as_slice(), array_backprop.as_slice().unwrap());
+    }
+
     #[test]
     fn test_softmax_eval() {
         const EPSILON: f64 = 0.000002;
@@ -0,0 +1,117 @@
+use nalgebra::{DVector, Scalar};
+
+use super::*;
+use crate::algebra::NeuraTensor;
+
+/// A layer wrapper that feeds `NeuraTensor`s to a layer working on flat `DVector`s:
+/// the inputs must have the shape that the layer was constructed with,
+/// and the outputs are given the output shape of the wrappee.
+///
+/// The input shape is recorded when the wrapper is constructed as part of a network,
+/// or can be given upfront with `NeuraTensorLayer::with_shape`.
+#[derive(Clone, Debug)]
+pub struct NeuraTensorLayer<Layer> {
+    layer: Layer,
+    input_shape: Option<NeuraShape>,
+}
+
+impl<Layer> NeuraTensorLayer<Layer> {
+    /// Wraps `layer`, whose input shape will be known once the wrapper is constructed
+    pub fn new(layer: Layer) -> Self {
+        Self {
+            layer,
+            input_shape: None,
+        }
+    }
+
+    /// Wraps an already-constructed `layer`, which takes in tensors of shape `input_shape`
+    pub fn with_shape(layer: Layer, input_shape: impl Into<NeuraShape>) -> Self {
+        Self {
+            layer,
+            input_shape: Some(input_shape.into()),
+        }
+    }
+
+    pub fn get(&self) -> &Layer {
+        &self.layer
+    }
+
+    pub fn get_mut(&mut self) -> &mut Layer {
+        &mut self.layer
+    }
+
+    pub fn into_inner(self) -> Layer {
+        self.layer
+    }
+
+    fn input_shape(&self) -> NeuraShape {
+        self.input_shape.expect(
+            "NeuraTensorLayer should be constructed with an input shape before being evaluated",
+        )
+    }
+}
+
+impl<Layer: NeuraPartialLayer> NeuraPartialLayer for NeuraTensorLayer<Layer> {
+    type Constructed = NeuraTensorLayer<Layer::Constructed>;
+    type Err = Layer::Err;
+
+    fn construct(self, input_shape: NeuraShape) -> Result<Self::Constructed, Self::Err> {
+        Ok(NeuraTensorLayer {
+            layer: self.layer.construct(input_shape)?,
+            input_shape: Some(input_shape),
+        })
+    }
+}
+
+impl<Layer: NeuraLayerBase> NeuraLayerBase for NeuraTensorLayer<Layer> {
+    type Gradient = Layer::Gradient;
+
+    delegate_layer_base!(layer);
+}
+
+impl<F: Scalar, Layer: NeuraLayer<DVector<F>, Output = DVector<F>>> NeuraLayer<NeuraTensor<F>>
+    for NeuraTensorLayer<Layer>
+{
+    type Output = NeuraTensor<F>;
+    type IntermediaryRepr = Layer::IntermediaryRepr;
+
+    fn eval(&self, input: &NeuraTensor<F>) -> Self::Output {
+        input.assert_shape(self.input_shape());
+        let output = self.layer.eval(input.as_vector());
+
+        NeuraTensor::from_vector(output, self.layer.output_shape()).unwrap()
+    }
+
+    fn eval_training(&self, input: &NeuraTensor<F>) -> (Self::Output, Self::IntermediaryRepr) {
+        input.assert_shape(self.input_shape());
+        let (output, intermediary) = self.layer.eval_training(input.as_vector());
+
+        (
+            NeuraTensor::from_vector(output, self.layer.output_shape()).unwrap(),
+            intermediary,
+        )
+    }
+
+    fn get_gradient(
+        &self,
+        input: &NeuraTensor<F>,
+        intermediary: &Self::IntermediaryRepr,
+        epsilon: &Self::Output,
+    ) -> Self::Gradient {
+        self.layer
+            .get_gradient(input.as_vector(), intermediary, epsilon.as_vector())
+    }
+
+    fn backprop_layer(
+        &self,
+        input: &NeuraTensor<F>,
+        intermediary: &Self::IntermediaryRepr,
+        epsilon: &Self::Output,
+    ) -> NeuraTensor<F> {
+        let epsilon =
+            self.layer
+                .backprop_layer(input.as_vector(), intermediary, epsilon.as_vector());
+
+        NeuraTensor::from_vector(epsilon, input.shape()).unwrap()
+    }
+}
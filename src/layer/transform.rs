@@ -0,0 +1,83 @@
+use nalgebra::{DVector, Scalar};
+
+use super::*;
+use crate::{
+    err::NeuraDimensionsMismatch,
+    preprocess::{NeuraStandardScaler, NeuraTransform},
+};
+
+/// A frozen layer applying a fitted preprocessing transform (see `crate::preprocess`) to its input.
+///
+/// The transform has no trainable parameters, but the gradient is still backpropagated through it.
+#[derive(Clone, Debug)]
+pub struct NeuraTransformLayer<T> {
+    pub transform: T,
+    input_len: usize,
+    output_len: usize,
+}
+
+/// A layer standardizing its input, using a fitted `NeuraStandardScaler`
+pub type NeuraStandardizeLayer<F> = NeuraTransformLayer<NeuraStandardScaler<F>>;
+
+impl<T> NeuraTransformLayer<T> {
+    pub fn new<F: Scalar>(transform: T) -> Self
+    where
+        T: NeuraTransform<F>,
+    {
+        Self {
+            input_len: transform.input_len(),
+            output_len: transform.output_len(),
+            transform,
+        }
+    }
+}
+
+impl<T: std::fmt::Debug + Clone + 'static> NeuraPartialLayer for NeuraTransformLayer<T> {
+    type Constructed = Self;
+    type Err = NeuraDimensionsMismatch;
+
+    fn construct(self, input_shape: NeuraShape) -> Result<Self::Constructed, Self::Err> {
+        if input_shape != NeuraShape::Vector(self.input_len) {
+            return Err(NeuraDimensionsMismatch {
+                existing: self.input_len,
+                new: input_shape,
+            });
+        }
+
+        Ok(self)
+    }
+}
+
+impl<T: std::fmt::Debug + Clone + 'static> NeuraLayerBase for NeuraTransformLayer<T> {
+    type Gradient = ();
+
+    fn output_shape(&self) -> NeuraShape {
+        NeuraShape::Vector(self.output_len)
+    }
+
+    fn default_gradient(&self) -> Self::Gradient {}
+}
+
+impl<F: Scalar, T: NeuraTransform<F> + std::fmt::Debug + Clone + 'static> NeuraLayer<DVector<F>>
+    for NeuraTransformLayer<T>
+{
+    type Output = DVector<F>;
+    type IntermediaryRepr = ();
+
+    fn eval_training(&self, input: &DVector<F>) -> (Self::Output, Self::IntermediaryRepr) {
+        (self.transform.transform(input), ())
+    }
+
+    fn eval(&self, input: &DVector<F>) -> Self::Output {
+        self.transform.transform(input)
+    }
+
+    fn backprop_layer(
+        &self,
+        _input: &DVector<F>,
+        _intermediary: &Self::IntermediaryRepr,
+        epsilon: &Self::Output,
+    ) -> DVector<F> {
+        self.transform.backprop(epsilon)
+    }
+}
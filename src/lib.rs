@@ -1,10 +1,15 @@
 pub mod algebra;
+pub mod augment;
 pub mod axis;
+pub mod dataset;
 pub mod derivable;
 pub mod err;
 pub mod gradient_solver;
 pub mod layer;
+pub mod metrics;
 pub mod network;
+pub mod preprocess;
+pub mod rng;
 pub mod train;
 
 mod utils;
@@ -15,6 +20,9 @@ pub use utils::{argmax, cycle_shuffling, one_hot, plot_losses};
 #[cfg(feature = "visualization")]
 pub use utils::draw_neuron_activation;
 
+#[cfg(feature = "half")]
+pub use half;
+
 /// Common traits and structs that are useful to use this library.
 /// All of these traits are prefixed with the word "neura" in some way,
 /// so there should not be any conflicts when doing a wildcard import of `prelude`.
@@ -23,10 +31,13 @@ pub mod prelude {
     pub use crate::{neura_layer, neura_residual, neura_sequential};
 
     // Structs and traits
+    pub use crate::dataset::NeuraDataset;
     pub use crate::gradient_solver::NeuraBackprop;
     pub use crate::layer::{NeuraLayer, NeuraLayerBase, NeuraPartialLayer, NeuraShape};
     pub use crate::network::sequential::{
-        NeuraSequential, NeuraSequentialLock, NeuraSequentialTail,
+        NeuraSequential, NeuraSequentialLearningRate, NeuraSequentialLock, NeuraSequentialQuantize,
+        NeuraSequentialTail,
     };
+    pub use crate::network::{NeuraPruneNeurons, NeuraSelectiveLock, NeuraSelectiveLockable};
     pub use crate::train::NeuraBatchedTrainer;
 }
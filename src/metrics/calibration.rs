@@ -0,0 +1,212 @@
+use std::fmt::{self, Display};
+
+use nalgebra::{DVector, Scalar};
+use num::Float;
+
+use crate::{argmax, dataset::NeuraDataset, layer::NeuraLayer};
+
+/// A bin of a reliability curve, see `NeuraCalibration`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct NeuraCalibrationBin {
+    /// How many samples had their confidence fall in this bin
+    pub count: usize,
+    confidence_sum: f64,
+    correct: usize,
+}
+
+impl NeuraCalibrationBin {
+    /// The average confidence of the samples in this bin
+    pub fn confidence(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.confidence_sum / self.count as f64
+        }
+    }
+
+    /// The fraction of correctly classified samples in this bin
+    pub fn accuracy(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.correct as f64 / self.count as f64
+        }
+    }
+}
+
+/// Measures how well the probabilities outputted by a classifier (like one ending with a `NeuraSoftmaxLayer`)
+/// match its actual accuracy.
+///
+/// Samples are grouped in equal-width bins by their confidence (the highest output of the network);
+/// for a well-calibrated network, the accuracy within each bin matches its average confidence.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NeuraCalibration {
+    bins: Vec<NeuraCalibrationBin>,
+}
+
+impl NeuraCalibration {
+    pub fn new(bins: usize) -> Self {
+        assert!(bins > 0, "NeuraCalibration needs at least one bin");
+
+        Self {
+            bins: vec![NeuraCalibrationBin::default(); bins],
+        }
+    }
+
+    /// Evaluates `network` on every pair of `test_inputs`, grouping the samples in `bins` bins.
+    pub fn evaluate<
+        F: Float + Scalar,
+        Input,
+        Network: NeuraLayer<Input, Output = DVector<F>>,
+        TestInputs: NeuraDataset<Input = Input, Target = DVector<F>> + ?Sized,
+    >(
+        network: &Network,
+        test_inputs: &TestInputs,
+        bins: usize,
+    ) -> Self {
+        let mut res = Self::new(bins);
+
+        for (input, target) in test_inputs.pairs() {
+            res.add(&network.eval(&input), &target);
+        }
+
+        res
+    }
+
+    /// Records the output of the network for a single sample
+    pub fn add<F: Float + Scalar>(&mut self, output: &DVector<F>, target: &DVector<F>) {
+        let predicted = argmax(output.as_slice());
+        let confidence = output[predicted].to_f64().unwrap().clamp(0.0, 1.0);
+        let index = ((confidence * self.bins.len() as f64) as usize).min(self.bins.len() - 1);
+
+        let bin = &mut self.bins[index];
+        bin.count += 1;
+        bin.confidence_sum += confidence;
+        if predicted == argmax(target.as_slice()) {
+            bin.correct += 1;
+        }
+    }
+
+    /// Returns the bins of the reliability curve, with the `i`-th bin covering the confidences in `[i / n; (i + 1) / n[`
+    pub fn bins(&self) -> &[NeuraCalibrationBin] {
+        &self.bins
+    }
+
+    /// Returns the number of recorded samples
+    pub fn len(&self) -> usize {
+        self.bins.iter().map(|bin| bin.count).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The reliability curve, as `(average confidence, accuracy)` pairs for each non-empty bin
+    pub fn curve(&self) -> Vec<(f64, f64)> {
+        self.bins
+            .iter()
+            .filter(|bin| bin.count > 0)
+            .map(|bin| (bin.confidence(), bin.accuracy()))
+            .collect()
+    }
+
+    /// The expected calibration error: the average gap between confidence and accuracy, weighted by the size of each bin
+    pub fn expected_calibration_error(&self) -> f64 {
+        let total = self.len();
+        if total == 0 {
+            return 0.0;
+        }
+
+        self.bins
+            .iter()
+            .map(|bin| bin.count as f64 * (bin.accuracy() - bin.confidence()).abs())
+            .sum::<f64>()
+            / total as f64
+    }
+
+    /// The maximum calibration error: the largest gap between confidence and accuracy among the non-empty bins
+    pub fn max_calibration_error(&self) -> f64 {
+        self.bins
+            .iter()
+            .filter(|bin| bin.count > 0)
+            .map(|bin| (bin.accuracy() - bin.confidence()).abs())
+            .fold(0.0, f64::max)
+    }
+}
+
+impl Display for NeuraCalibration {
+    /// Prints the reliability curve as a bar chart of the accuracy of each bin, with `|` marking its average confidence
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const WIDTH: usize = 40;
+        let bins = self.bins.len() as f64;
+
+        writeln!(f, "  confidence │ accuracy")?;
+        for (i, bin) in self.bins.iter().enumerate() {
+            write!(
+                f,
+                " {:.2} - {:.2} │",
+                i as f64 / bins,
+                (i + 1) as f64 / bins
+            )?;
+
+            if bin.count > 0 {
+                let filled = (bin.accuracy() * WIDTH as f64).round() as usize;
+                let marker = ((bin.confidence() * WIDTH as f64).round() as usize).min(WIDTH);
+                let bar: String = (0..=WIDTH)
+                    .map(|x| match (x == marker, x < filled) {
+                        (true, _) => '|',
+                        (false, true) => '█',
+                        (false, false) => ' ',
+                    })
+                    .collect();
+                write!(f, "{} {:.3} ({})", bar, bin.accuracy(), bin.count)?;
+            }
+            writeln!(f)?;
+        }
+
+        write!(
+            f,
+            "ECE: {:.4}, MCE: {:.4}",
+            self.expected_calibration_error(),
+            self.max_calibration_error()
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::dvector;
+
+    use super::*;
+
+    #[test]
+    fn test_calibration() {
+        let mut calibration = NeuraCalibration::new(5);
+
+        // Two confident samples, one of them is wrong
+        calibration.add(&dvector![0.9, 0.1], &dvector![1.0, 0.0]);
+        calibration.add(&dvector![0.1, 0.9], &dvector![1.0, 0.0]);
+        // Two unsure samples, both are right
+        calibration.add(&dvector![0.5, 0.5], &dvector![1.0, 0.0]);
+        calibration.add(&dvector![0.45, 0.55], &dvector![0.0, 1.0]);
+
+        assert_eq!(calibration.len(), 4);
+        assert_eq!(calibration.bins()[4].count, 2);
+        assert_eq!(calibration.bins()[2].count, 2);
+
+        let curve = calibration.curve();
+        assert_eq!(curve.len(), 2);
+        assert_relative_eq!(curve[0].0, 0.525);
+        assert_relative_eq!(curve[0].1, 1.0);
+        assert_relative_eq!(curve[1].0, 0.9);
+        assert_relative_eq!(curve[1].1, 0.5);
+
+        assert_relative_eq!(
+            calibration.expected_calibration_error(),
+            (2.0 * 0.475 + 2.0 * 0.4) / 4.0
+        );
+        assert_relative_eq!(calibration.max_calibration_error(), 0.475);
+        assert!(calibration.to_string().contains("ECE: 0.4375"));
+    }
+}
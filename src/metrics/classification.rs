@@ -0,0 +1,402 @@
+use std::fmt::{self, Display};
+
+use nalgebra::{DMatrix, DVector, Scalar};
+use num::Float;
+
+use crate::{argmax, dataset::NeuraDataset, layer::NeuraLayer};
+
+/// Classification metrics of a network, computed from its outputs on a test set.
+///
+/// Both the outputs of the network and the targets are expected to be vectors with one entry per class,
+/// with the predicted class being the `argmax` of the output, and the actual class the `argmax` of the (usually one-hot) target.
+///
+/// ```
+/// use neuramethyst::metrics::NeuraClassificationMetrics;
+/// use nalgebra::dvector;
+///
+/// let mut metrics = NeuraClassificationMetrics::new(3);
+/// metrics.add(&dvector![0.7, 0.2, 0.1], &dvector![1.0, 0.0, 0.0]);
+/// metrics.add(&dvector![0.1, 0.3, 0.6], &dvector![0.0, 1.0, 0.0]);
+///
+/// assert_eq!(metrics.accuracy(), 0.5);
+/// assert_eq!(metrics.top_k_accuracy(2), 1.0);
+/// println!("{}", metrics);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct NeuraClassificationMetrics {
+    /// `confusion[(actual, predicted)]` counts how many inputs of class `actual` were classified as `predicted`
+    confusion: DMatrix<usize>,
+
+    /// For each sample, how many classes were ranked strictly before the actual class
+    ranks: Vec<usize>,
+
+    class_names: Option<Vec<String>>,
+}
+
+impl NeuraClassificationMetrics {
+    pub fn new(classes: usize) -> Self {
+        Self {
+            confusion: DMatrix::zeros(classes, classes),
+            ranks: Vec::new(),
+            class_names: None,
+        }
+    }
+
+    /// Sets the names of the classes, used when printing the metrics (eg. `NeuraCsvDataset::classes`)
+    pub fn class_names(mut self, class_names: Vec<String>) -> Self {
+        assert_eq!(
+            class_names.len(),
+            self.classes(),
+            "Expected {} class names",
+            self.classes()
+        );
+        self.class_names = Some(class_names);
+        self
+    }
+
+    /// Evaluates `network` on every pair of `test_inputs`.
+    /// The number of classes is given by the length of the first output.
+    pub fn evaluate<
+        F: Float + Scalar,
+        Input,
+        Network: NeuraLayer<Input, Output = DVector<F>>,
+        TestInputs: NeuraDataset<Input = Input, Target = DVector<F>> + ?Sized,
+    >(
+        network: &Network,
+        test_inputs: &TestInputs,
+    ) -> Self {
+        let mut res: Option<Self> = None;
+
+        for (input, target) in test_inputs.pairs() {
+            let output = network.eval(&input);
+            res.get_or_insert_with(|| Self::new(output.len()))
+                .add(&output, &target);
+        }
+
+        res.unwrap_or_else(|| Self::new(0))
+    }
+
+    /// Records the output of the network for a single sample
+    pub fn add<F: Float + Scalar>(&mut self, output: &DVector<F>, target: &DVector<F>) {
+        assert_eq!(
+            output.len(),
+            self.classes(),
+            "Output has a different number of classes than the metrics"
+        );
+        assert_eq!(
+            target.len(),
+            self.classes(),
+            "Target has a different number of classes than the metrics"
+        );
+
+        let predicted = argmax(output.as_slice());
+        let actual = argmax(target.as_slice());
+        self.confusion[(actual, predicted)] += 1;
+
+        // Ties are broken the same way as `argmax`, in favor of the lowest index
+        let rank = (0..output.len())
+            .filter(|&i| output[i] > output[actual] || (output[i] == output[actual] && i < actual))
+            .count();
+        self.ranks.push(rank);
+    }
+
+    pub fn classes(&self) -> usize {
+        self.confusion.nrows()
+    }
+
+    /// Returns the number of recorded samples
+    pub fn len(&self) -> usize {
+        self.ranks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranks.is_empty()
+    }
+
+    /// Returns the confusion matrix, where the entry at `(actual, predicted)` counts
+    /// how many samples of class `actual` were classified as `predicted`
+    pub fn confusion_matrix(&self) -> &DMatrix<usize> {
+        &self.confusion
+    }
+
+    /// The fraction of samples that were correctly classified
+    pub fn accuracy(&self) -> f64 {
+        ratio(self.confusion.diagonal().sum(), self.len())
+    }
+
+    /// The fraction of samples whose actual class was among the `k` highest outputs of the network
+    pub fn top_k_accuracy(&self, k: usize) -> f64 {
+        ratio(
+            self.ranks.iter().filter(|&&rank| rank < k).count(),
+            self.len(),
+        )
+    }
+
+    /// The fraction of samples classified as `class` that actually belong to `class`
+    pub fn precision(&self, class: usize) -> f64 {
+        ratio(
+            self.confusion[(class, class)],
+            self.confusion.column(class).sum(),
+        )
+    }
+
+    /// The fraction of samples of class `class` that were classified as such
+    pub fn recall(&self, class: usize) -> f64 {
+        ratio(
+            self.confusion[(class, class)],
+            self.confusion.row(class).sum(),
+        )
+    }
+
+    /// The harmonic mean of `precision(class)` and `recall(class)`
+    pub fn f1(&self, class: usize) -> f64 {
+        f1(self.precision(class), self.recall(class))
+    }
+
+    /// The unweighted mean of the precision of each class
+    pub fn macro_precision(&self) -> f64 {
+        self.class_mean(|class| self.precision(class))
+    }
+
+    /// The unweighted mean of the recall of each class
+    pub fn macro_recall(&self) -> f64 {
+        self.class_mean(|class| self.recall(class))
+    }
+
+    /// The unweighted mean of the F1 score of each class
+    pub fn macro_f1(&self) -> f64 {
+        self.class_mean(|class| self.f1(class))
+    }
+
+    /// The precision computed over the true and false positives of every class.
+    ///
+    /// Since each sample is assigned exactly one class, the micro-averaged precision, recall and F1 score
+    /// are all equal to the accuracy.
+    pub fn micro_precision(&self) -> f64 {
+        self.accuracy()
+    }
+
+    pub fn micro_recall(&self) -> f64 {
+        self.accuracy()
+    }
+
+    pub fn micro_f1(&self) -> f64 {
+        f1(self.micro_precision(), self.micro_recall())
+    }
+
+    fn class_mean(&self, metric: impl Fn(usize) -> f64) -> f64 {
+        ratio_f64((0..self.classes()).map(metric).sum(), self.classes() as f64)
+    }
+
+    fn class_name(&self, class: usize) -> String {
+        match &self.class_names {
+            Some(names) => names[class].clone(),
+            None => class.to_string(),
+        }
+    }
+}
+
+#[inline(always)]
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    ratio_f64(numerator as f64, denominator as f64)
+}
+
+#[inline(always)]
+fn ratio_f64(numerator: f64, denominator: f64) -> f64 {
+    if denominator == 0.0 {
+        0.0
+    } else {
+        numerator / denominator
+    }
+}
+
+#[inline(always)]
+fn f1(precision: f64, recall: f64) -> f64 {
+    ratio_f64(2.0 * precision * recall, precision + recall)
+}
+
+/// Shades used to draw the confusion matrix, from lowest to highest row-wise frequency
+const SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+impl Display for NeuraClassificationMetrics {
+    /// Prints the confusion matrix, shaded by the fraction of each actual class that went in each cell,
+    /// followed by the per-class and averaged metrics.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<String> = (0..self.classes()).map(|i| self.class_name(i)).collect();
+        let name_width = names
+            .iter()
+            .map(|name| name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(6);
+        let count_width = self
+            .confusion
+            .iter()
+            .map(|count| count.to_string().len())
+            .max()
+            .unwrap_or(1)
+            .max(
+                names
+                    .iter()
+                    .map(|name| name.chars().count())
+                    .max()
+                    .unwrap_or(0),
+            )
+            + 1;
+
+        writeln!(f, "{:>name_width$} │ predicted", "actual")?;
+        write!(f, "{:>name_width$} │", "")?;
+        for name in names.iter() {
+            write!(f, " {:>count_width$}", name)?;
+        }
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{}┼{}",
+            "─".repeat(name_width + 1),
+            "─".repeat((count_width + 1) * self.classes() + 1)
+        )?;
+
+        for (actual, name) in names.iter().enumerate() {
+            let total = self.confusion.row(actual).sum();
+            write!(f, "{:>name_width$} │", name)?;
+
+            for predicted in 0..self.classes() {
+                let count = self.confusion[(actual, predicted)];
+                let shade = if count == 0 {
+                    SHADES[0]
+                } else {
+                    let fraction = ratio(count, total);
+                    SHADES[1
+                        + ((fraction * (SHADES.len() - 1) as f64) as usize).min(SHADES.len() - 2)]
+                };
+                write!(f, "{}{:>count_width$}", shade, count)?;
+            }
+            writeln!(f)?;
+        }
+
+        writeln!(f)?;
+        writeln!(
+            f,
+            "{:>name_width$}  {:>9}  {:>9}  {:>9}  {:>7}",
+            "class", "precision", "recall", "f1", "support"
+        )?;
+        for (class, name) in names.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>name_width$}  {:>9.3}  {:>9.3}  {:>9.3}  {:>7}",
+                name,
+                self.precision(class),
+                self.recall(class),
+                self.f1(class),
+                self.confusion.row(class).sum()
+            )?;
+        }
+        writeln!(
+            f,
+            "{:>name_width$}  {:>9.3}  {:>9.3}  {:>9.3}  {:>7}",
+            "macro",
+            self.macro_precision(),
+            self.macro_recall(),
+            self.macro_f1(),
+            self.len()
+        )?;
+        writeln!(
+            f,
+            "{:>name_width$}  {:>9.3}  {:>9.3}  {:>9.3}  {:>7}",
+            "micro",
+            self.micro_precision(),
+            self.micro_recall(),
+            self.micro_f1(),
+            self.len()
+        )?;
+        write!(f, "Accuracy: {:.2}%", self.accuracy() * 100.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::dvector;
+
+    use super::*;
+    use crate::{derivable::activation::Linear, one_hot, prelude::*};
+
+    fn metrics() -> NeuraClassificationMetrics {
+        let mut metrics = NeuraClassificationMetrics::new(3);
+
+        // (output, actual class)
+        let samples = [
+            (dvector![0.8, 0.1, 0.1], 0),
+            (dvector![0.6, 0.3, 0.1], 0),
+            (dvector![0.2, 0.5, 0.3], 0),
+            (dvector![0.1, 0.7, 0.2], 1),
+            (dvector![0.5, 0.4, 0.1], 1),
+            (dvector![0.1, 0.2, 0.7], 2),
+        ];
+
+        for (output, class) in samples {
+            metrics.add(&output.cast::<f32>(), &one_hot(class, 3));
+        }
+
+        metrics
+    }
+
+    #[test]
+    fn test_classification_metrics() {
+        let metrics = metrics();
+
+        assert_eq!(
+            metrics.confusion_matrix(),
+            &DMatrix::from_row_slice(3, 3, &[2, 1, 0, 1, 1, 0, 0, 0, 1])
+        );
+        assert_eq!(metrics.len(), 6);
+        assert_relative_eq!(metrics.accuracy(), 4.0 / 6.0);
+        assert_relative_eq!(metrics.top_k_accuracy(1), 4.0 / 6.0);
+        assert_relative_eq!(metrics.top_k_accuracy(2), 5.0 / 6.0);
+        assert_relative_eq!(metrics.top_k_accuracy(3), 1.0);
+
+        assert_relative_eq!(metrics.precision(0), 2.0 / 3.0);
+        assert_relative_eq!(metrics.recall(0), 2.0 / 3.0);
+        assert_relative_eq!(metrics.precision(1), 0.5);
+        assert_relative_eq!(metrics.recall(1), 0.5);
+        assert_relative_eq!(metrics.f1(2), 1.0);
+
+        assert_relative_eq!(metrics.macro_precision(), (2.0 / 3.0 + 0.5 + 1.0) / 3.0);
+        assert_relative_eq!(metrics.macro_f1(), (2.0 / 3.0 + 0.5 + 1.0) / 3.0);
+        assert_relative_eq!(metrics.micro_f1(), 4.0 / 6.0);
+
+        // Classes that are never predicted nor present don't produce NaNs
+        let empty = NeuraClassificationMetrics::new(2);
+        assert_eq!(empty.accuracy(), 0.0);
+        assert_eq!(empty.macro_f1(), 0.0);
+    }
+
+    #[test]
+    fn test_classification_display() {
+        let metrics = metrics().class_names(vec!["cat".into(), "dog".into(), "fish".into()]);
+        let display = metrics.to_string();
+
+        assert!(display.contains("fish"));
+        assert!(display.contains("Accuracy: 66.67%"));
+        // Cells are shaded by the fraction of their row
+        assert!(display.contains("▓    2"));
+        assert!(display.contains("█    1"));
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let network = neura_sequential![neura_layer!("dense", 2).activation(Linear)]
+            .construct(NeuraShape::Vector(2))
+            .unwrap();
+        let test_inputs = vec![
+            (dvector![1.0, 0.0], dvector![1.0, 0.0]),
+            (dvector![0.0, 1.0], dvector![0.0, 1.0]),
+        ];
+
+        let metrics = NeuraClassificationMetrics::evaluate(&network, &test_inputs);
+        assert_eq!(metrics.classes(), 2);
+        assert_eq!(metrics.len(), 2);
+        assert_eq!(metrics.confusion_matrix().sum(), 2);
+    }
+}
@@ -0,0 +1,210 @@
+//! Metrics to evaluate a trained network on a test set.
+
+use nalgebra::{DVector, Scalar};
+use num::Float;
+
+use crate::{dataset::NeuraDataset, layer::NeuraLayer};
+
+mod calibration;
+pub use calibration::*;
+
+mod classification;
+pub use classification::*;
+
+mod quantization;
+pub use quantization::*;
+
+mod regression;
+pub use regression::*;
+
+/// A metric that `NeuraBatchedTrainer::train_with_metrics` and `NeuraBatchedTrainer::train_epochs_with_metrics`
+/// can log alongside the validation loss.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NeuraMetric {
+    /// See `NeuraClassificationMetrics::accuracy`
+    Accuracy,
+    /// See `NeuraClassificationMetrics::top_k_accuracy`
+    TopK(usize),
+    /// See `NeuraClassificationMetrics::macro_precision`
+    MacroPrecision,
+    /// See `NeuraClassificationMetrics::macro_recall`
+    MacroRecall,
+    /// See `NeuraClassificationMetrics::macro_f1`
+    MacroF1,
+    /// See `NeuraClassificationMetrics::micro_f1`
+    MicroF1,
+    /// See `NeuraRegressionMetrics::r2`
+    R2,
+    /// See `NeuraRegressionMetrics::mae`
+    Mae,
+    /// See `NeuraRegressionMetrics::rmse`
+    Rmse,
+    /// See `NeuraRegressionMetrics::explained_variance`
+    ExplainedVariance,
+    /// The expected calibration error, with the given number of bins, see `NeuraCalibration`
+    Ece(usize),
+}
+
+impl NeuraMetric {
+    pub fn name(&self) -> String {
+        match self {
+            Self::Accuracy => String::from("Accuracy"),
+            Self::TopK(k) => format!("Top-{} accuracy", k),
+            Self::MacroPrecision => String::from("Macro precision"),
+            Self::MacroRecall => String::from("Macro recall"),
+            Self::MacroF1 => String::from("Macro F1"),
+            Self::MicroF1 => String::from("Micro F1"),
+            Self::R2 => String::from("R²"),
+            Self::Mae => String::from("MAE"),
+            Self::Rmse => String::from("RMSE"),
+            Self::ExplainedVariance => String::from("Explained variance"),
+            Self::Ece(_) => String::from("ECE"),
+        }
+    }
+}
+
+/// The metrics of a network over a test set, as computed by `evaluate_metrics`.
+///
+/// Only the families of metrics needed by the requested `NeuraMetric`s are computed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NeuraMetrics {
+    pub classification: Option<NeuraClassificationMetrics>,
+    pub regression: Option<NeuraRegressionMetrics>,
+    /// The calibration of the network for each requested number of bins
+    pub calibration: Vec<NeuraCalibration>,
+}
+
+impl NeuraMetrics {
+    /// Returns the value of `metric`, or `None` if it wasn't computed
+    pub fn get(&self, metric: NeuraMetric) -> Option<f64> {
+        use NeuraMetric::*;
+
+        match metric {
+            Accuracy | TopK(_) | MacroPrecision | MacroRecall | MacroF1 | MicroF1 => {
+                let classification = self.classification.as_ref()?;
+
+                Some(match metric {
+                    Accuracy => classification.accuracy(),
+                    TopK(k) => classification.top_k_accuracy(k),
+                    MacroPrecision => classification.macro_precision(),
+                    MacroRecall => classification.macro_recall(),
+                    MacroF1 => classification.macro_f1(),
+                    _ => classification.micro_f1(),
+                })
+            }
+            R2 | Mae | Rmse | ExplainedVariance => {
+                let regression = self.regression.as_ref()?;
+
+                Some(match metric {
+                    R2 => regression.r2(),
+                    Mae => regression.mae(),
+                    Rmse => regression.rmse(),
+                    _ => regression.explained_variance(),
+                })
+            }
+            Ece(bins) => self
+                .calibration
+                .iter()
+                .find(|calibration| calibration.bins().len() == bins)
+                .map(|calibration| calibration.expected_calibration_error()),
+        }
+    }
+}
+
+/// Evaluates `network` over `test_inputs`, computing everything needed for `metrics` in a single pass.
+pub fn evaluate_metrics<
+    F: Float + Scalar,
+    Input,
+    Network: NeuraLayer<Input, Output = DVector<F>>,
+    TestInputs: NeuraDataset<Input = Input, Target = DVector<F>> + ?Sized,
+>(
+    metrics: &[NeuraMetric],
+    network: &Network,
+    test_inputs: &TestInputs,
+) -> NeuraMetrics {
+    use NeuraMetric::*;
+
+    let needs_classification = metrics.iter().any(|metric| {
+        matches!(
+            metric,
+            Accuracy | TopK(_) | MacroPrecision | MacroRecall | MacroF1 | MicroF1
+        )
+    });
+    let needs_regression = metrics
+        .iter()
+        .any(|metric| matches!(metric, R2 | Mae | Rmse | ExplainedVariance));
+
+    let mut res = NeuraMetrics::default();
+    for metric in metrics {
+        if let Ece(bins) = *metric {
+            if res.get(*metric).is_none() {
+                res.calibration.push(NeuraCalibration::new(bins));
+            }
+        }
+    }
+
+    if !needs_classification && !needs_regression && res.calibration.is_empty() {
+        return res;
+    }
+
+    for (input, target) in test_inputs.pairs() {
+        let output = network.eval(&input);
+
+        if needs_classification {
+            res.classification
+                .get_or_insert_with(|| NeuraClassificationMetrics::new(output.len()))
+                .add(&output, &target);
+        }
+
+        if needs_regression {
+            res.regression
+                .get_or_insert_with(|| NeuraRegressionMetrics::new(output.len()))
+                .add(&output, &target);
+        }
+
+        for calibration in res.calibration.iter_mut() {
+            calibration.add(&output, &target);
+        }
+    }
+
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::{dmatrix, dvector};
+
+    use super::*;
+    use crate::{
+        derivable::{activation::Linear, regularize::NeuraL0},
+        layer::dense::NeuraDenseLayer,
+    };
+
+    #[test]
+    fn test_evaluate_metrics() {
+        let network = NeuraDenseLayer::new(
+            dmatrix![1.0, 0.0; 0.0, 1.0],
+            dvector![0.0, 0.0],
+            Linear,
+            NeuraL0,
+        );
+        let test_inputs = vec![
+            (dvector![0.9, 0.1], dvector![1.0, 0.0]),
+            (dvector![0.3, 0.7], dvector![1.0, 0.0]),
+        ];
+
+        let metrics = evaluate_metrics(
+            &[NeuraMetric::Accuracy, NeuraMetric::Mae, NeuraMetric::Ece(2)],
+            &network,
+            &test_inputs,
+        );
+
+        assert_eq!(metrics.get(NeuraMetric::Accuracy), Some(0.5));
+        assert_relative_eq!(metrics.get(NeuraMetric::Mae).unwrap(), 0.4);
+        assert!(metrics.get(NeuraMetric::Ece(2)).is_some());
+        assert_eq!(metrics.get(NeuraMetric::R2), Some(0.0));
+        assert_eq!(metrics.get(NeuraMetric::Ece(10)), None);
+        assert_eq!(metrics.calibration.len(), 1);
+    }
+}
@@ -0,0 +1,164 @@
+use std::fmt::{self, Display};
+
+use nalgebra::DVector;
+
+use crate::{argmax, dataset::NeuraDataset, layer::NeuraLayer};
+
+/// Compares a quantized network against the network it was quantized from, see `NeuraSequentialQuantize`.
+///
+/// The accuracies treat both networks as classifiers, like `NeuraClassificationMetrics`;
+/// the output errors are meaningful for any kind of network.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NeuraQuantizationReport {
+    /// The number of evaluated samples
+    pub samples: usize,
+
+    /// The accuracy of the original network
+    pub float_accuracy: f64,
+
+    /// The accuracy of the quantized network
+    pub quantized_accuracy: f64,
+
+    /// The fraction of samples for which both networks predicted the same class
+    pub agreement: f64,
+
+    /// The mean absolute difference between the outputs of both networks, over every output entry
+    pub mean_abs_error: f64,
+
+    /// The largest absolute difference between the outputs of both networks
+    pub max_abs_error: f64,
+}
+
+impl NeuraQuantizationReport {
+    /// Evaluates `float_network` and `quantized_network` on every pair of `test_inputs`
+    pub fn evaluate<
+        Input,
+        FloatNetwork: NeuraLayer<Input, Output = DVector<f32>>,
+        QuantizedNetwork: NeuraLayer<Input, Output = DVector<f32>>,
+        TestInputs: NeuraDataset<Input = Input, Target = DVector<f32>> + ?Sized,
+    >(
+        float_network: &FloatNetwork,
+        quantized_network: &QuantizedNetwork,
+        test_inputs: &TestInputs,
+    ) -> Self {
+        let mut samples = 0;
+        let mut float_correct = 0;
+        let mut quantized_correct = 0;
+        let mut agreeing = 0;
+        let mut entries = 0;
+        let mut abs_error_sum = 0.0;
+        let mut max_abs_error = 0.0f64;
+
+        for (input, target) in test_inputs.pairs() {
+            let float_output = float_network.eval(&input);
+            let quantized_output = quantized_network.eval(&input);
+
+            let actual = argmax(target.as_slice());
+            let float_predicted = argmax(float_output.as_slice());
+            let quantized_predicted = argmax(quantized_output.as_slice());
+
+            samples += 1;
+            float_correct += (float_predicted == actual) as usize;
+            quantized_correct += (quantized_predicted == actual) as usize;
+            agreeing += (float_predicted == quantized_predicted) as usize;
+
+            for (x, y) in float_output.iter().zip(quantized_output.iter()) {
+                let error = (x - y).abs() as f64;
+                entries += 1;
+                abs_error_sum += error;
+                max_abs_error = max_abs_error.max(error);
+            }
+        }
+
+        let ratio = |count: usize, total: usize| {
+            if total == 0 {
+                0.0
+            } else {
+                count as f64 / total as f64
+            }
+        };
+
+        Self {
+            samples,
+            float_accuracy: ratio(float_correct, samples),
+            quantized_accuracy: ratio(quantized_correct, samples),
+            agreement: ratio(agreeing, samples),
+            mean_abs_error: if entries == 0 {
+                0.0
+            } else {
+                abs_error_sum / entries as f64
+            },
+            max_abs_error,
+        }
+    }
+
+    /// The change in accuracy caused by the quantization, negative if the quantized network is less accurate
+    pub fn accuracy_delta(&self) -> f64 {
+        self.quantized_accuracy - self.float_accuracy
+    }
+}
+
+impl Display for NeuraQuantizationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Samples:            {}", self.samples)?;
+        writeln!(f, "Float accuracy:     {:.2}%", self.float_accuracy * 100.0)?;
+        writeln!(
+            f,
+            "Quantized accuracy: {:.2}% ({:+.2}%)",
+            self.quantized_accuracy * 100.0,
+            self.accuracy_delta() * 100.0
+        )?;
+        writeln!(f, "Agreement:          {:.2}%", self.agreement * 100.0)?;
+        write!(
+            f,
+            "Output error:       {:.4} mean, {:.4} max",
+            self.mean_abs_error, self.max_abs_error
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::{
+        derivable::{activation::Relu, regularize::NeuraL0},
+        layer::{dense::NeuraDenseLayer, softmax::NeuraSoftmaxLayer},
+        network::sequential::NeuraSequentialQuantize,
+        neura_sequential, one_hot,
+    };
+
+    #[test]
+    fn test_quantization_report() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let network = neura_sequential![
+            NeuraDenseLayer::from_rng(4, 8, &mut rng, Relu, NeuraL0) as NeuraDenseLayer<f32, _, _>,
+            NeuraDenseLayer::from_rng(8, 3, &mut rng, Relu, NeuraL0) as NeuraDenseLayer<f32, _, _>,
+            NeuraSoftmaxLayer::new()
+        ];
+        let inputs: Vec<DVector<f32>> = (0..200)
+            .map(|_| DVector::from_fn(4, |_, _| rng.gen_range(-1.0..1.0)))
+            .collect();
+        // Label the inputs with the predictions of the original network, so that its accuracy is 1
+        let dataset: Vec<_> = inputs
+            .iter()
+            .map(|input| {
+                let class = argmax(network.eval(input).as_slice());
+                (input.clone(), one_hot(class, 3))
+            })
+            .collect();
+
+        let quantized = network.quantize(inputs.iter().take(100).cloned());
+        let report = NeuraQuantizationReport::evaluate(&network, &quantized, &dataset);
+
+        assert_eq!(report.samples, 200);
+        assert_relative_eq!(report.float_accuracy, 1.0);
+        assert_relative_eq!(report.accuracy_delta(), report.agreement - 1.0);
+        assert!(report.agreement > 0.9);
+        assert!(report.max_abs_error < 0.05);
+        assert!(report.to_string().contains("Float accuracy:     100.00%"));
+    }
+}
@@ -0,0 +1,231 @@
+use std::fmt::{self, Display};
+
+use nalgebra::{DVector, Scalar};
+use num::Float;
+
+use crate::{dataset::NeuraDataset, layer::NeuraLayer};
+
+/// Regression metrics of a network, computed from its outputs on a test set.
+///
+/// The metrics are accumulated one sample at a time, without storing the outputs;
+/// variances are computed with Welford's online algorithm, so that constant targets have a variance of exactly zero.
+/// For networks with several outputs, `r2` and `explained_variance` are averaged over the outputs,
+/// while `mae` and `rmse` are computed over all of the entries.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NeuraRegressionMetrics {
+    count: usize,
+    target_mean: DVector<f64>,
+    target_m2: DVector<f64>,
+    error_mean: DVector<f64>,
+    error_m2: DVector<f64>,
+    error_squared_sum: DVector<f64>,
+    absolute_error_sum: DVector<f64>,
+}
+
+impl NeuraRegressionMetrics {
+    pub fn new(outputs: usize) -> Self {
+        Self {
+            count: 0,
+            target_mean: DVector::zeros(outputs),
+            target_m2: DVector::zeros(outputs),
+            error_mean: DVector::zeros(outputs),
+            error_m2: DVector::zeros(outputs),
+            error_squared_sum: DVector::zeros(outputs),
+            absolute_error_sum: DVector::zeros(outputs),
+        }
+    }
+
+    /// Evaluates `network` on every pair of `test_inputs`.
+    /// The number of outputs is given by the length of the first output.
+    pub fn evaluate<
+        F: Float + Scalar,
+        Input,
+        Network: NeuraLayer<Input, Output = DVector<F>>,
+        TestInputs: NeuraDataset<Input = Input, Target = DVector<F>> + ?Sized,
+    >(
+        network: &Network,
+        test_inputs: &TestInputs,
+    ) -> Self {
+        let mut res: Option<Self> = None;
+
+        for (input, target) in test_inputs.pairs() {
+            let output = network.eval(&input);
+            res.get_or_insert_with(|| Self::new(output.len()))
+                .add(&output, &target);
+        }
+
+        res.unwrap_or_else(|| Self::new(0))
+    }
+
+    /// Records the output of the network for a single sample
+    pub fn add<F: Float + Scalar>(&mut self, output: &DVector<F>, target: &DVector<F>) {
+        assert_eq!(
+            output.len(),
+            self.outputs(),
+            "Output has a different length than the metrics"
+        );
+        assert_eq!(
+            target.len(),
+            self.outputs(),
+            "Target has a different length than the metrics"
+        );
+
+        self.count += 1;
+        let count = self.count as f64;
+
+        for i in 0..self.outputs() {
+            let target = target[i].to_f64().unwrap();
+            let error = output[i].to_f64().unwrap() - target;
+
+            // Welford's online algorithm
+            let delta = target - self.target_mean[i];
+            self.target_mean[i] += delta / count;
+            self.target_m2[i] += delta * (target - self.target_mean[i]);
+
+            let delta = error - self.error_mean[i];
+            self.error_mean[i] += delta / count;
+            self.error_m2[i] += delta * (error - self.error_mean[i]);
+
+            self.error_squared_sum[i] += error * error;
+            self.absolute_error_sum[i] += error.abs();
+        }
+    }
+
+    pub fn outputs(&self) -> usize {
+        self.target_mean.len()
+    }
+
+    /// Returns the number of recorded samples
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// The mean absolute error
+    pub fn mae(&self) -> f64 {
+        self.entry_mean(self.absolute_error_sum.sum())
+    }
+
+    /// The mean squared error
+    pub fn mse(&self) -> f64 {
+        self.entry_mean(self.error_squared_sum.sum())
+    }
+
+    /// The root of the mean squared error
+    pub fn rmse(&self) -> f64 {
+        self.mse().sqrt()
+    }
+
+    /// The coefficient of determination, `1 - SS_res / SS_tot`, averaged over the outputs.
+    ///
+    /// A perfect model has an R² of `1`, and a model always predicting the mean of the targets has an R² of `0`.
+    pub fn r2(&self) -> f64 {
+        self.output_mean(|i| 1.0 - self.error_squared_sum[i] / self.target_variance(i))
+    }
+
+    /// The explained variance, `1 - Var(target - output) / Var(target)`, averaged over the outputs.
+    ///
+    /// Unlike `r2`, it does not penalize biased outputs.
+    pub fn explained_variance(&self) -> f64 {
+        self.output_mean(|i| 1.0 - self.error_m2[i] / self.target_variance(i))
+    }
+
+    /// Returns the sum of squared deviations of the `i`-th target from its mean
+    fn target_variance(&self, i: usize) -> f64 {
+        self.target_m2[i]
+    }
+
+    /// Averages `metric` over the outputs, ignoring outputs with constant targets (for which it is undefined)
+    fn output_mean(&self, metric: impl Fn(usize) -> f64) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+
+        let values: Vec<f64> = (0..self.outputs())
+            .filter(|&i| self.target_variance(i) > 0.0)
+            .map(metric)
+            .collect();
+
+        if values.is_empty() {
+            0.0
+        } else {
+            values.iter().sum::<f64>() / values.len() as f64
+        }
+    }
+
+    fn entry_mean(&self, sum: f64) -> f64 {
+        let entries = self.count * self.outputs();
+
+        if entries == 0 {
+            0.0
+        } else {
+            sum / entries as f64
+        }
+    }
+}
+
+impl Display for NeuraRegressionMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "R²: {:.4}, MAE: {:.4}, RMSE: {:.4}, Explained variance: {:.4}",
+            self.r2(),
+            self.mae(),
+            self.rmse(),
+            self.explained_variance()
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::dvector;
+
+    use super::*;
+
+    #[test]
+    fn test_regression_metrics() {
+        let mut metrics = NeuraRegressionMetrics::new(1);
+        for (output, target) in [(1.0, 1.0), (2.5, 2.0), (2.0, 3.0), (4.5, 4.0)] {
+            metrics.add(&dvector![output], &dvector![target]);
+        }
+
+        // Errors: 0, 0.5, -1, 0.5; targets have a mean of 2.5 and SS_tot = 5
+        assert_eq!(metrics.len(), 4);
+        assert_relative_eq!(metrics.mae(), 0.5);
+        assert_relative_eq!(metrics.mse(), 1.5 / 4.0);
+        assert_relative_eq!(metrics.rmse(), (1.5f64 / 4.0).sqrt());
+        assert_relative_eq!(metrics.r2(), 1.0 - 1.5 / 5.0);
+        // The errors have a mean of 0, so their variance is 1.5 / 4
+        assert_relative_eq!(metrics.explained_variance(), 1.0 - 1.5 / 5.0);
+
+        // A constant bias lowers R², but not the explained variance
+        let mut metrics = NeuraRegressionMetrics::new(2);
+        for target in [dvector![1.0, -1.0], dvector![2.0, 0.0], dvector![3.0, 1.0]] {
+            metrics.add(&target.add_scalar(1.0), &target);
+        }
+        assert_relative_eq!(metrics.mae(), 1.0);
+        assert_relative_eq!(metrics.r2(), -0.5);
+        assert_relative_eq!(metrics.explained_variance(), 1.0);
+
+        assert_eq!(NeuraRegressionMetrics::new(1).r2(), 0.0);
+    }
+
+    #[test]
+    fn test_regression_metrics_constant_target() {
+        // 0.1 isn't representable exactly, which used to leave a tiny positive variance
+        let mut metrics = NeuraRegressionMetrics::new(2);
+        for (output, target) in [(0.5, 1.0), (2.5, 2.0), (2.0, 3.0), (4.5, 4.0), (5.0, 5.0)] {
+            metrics.add(&dvector![output, 0.3], &dvector![target, 0.1]);
+        }
+
+        // Only the first output is taken into account: its errors are -0.5, 0.5, -1, 0.5, 0, and SS_tot = 10
+        assert_relative_eq!(metrics.r2(), 1.0 - 1.75 / 10.0);
+        // The errors have a mean of -0.1, so the sum of their squared deviations is 1.75 - 5 * 0.01
+        assert_relative_eq!(metrics.explained_variance(), 1.0 - 1.7 / 10.0);
+    }
+}
@@ -115,7 +115,17 @@ impl<Data> NeuraGraph<Data> {
             // TODO: create more wrapper types to avoid this dereferencing mess
             let intermediary = &**intermediary_buffer[node.output].as_ref().unwrap();
 
-            let epsilon_out = node.node.backprop(intermediary, &epsilon_in);
+            let inputs: Vec<_> = node
+                .inputs
+                .iter()
+                .map(|&i| {
+                    output_buffer[i]
+                        .as_ref()
+                        .expect("Unreachable: output of previous layer was not set")
+                })
+                .collect();
+
+            let epsilon_out = node.node.backprop(intermediary, &inputs, &epsilon_in);
             let gradient = node.node.get_gradient(intermediary, &epsilon_in);
 
             (*gradient_buffer[node.output]).add_assign(&*gradient);
@@ -1,7 +1,11 @@
 use std::any::Any;
 
 use crate::{
-    algebra::NeuraDynVectorSpace, derivable::NeuraLoss, layer::NeuraLayerBase, prelude::*,
+    algebra::{NeuraDynVectorSpace, NeuraVectorSpace},
+    derivable::NeuraLoss,
+    layer::NeuraLayerBase,
+    network::{NeuraLayerVisitor, NeuraLayerVisitorMut},
+    prelude::*,
 };
 
 mod node;
@@ -69,8 +73,10 @@ impl<Data: Clone + std::fmt::Debug + 'static> NeuraLayerBase for NeuraGraph<Data
     }
 
     fn apply_gradient(&mut self, gradient: &Self::Gradient) {
-        for (node, gradient) in self.nodes.iter_mut().zip(gradient.iter()) {
-            node.node.apply_gradient(gradient);
+        // The first entry of the gradient corresponds to the input of the graph
+        for (node, gradient) in self.nodes.iter_mut().zip(gradient.iter().skip(1)) {
+            // Dereference the box, as `Box<dyn NeuraDynVectorSpace>` is itself a `NeuraDynVectorSpace`
+            node.node.apply_gradient(&**gradient);
         }
     }
 
@@ -91,6 +97,35 @@ impl<Data: Clone + std::fmt::Debug + 'static> NeuraLayerBase for NeuraGraph<Data
 
         res
     }
+
+    fn decay_layer(&mut self, factor: f64) {
+        for node in self.nodes.iter_mut() {
+            node.node.decay(factor);
+        }
+    }
+
+    fn clip_layer_gradient(&self, gradient: &mut Self::Gradient, max_norm: f64) {
+        // Each entry of the gradient corresponds to one node of the graph
+        for node_gradient in gradient.iter_mut() {
+            node_gradient.clip_norm(max_norm);
+        }
+    }
+
+    fn accept_visitor(&self, _input_shapes: &[NeuraShape], visitor: &mut dyn NeuraLayerVisitor) {
+        for node in self.nodes.iter() {
+            node.node.accept_visitor(visitor);
+        }
+    }
+
+    fn accept_visitor_mut(
+        &mut self,
+        _input_shapes: &[NeuraShape],
+        visitor: &mut dyn NeuraLayerVisitorMut,
+    ) {
+        for node in self.nodes.iter_mut() {
+            node.node.accept_visitor_mut(visitor);
+        }
+    }
 }
 
 impl<Data> NeuraGraph<Data> {
@@ -161,7 +196,10 @@ impl<Data: Clone + std::fmt::Debug + 'static> NeuraLayer<Data> for NeuraGraph<Da
 
 #[cfg(test)]
 mod test {
-    use crate::{axis::NeuraAxisAppend, err::NeuraGraphErr, utils::uniform_vector};
+    use crate::{
+        axis::NeuraAxisAppend, derivable::loss::Euclidean, err::NeuraGraphErr,
+        gradient_solver::NeuraGradientSolver, utils::uniform_vector,
+    };
 
     use super::*;
 
@@ -299,4 +337,73 @@ mod test {
             approx::assert_relative_eq!(seq_result[1], graph_result[1]);
         }
     }
+
+    #[test]
+    fn test_graph_summary() {
+        let graph = NeuraGraphPartial {
+            nodes: vec![
+                NeuraGraphNode::new(
+                    vec!["input".to_string()],
+                    NeuraAxisAppend,
+                    neura_layer!("dense", 10),
+                    "inter".to_string(),
+                )
+                .as_boxed(),
+                NeuraGraphNode::new(
+                    vec!["input".to_string(), "inter".to_string()],
+                    NeuraAxisAppend,
+                    neura_layer!("dense", 2),
+                    "output".to_string(),
+                )
+                .as_boxed(),
+            ],
+            output: "output".to_string(),
+            input: "input".to_string(),
+        };
+
+        let summary = graph.construct(NeuraShape::Vector(5)).unwrap().summary();
+
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary.rows[0].input_shapes, vec![NeuraShape::Vector(5)]);
+        assert_eq!(
+            summary.rows[1].input_shapes,
+            vec![NeuraShape::Vector(5), NeuraShape::Vector(10)]
+        );
+        assert_eq!(summary.total_parameters(), (5 * 10 + 10) + (15 * 2 + 2));
+        assert_eq!(summary.trainable_parameters(), summary.total_parameters());
+    }
+
+    #[test]
+    fn test_graph_input_slot() {
+        // The node declared first must not share its buffer slot with the input of the graph
+        let graph = NeuraGraphPartial {
+            nodes: vec![
+                NeuraGraphNode::new(
+                    vec!["input".to_string()],
+                    NeuraAxisAppend,
+                    neura_layer!("dense", 4, f64),
+                    "inter".to_string(),
+                )
+                .as_boxed(),
+                NeuraGraphNode::new(
+                    vec!["input".to_string(), "inter".to_string()],
+                    NeuraAxisAppend,
+                    neura_layer!("isolate", 0, 3),
+                    "output".to_string(),
+                )
+                .as_boxed(),
+            ],
+            output: "output".to_string(),
+            input: "input".to_string(),
+        };
+
+        let mut graph = graph.construct(NeuraShape::Vector(3)).unwrap();
+        let input = uniform_vector(3);
+        assert_eq!(graph.eval(&input), input);
+
+        // The first entry of the gradient belongs to the input, and must be skipped by `apply_gradient`
+        let gradient =
+            NeuraGraphBackprop::new(Euclidean).get_gradient(&graph, &input, &uniform_vector(3));
+        graph.apply_gradient(&gradient);
+    }
 }
@@ -23,7 +23,8 @@ pub trait NeuraGraphNodeEval<Data>: DynClone + Debug {
     fn eval(&self, inputs: &[Data]) -> Data;
 
     fn eval_training(&self, inputs: &[Data]) -> (Data, Box<dyn Any>);
-    fn backprop(&self, intermediary: &dyn Any, epsilon_in: &Data) -> Vec<Data>;
+    /// `inputs` must be the values that were passed to `eval_training`
+    fn backprop(&self, intermediary: &dyn Any, inputs: &[&Data], epsilon_in: &Data) -> Vec<Data>;
 
     fn default_gradient(&self) -> Box<dyn NeuraDynVectorSpace>;
 
@@ -37,7 +38,13 @@ pub trait NeuraGraphNodeEval<Data>: DynClone + Debug {
 
     fn apply_gradient(&mut self, gradient: &dyn NeuraDynVectorSpace);
 
+    fn decay(&mut self, factor: f64);
+
     fn prepare(&mut self, is_training: bool);
+
+    fn accept_visitor(&self, visitor: &mut dyn NeuraLayerVisitor);
+
+    fn accept_visitor_mut(&mut self, visitor: &mut dyn NeuraLayerVisitorMut);
 }
 
 #[derive(Clone, Debug)]
@@ -113,12 +120,16 @@ impl<Data: Clone, Axis: NeuraAxis<Data>, Layer: NeuraLayer<Axis::Combined, Outpu
     NeuraGraphNodeEval<Data> for NeuraGraphNode<Axis, Layer>
 {
     fn eval(&self, inputs: &[Data]) -> Data {
-        let combined = self.axis.combine(inputs);
+        let combined = self
+            .axis
+            .combine(inputs, self.input_shapes.as_deref().unwrap_or_default());
         self.layer.eval(&combined)
     }
 
     fn eval_training<'a>(&self, inputs: &[Data]) -> (Data, Box<dyn Any>) {
-        let combined = self.axis.combine(inputs);
+        let combined = self
+            .axis
+            .combine(inputs, self.input_shapes.as_deref().unwrap_or_default());
         let (result, layer_intermediary) = self.layer.eval_training(&combined);
 
         let intermediary: Intermediary<Axis::Combined, Layer> = Intermediary {
@@ -129,7 +140,7 @@ impl<Data: Clone, Axis: NeuraAxis<Data>, Layer: NeuraLayer<Axis::Combined, Outpu
         (result, Box::new(intermediary))
     }
 
-    fn backprop(&self, intermediary: &dyn Any, epsilon_in: &Data) -> Vec<Data> {
+    fn backprop(&self, intermediary: &dyn Any, inputs: &[&Data], epsilon_in: &Data) -> Vec<Data> {
         let intermediary = self.downcast_intermediary(intermediary);
 
         let epsilon_out = self.layer.backprop_layer(
@@ -139,7 +150,7 @@ impl<Data: Clone, Axis: NeuraAxis<Data>, Layer: NeuraLayer<Axis::Combined, Outpu
         );
 
         self.axis
-            .split(&epsilon_out, self.input_shapes.as_ref().unwrap())
+            .split(&epsilon_out, inputs, self.input_shapes.as_ref().unwrap())
     }
 
     fn get_gradient(
@@ -169,6 +180,10 @@ impl<Data: Clone, Axis: NeuraAxis<Data>, Layer: NeuraLayer<Axis::Combined, Outpu
         Box::new(self.layer.default_gradient())
     }
 
+    fn decay(&mut self, factor: f64) {
+        self.layer.decay_layer(factor);
+    }
+
     fn prepare(&mut self, is_training: bool) {
         self.layer.prepare_layer(is_training);
     }
@@ -176,6 +191,16 @@ impl<Data: Clone, Axis: NeuraAxis<Data>, Layer: NeuraLayer<Axis::Combined, Outpu
     fn get_regularization_gradient(&self) -> Box<dyn NeuraDynVectorSpace> {
         Box::new(self.layer.regularize_layer())
     }
+
+    fn accept_visitor(&self, visitor: &mut dyn NeuraLayerVisitor) {
+        self.layer
+            .accept_visitor(self.input_shapes.as_deref().unwrap_or_default(), visitor);
+    }
+
+    fn accept_visitor_mut(&mut self, visitor: &mut dyn NeuraLayerVisitorMut) {
+        self.layer
+            .accept_visitor_mut(self.input_shapes.as_deref().unwrap_or_default(), visitor);
+    }
 }
 
 impl<Data: Clone, Axis: NeuraAxis<Data>, Layer: NeuraPartialLayer + Clone + Debug>
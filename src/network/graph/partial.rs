@@ -133,10 +133,12 @@ impl<Data: Clone + std::fmt::Debug + 'static> NeuraPartialLayer for NeuraGraphPa
 
         // List out the nodes in their execution order
         let node_order = self.get_node_order(&index_map, &reverse_graph)?;
+        // The slot 0 of the buffers is reserved for the input, so nodes are stored in the slots `1..=nodes.len()`
         let mut new_index_map: HashMap<String, usize> = HashMap::from_iter(
             node_order
                 .iter()
-                .map(|&i| (self.nodes[i].name().to_string(), i)),
+                .enumerate()
+                .map(|(position, &i)| (self.nodes[i].name().to_string(), position + 1)),
         );
         new_index_map.insert(self.input.clone(), 0);
 
@@ -171,15 +173,16 @@ impl<Data: Clone + std::fmt::Debug + 'static> NeuraPartialLayer for NeuraGraphPa
                 .construct(input_shapes)
                 .map_err(NeuraGraphErr::LayerErr)?;
 
-            shapes[index] = Some(output_shape);
+            let output = new_index_map
+                .get(node.name())
+                .copied()
+                .unwrap_or_else(|| unreachable!());
+            shapes[output] = Some(output_shape);
 
             nodes.push(NeuraGraphNodeConstructed {
                 node: constructed,
                 inputs,
-                output: new_index_map
-                    .get(node.name())
-                    .copied()
-                    .unwrap_or_else(|| unreachable!()),
+                output,
             });
         }
 
@@ -0,0 +1,83 @@
+use std::ops::RangeBounds;
+
+use super::NeuraLayerInfo;
+
+/// Locks and unlocks individual layers of a network at runtime, for instance to fine-tune only its last layers.
+///
+/// Unlike `NeuraSequentialLock::lock` and `NeuraLayerBase::lock_layer`, this does not change the type of the network:
+/// locked layers are still evaluated and backpropagated through, but their gradient isn't computed nor applied.
+///
+/// This is implemented for networks whose layers are wrapped in a `NeuraSelectiveLockLayer`, see `NeuraSelectiveLockable`.
+/// Layers are indexed in order of evaluation; nested networks count as a single layer.
+pub trait NeuraSelectiveLock {
+    /// Calls `callback` with the index of each layer, the layer itself and whether it is currently locked,
+    /// and locks the layer if `callback` returns `true`, or unlocks it otherwise.
+    /// `index` is the index of the first layer of `self`.
+    fn map_locked_from(
+        &mut self,
+        index: usize,
+        callback: &mut dyn FnMut(usize, &dyn NeuraLayerInfo, bool) -> bool,
+    );
+
+    /// Calls `callback` with the index of each layer, the layer itself and whether it is currently locked.
+    /// `index` is the index of the first layer of `self`.
+    fn inspect_locked_from(
+        &self,
+        index: usize,
+        callback: &mut dyn FnMut(usize, &dyn NeuraLayerInfo, bool),
+    );
+
+    /// See `map_locked_from`
+    fn map_locked(&mut self, mut callback: impl FnMut(usize, &dyn NeuraLayerInfo, bool) -> bool) {
+        self.map_locked_from(0, &mut callback);
+    }
+
+    /// Returns the number of layers that can be locked
+    fn layer_count(&self) -> usize {
+        let mut res = 0;
+        self.inspect_locked_from(0, &mut |_, _, _| res += 1);
+        res
+    }
+
+    /// Returns the indices of the locked layers
+    fn locked_layers(&self) -> Vec<usize> {
+        let mut res = Vec::new();
+        self.inspect_locked_from(0, &mut |index, _, locked| {
+            if locked {
+                res.push(index);
+            }
+        });
+        res
+    }
+
+    /// Locks the layers within `range`, leaving the other layers untouched.
+    ///
+    /// For instance, `network.lock_layers(..network.layer_count() - 2)` locks all but the last two layers.
+    fn lock_layers(&mut self, range: impl RangeBounds<usize>) {
+        self.map_locked(|index, _, locked| locked || range.contains(&index));
+    }
+
+    /// Unlocks the layers within `range`, leaving the other layers untouched
+    fn unlock_layers(&mut self, range: impl RangeBounds<usize>) {
+        self.map_locked(|index, _, locked| locked && !range.contains(&index));
+    }
+
+    /// Locks the layers for which `predicate` returns `true`, leaving the other layers untouched
+    fn lock_where(&mut self, mut predicate: impl FnMut(usize, &dyn NeuraLayerInfo) -> bool) {
+        self.map_locked(|index, layer, locked| locked || predicate(index, layer));
+    }
+
+    /// Unlocks every layer
+    fn unlock(&mut self) {
+        self.map_locked(|_, _, _| false);
+    }
+}
+
+/// Wraps each layer of a network in a `NeuraSelectiveLockLayer`, so that the network implements `NeuraSelectiveLock`.
+///
+/// For instance, `neura_sequential![..backbone.lockable(), head]` allows the layers of `backbone` to be locked later on.
+pub trait NeuraSelectiveLockable {
+    type Lockable;
+
+    fn lockable(self) -> Self::Lockable;
+}
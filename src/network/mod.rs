@@ -2,5 +2,17 @@ pub mod graph;
 pub mod residual;
 pub mod sequential;
 
+mod lock;
+pub use lock::*;
+
+mod prune;
+pub use prune::*;
+
+mod summary;
+pub use summary::*;
+
 mod traits;
 pub use traits::*;
+
+mod visitor;
+pub use visitor::*;
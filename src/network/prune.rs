@@ -0,0 +1,37 @@
+use crate::layer::prune::NeuraPrunable;
+
+/// Removes whole neurons from the layers of a network, shrinking it.
+///
+/// A neuron of a prunable layer (see `NeuraLayerBase::prunable`) can only be removed if the layer is directly followed
+/// by another prunable layer, whose matching inputs are removed along with it.
+/// The last prunable layer of a chain is thus never pruned, and the output shape of the network is left unchanged.
+/// Locked layers are left untouched.
+///
+/// This is only implemented for `NeuraSequential` networks: in residual networks and graphs, the outputs of a layer
+/// can be combined with other outputs and fed to several layers, which would all need to be updated.
+/// Use `NeuraLayerBase::prune_magnitude` to prune the weights of those networks instead.
+pub trait NeuraPruneNeurons {
+    /// Returns the first layer of the network, if it can be pruned and isn't locked
+    fn first_prunable_mut(&mut self) -> Option<&mut dyn NeuraPrunable>;
+
+    /// Calls `select` on each layer whose neurons can be removed, and keeps only the neurons it returns.
+    /// The returned indices must be sorted and not contain duplicates.
+    fn prune_neurons_with(&mut self, select: &mut dyn FnMut(&dyn NeuraPrunable) -> Vec<usize>);
+
+    /// Removes a fraction `fraction` of the neurons of each layer, starting with those whose weights have the smallest L2 norm.
+    /// At least one neuron is kept in each layer.
+    fn prune_neurons(&mut self, fraction: f64) {
+        self.prune_neurons_with(&mut |layer| {
+            let norms = layer.neuron_norms();
+            let removed = (fraction.clamp(0.0, 1.0) * norms.len() as f64).round() as usize;
+            let kept = norms.len().saturating_sub(removed).max(1);
+
+            let mut neurons: Vec<usize> = (0..norms.len()).collect();
+            neurons.sort_by(|&a, &b| norms[b].total_cmp(&norms[a]));
+            neurons.truncate(kept);
+            neurons.sort_unstable();
+
+            neurons
+        });
+    }
+}
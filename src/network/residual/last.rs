@@ -74,6 +74,20 @@ impl NeuraLayerBase for NeuraResidualLast {
         self.output_shape
             .expect("Called NeuraResidualLast::output_shape before constructing it")
     }
+
+    #[inline(always)]
+    fn accept_visitor(&self, _input_shapes: &[NeuraShape], _visitor: &mut dyn NeuraLayerVisitor) {
+        // Noop
+    }
+
+    #[inline(always)]
+    fn accept_visitor_mut(
+        &mut self,
+        _input_shapes: &[NeuraShape],
+        _visitor: &mut dyn NeuraLayerVisitorMut,
+    ) {
+        // Noop
+    }
 }
 
 impl NeuraNetworkBase for NeuraResidualLast {
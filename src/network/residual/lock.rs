@@ -0,0 +1,100 @@
+use crate::layer::lock::NeuraSelectiveLockLayer;
+use crate::network::*;
+
+use super::*;
+
+impl NeuraSelectiveLock for NeuraResidualLast {
+    fn map_locked_from(
+        &mut self,
+        _index: usize,
+        _callback: &mut dyn FnMut(usize, &dyn NeuraLayerInfo, bool) -> bool,
+    ) {
+        // Noop
+    }
+
+    fn inspect_locked_from(
+        &self,
+        _index: usize,
+        _callback: &mut dyn FnMut(usize, &dyn NeuraLayerInfo, bool),
+    ) {
+        // Noop
+    }
+}
+
+impl NeuraSelectiveLockable for NeuraResidualLast {
+    type Lockable = NeuraResidualLast;
+
+    fn lockable(self) -> Self::Lockable {
+        self
+    }
+}
+
+impl<Layer, ChildNetwork: NeuraSelectiveLockable, Axis> NeuraSelectiveLockable
+    for NeuraResidualNode<Layer, ChildNetwork, Axis>
+{
+    type Lockable = NeuraResidualNode<NeuraSelectiveLockLayer<Layer>, ChildNetwork::Lockable, Axis>;
+
+    fn lockable(self) -> Self::Lockable {
+        NeuraResidualNode {
+            layer: NeuraSelectiveLockLayer::new(self.layer),
+            child_network: self.child_network.lockable(),
+            offsets: self.offsets,
+            axis: self.axis,
+            output_shape: self.output_shape,
+            input_shapes: self.input_shapes,
+            input_offsets: self.input_offsets,
+        }
+    }
+}
+
+impl<Layers: NeuraSelectiveLockable> NeuraSelectiveLockable for NeuraResidual<Layers> {
+    type Lockable = NeuraResidual<Layers::Lockable>;
+
+    fn lockable(self) -> Self::Lockable {
+        NeuraResidual {
+            layers: self.layers.lockable(),
+            initial_offsets: self.initial_offsets,
+        }
+    }
+}
+
+impl<Layer: NeuraLayerBase, ChildNetwork: NeuraSelectiveLock, Axis> NeuraSelectiveLock
+    for NeuraResidualNode<NeuraSelectiveLockLayer<Layer>, ChildNetwork, Axis>
+{
+    fn map_locked_from(
+        &mut self,
+        index: usize,
+        callback: &mut dyn FnMut(usize, &dyn NeuraLayerInfo, bool) -> bool,
+    ) {
+        let locked = callback(index, self.layer.get(), self.layer.is_locked());
+        self.layer.set_locked(locked);
+        self.child_network.map_locked_from(index + 1, callback);
+    }
+
+    fn inspect_locked_from(
+        &self,
+        index: usize,
+        callback: &mut dyn FnMut(usize, &dyn NeuraLayerInfo, bool),
+    ) {
+        callback(index, self.layer.get(), self.layer.is_locked());
+        self.child_network.inspect_locked_from(index + 1, callback);
+    }
+}
+
+impl<Layers: NeuraSelectiveLock> NeuraSelectiveLock for NeuraResidual<Layers> {
+    fn map_locked_from(
+        &mut self,
+        index: usize,
+        callback: &mut dyn FnMut(usize, &dyn NeuraLayerInfo, bool) -> bool,
+    ) {
+        self.layers.map_locked_from(index, callback);
+    }
+
+    fn inspect_locked_from(
+        &self,
+        index: usize,
+        callback: &mut dyn FnMut(usize, &dyn NeuraLayerInfo, bool),
+    ) {
+        self.layers.inspect_locked_from(index, callback);
+    }
+}
@@ -17,6 +17,8 @@ pub use node::*;
 mod last;
 pub use last::*;
 
+mod lock;
+
 #[macro_export]
 macro_rules! neura_residual {
     [ "__combine_layers", ] => {
@@ -64,9 +66,14 @@ macro_rules! neura_residual {
 
 #[cfg(test)]
 mod test {
-    use nalgebra::dvector;
+    use approx::assert_relative_eq;
+    use nalgebra::{dmatrix, dvector};
 
+    use crate::axis::NeuraAxisSum;
+    use crate::derivable::{activation::Linear, regularize::NeuraL0};
     use crate::gradient_solver::NeuraGradientSolver;
+    use crate::layer::dense::NeuraDenseLayer;
+    use crate::network::{NeuraSelectiveLock, NeuraSelectiveLockable};
     use crate::{derivable::loss::Euclidean, neura_layer, prelude::NeuraBackprop};
 
     use super::*;
@@ -183,4 +190,91 @@ mod test {
 
         backprop.get_gradient(&network, &dvector![0.0], &dvector![0.0, 0.0, 0.0, 0.0]);
     }
+
+    #[test]
+    fn test_resnet_summary() {
+        let network = neura_residual![
+            <= 0, 1;
+            neura_layer!("dense", 2) => 0, 1;
+            neura_layer!("dense", 4);
+            neura_layer!("dense", 8)
+        ]
+        .construct(NeuraShape::Vector(1))
+        .unwrap();
+
+        let summary = network.summary();
+
+        assert_eq!(summary.len(), 3);
+        assert_eq!(
+            summary.rows[1].input_shapes,
+            vec![NeuraShape::Vector(1), NeuraShape::Vector(2)]
+        );
+        assert_eq!(summary.rows[2].output_shape, NeuraShape::Vector(8));
+        assert_eq!(
+            summary.total_parameters(),
+            (2 + 2) + (3 * 4 + 4) + (6 * 8 + 8)
+        );
+    }
+
+    #[test]
+    fn test_resnet_axis_sum() {
+        let network = neura_residual![
+            <= 0, 1;
+            NeuraDenseLayer::new(dmatrix![0.5, -1.0; 2.0, 0.25], dvector![0.1, 0.2], Linear, NeuraL0) => 0;
+            NeuraDenseLayer::new(dmatrix![1.0, 0.5], dvector![-0.3], Linear, NeuraL0), NeuraAxisSum
+        ];
+        let Ok(network) = network.construct(NeuraShape::Vector(2)) else {
+            panic!("Couldn't construct the network");
+        };
+        assert_eq!(network.layers.child_network.input_shapes.len(), 2);
+
+        let input = dvector![1.0, -2.0];
+        let target = dvector![0.5];
+
+        // The second layer takes in `input + hidden`
+        let hidden = dvector![2.6, 1.7];
+        let output = 1.0 * (1.0 + 2.6) + 0.5 * (-2.0 + 1.7) - 0.3;
+        assert_relative_eq!(network.eval(&input), dvector![output], epsilon = 1e-12);
+
+        let (first_gradient, second_gradient) =
+            NeuraBackprop::new(Euclidean).get_gradient(&network, &input, &target);
+        let delta = output - target[0];
+
+        assert_relative_eq!(
+            second_gradient.0 .0.as_slice(),
+            ((&input + &hidden) * delta).as_slice(),
+            epsilon = 1e-12
+        );
+        // The first layer only receives the gradient through the second layer
+        assert_relative_eq!(first_gradient.1, dvector![1.0, 0.5] * delta, epsilon = 1e-12);
+        assert_relative_eq!(
+            first_gradient.0,
+            dvector![1.0, 0.5] * delta * input.transpose(),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_resnet_selective_lock() {
+        let mut network = neura_residual![
+            <= 0, 1;
+            neura_layer!("dense", 2) => 0, 1;
+            neura_layer!("dense", 4);
+            neura_layer!("dense", 8)
+        ]
+        .lockable()
+        .construct(NeuraShape::Vector(1))
+        .unwrap();
+
+        assert_eq!(network.layer_count(), 3);
+        network.lock_layers(1..);
+
+        let summary = network.summary();
+        assert!(!summary.rows[0].locked);
+        assert!(summary.rows[1].locked && summary.rows[2].locked);
+        assert_eq!(summary.trainable_parameters(), 2 + 2);
+
+        network.unlock();
+        assert_eq!(network.summary().locked_parameters(), 0);
+    }
 }
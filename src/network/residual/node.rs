@@ -73,7 +73,7 @@ impl<Layer, ChildNetwork, Axis> NeuraResidualNode<Layer, ChildNetwork, Axis> {
     {
         let (inputs, rest) = input.shift();
 
-        let layer_input = self.axis.combine(&inputs);
+        let layer_input = self.axis.combine(&inputs, &self.input_shapes);
 
         (layer_input, rest)
     }
@@ -96,7 +96,7 @@ impl<Layer, ChildNetwork, Axis> NeuraResidualNode<Layer, ChildNetwork, Axis> {
     where
         Axis: NeuraAxis<Data>,
     {
-        self.axis.combine(&input.shift().0)
+        self.axis.combine(&input.shift().0, &self.input_shapes)
     }
 }
 
@@ -132,6 +132,11 @@ impl<
         self.child_network.apply_gradient(&gradient.1);
     }
 
+    fn offset_parameters(&mut self, offset: &Self::Gradient) {
+        self.layer.offset_parameters(&offset.0);
+        self.child_network.offset_parameters(&offset.1);
+    }
+
     fn prepare_layer(&mut self, is_training: bool) {
         self.layer.prepare_layer(is_training);
         self.child_network.prepare_layer(is_training);
@@ -143,6 +148,31 @@ impl<
             Box::new(self.child_network.regularize_layer()),
         )
     }
+
+    fn decay_layer(&mut self, factor: f64) {
+        self.layer.decay_layer(factor);
+        self.child_network.decay_layer(factor);
+    }
+
+    fn clip_layer_gradient(&self, gradient: &mut Self::Gradient, max_norm: f64) {
+        self.layer.clip_layer_gradient(&mut gradient.0, max_norm);
+        self.child_network
+            .clip_layer_gradient(&mut gradient.1, max_norm);
+    }
+
+    fn accept_visitor(&self, _input_shapes: &[NeuraShape], visitor: &mut dyn NeuraLayerVisitor) {
+        self.layer.accept_visitor(&self.input_shapes, visitor);
+        self.child_network.accept_visitor(&[], visitor);
+    }
+
+    fn accept_visitor_mut(
+        &mut self,
+        _input_shapes: &[NeuraShape],
+        visitor: &mut dyn NeuraLayerVisitorMut,
+    ) {
+        self.layer.accept_visitor_mut(&self.input_shapes, visitor);
+        self.child_network.accept_visitor_mut(&[], visitor);
+    }
 }
 
 impl<Data: Clone + 'static, Layer, ChildNetwork, Axis: Clone + std::fmt::Debug + 'static>
@@ -297,8 +327,9 @@ where
         gradient_out: &'a Self::LayerInput,
     ) -> Cow<'a, NeuraResidualInput<Data>> {
         let (_, mut rest) = gradient_in.shift();
+        let (inputs, _) = input.shift();
 
-        let split = self.axis.split(gradient_out, &self.input_shapes);
+        let split = self.axis.split(gradient_out, &inputs, &self.input_shapes);
 
         for (offset, gradient) in self.input_offsets.iter().copied().zip(split.into_iter()) {
             rest.push(offset, Rc::new(gradient));
@@ -99,13 +99,39 @@ impl<Layers: NeuraLayerBase> NeuraLayerBase for NeuraResidual<Layers> {
         self.layers.apply_gradient(gradient);
     }
 
+    fn offset_parameters(&mut self, offset: &Self::Gradient) {
+        self.layers.offset_parameters(offset);
+    }
+
     fn regularize_layer(&self) -> Self::Gradient {
         self.layers.regularize_layer()
     }
 
+    #[inline(always)]
+    fn decay_layer(&mut self, factor: f64) {
+        self.layers.decay_layer(factor);
+    }
+
+    #[inline(always)]
+    fn clip_layer_gradient(&self, gradient: &mut Self::Gradient, max_norm: f64) {
+        self.layers.clip_layer_gradient(gradient, max_norm);
+    }
+
     fn output_shape(&self) -> NeuraShape {
         self.layers.output_shape()
     }
+
+    fn accept_visitor(&self, input_shapes: &[NeuraShape], visitor: &mut dyn NeuraLayerVisitor) {
+        self.layers.accept_visitor(input_shapes, visitor);
+    }
+
+    fn accept_visitor_mut(
+        &mut self,
+        input_shapes: &[NeuraShape],
+        visitor: &mut dyn NeuraLayerVisitorMut,
+    ) {
+        self.layers.accept_visitor_mut(input_shapes, visitor);
+    }
 }
 
 impl<Layers> NeuraNetworkBase for NeuraResidual<Layers> {
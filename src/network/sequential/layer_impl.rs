@@ -28,12 +28,45 @@ impl<Layer: NeuraLayerBase, ChildNetwork: NeuraLayerBase> NeuraLayerBase
         self.child_network.apply_gradient(&gradient.1);
     }
 
+    fn offset_parameters(&mut self, offset: &Self::Gradient) {
+        self.layer.offset_parameters(&offset.0);
+        self.child_network.offset_parameters(&offset.1);
+    }
+
     fn regularize_layer(&self) -> Self::Gradient {
         (
             self.layer.regularize_layer(),
             Box::new(self.child_network.regularize_layer()),
         )
     }
+
+    fn decay_layer(&mut self, factor: f64) {
+        self.layer.decay_layer(factor);
+        self.child_network.decay_layer(factor);
+    }
+
+    fn clip_layer_gradient(&self, gradient: &mut Self::Gradient, max_norm: f64) {
+        self.layer.clip_layer_gradient(&mut gradient.0, max_norm);
+        self.child_network.clip_layer_gradient(&mut gradient.1, max_norm);
+    }
+
+    fn accept_visitor(&self, input_shapes: &[NeuraShape], visitor: &mut dyn NeuraLayerVisitor) {
+        self.layer.accept_visitor(input_shapes, visitor);
+        self.child_network
+            .accept_visitor(&[self.layer.output_shape()], visitor);
+    }
+
+    fn accept_visitor_mut(
+        &mut self,
+        input_shapes: &[NeuraShape],
+        visitor: &mut dyn NeuraLayerVisitorMut,
+    ) {
+        let layer_shape = self.layer.output_shape();
+
+        self.layer.accept_visitor_mut(input_shapes, visitor);
+        self.child_network
+            .accept_visitor_mut(&[layer_shape], visitor);
+    }
 }
 
 impl<Input, Layer: NeuraLayer<Input>, ChildNetwork: NeuraLayer<Layer::Output>> NeuraLayer<Input>
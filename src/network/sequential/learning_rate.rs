@@ -0,0 +1,39 @@
+use crate::layer::learning_rate::NeuraLearningRateLayer;
+
+use super::*;
+
+pub trait NeuraSequentialLearningRate {
+    type Scaled;
+
+    /// Wraps each layer of the network in a `NeuraLearningRateLayer`, multiplying their learning rate by `multiplier`.
+    ///
+    /// For instance, `neura_sequential![..backbone.learning_rate_multiplier(0.1), head]`
+    /// trains the layers of `backbone` at a tenth of the learning rate of `head`.
+    fn learning_rate_multiplier(self, multiplier: f64) -> Self::Scaled;
+}
+
+impl NeuraSequentialLearningRate for NeuraSequentialLast {
+    type Scaled = NeuraSequentialLast;
+
+    fn learning_rate_multiplier(self, _multiplier: f64) -> Self::Scaled {
+        self
+    }
+}
+
+impl<Layer, ChildNetwork: NeuraSequentialLearningRate> NeuraSequentialLearningRate
+    for NeuraSequential<Layer, ChildNetwork>
+{
+    type Scaled = NeuraSequential<NeuraLearningRateLayer<Layer>, ChildNetwork::Scaled>;
+
+    fn learning_rate_multiplier(self, multiplier: f64) -> Self::Scaled {
+        let Self {
+            layer,
+            child_network,
+        } = self;
+
+        NeuraSequential {
+            layer: NeuraLearningRateLayer::new(layer, multiplier),
+            child_network: Box::new(child_network.learning_rate_multiplier(multiplier)),
+        }
+    }
+}
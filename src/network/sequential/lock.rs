@@ -1,4 +1,4 @@
-use crate::layer::lock::NeuraLockLayer;
+use crate::layer::lock::{NeuraLockLayer, NeuraSelectiveLockLayer};
 
 use super::*;
 
@@ -33,3 +33,70 @@ impl<Layer, ChildNetwork: NeuraSequentialLock> NeuraSequentialLock
         }
     }
 }
+
+impl NeuraSelectiveLock for NeuraSequentialLast {
+    fn map_locked_from(
+        &mut self,
+        _index: usize,
+        _callback: &mut dyn FnMut(usize, &dyn NeuraLayerInfo, bool) -> bool,
+    ) {
+        // Noop
+    }
+
+    fn inspect_locked_from(
+        &self,
+        _index: usize,
+        _callback: &mut dyn FnMut(usize, &dyn NeuraLayerInfo, bool),
+    ) {
+        // Noop
+    }
+}
+
+impl NeuraSelectiveLockable for NeuraSequentialLast {
+    type Lockable = NeuraSequentialLast;
+
+    fn lockable(self) -> Self::Lockable {
+        self
+    }
+}
+
+impl<Layer, ChildNetwork: NeuraSelectiveLockable> NeuraSelectiveLockable
+    for NeuraSequential<Layer, ChildNetwork>
+{
+    type Lockable = NeuraSequential<NeuraSelectiveLockLayer<Layer>, ChildNetwork::Lockable>;
+
+    fn lockable(self) -> Self::Lockable {
+        let Self {
+            layer,
+            child_network,
+        } = self;
+
+        NeuraSequential {
+            layer: NeuraSelectiveLockLayer::new(layer),
+            child_network: Box::new(child_network.lockable()),
+        }
+    }
+}
+
+impl<Layer: NeuraLayerBase, ChildNetwork: NeuraSelectiveLock> NeuraSelectiveLock
+    for NeuraSequential<NeuraSelectiveLockLayer<Layer>, ChildNetwork>
+{
+    fn map_locked_from(
+        &mut self,
+        index: usize,
+        callback: &mut dyn FnMut(usize, &dyn NeuraLayerInfo, bool) -> bool,
+    ) {
+        let locked = callback(index, self.layer.get(), self.layer.is_locked());
+        self.layer.set_locked(locked);
+        self.child_network.map_locked_from(index + 1, callback);
+    }
+
+    fn inspect_locked_from(
+        &self,
+        index: usize,
+        callback: &mut dyn FnMut(usize, &dyn NeuraLayerInfo, bool),
+    ) {
+        callback(index, self.layer.get(), self.layer.is_locked());
+        self.child_network.inspect_locked_from(index + 1, callback);
+    }
+}
@@ -5,11 +5,17 @@ use crate::layer::{NeuraLayer, NeuraLayerBase, NeuraPartialLayer, NeuraShape};
 
 mod construct;
 mod layer_impl;
+mod learning_rate;
 mod lock;
+mod precision;
+mod prune;
+mod quantize;
 mod tail;
 
 pub use construct::*;
+pub use learning_rate::*;
 pub use lock::*;
+pub use quantize::*;
 pub use tail::*;
 
 /// Chains a layer with the rest of a neural network, in a fashion similar to a cartesian product,
@@ -181,11 +187,20 @@ macro_rules! neura_sequential {
 
 #[cfg(test)]
 mod test {
-    use nalgebra::dvector;
+    use nalgebra::{dmatrix, dvector};
 
     use crate::{
-        derivable::{activation::Relu, regularize::NeuraL0},
-        layer::{dense::NeuraDenseLayer, NeuraLayer, NeuraShape},
+        derivable::{
+            activation::{Linear, Relu},
+            loss::Euclidean,
+            regularize::NeuraL0,
+        },
+        gradient_solver::{NeuraBackprop, NeuraGradientSolver},
+        layer::{dense::NeuraDenseLayer, NeuraLayer, NeuraLayerBase, NeuraShape},
+        network::{
+            sequential::{NeuraSequentialLearningRate, NeuraSequentialTail},
+            NeuraPruneNeurons, NeuraSelectiveLock, NeuraSelectiveLockable,
+        },
         neura_layer,
         prelude::NeuraPartialLayer,
     };
@@ -221,4 +236,179 @@ mod test {
 
         network.eval(&dvector![0.0, 0.0]);
     }
+
+    #[test]
+    fn test_summary() {
+        let mut rng = rand::thread_rng();
+
+        let network = neura_sequential![
+            (NeuraDenseLayer::from_rng(8, 12, &mut rng, Relu, NeuraL0)
+                as NeuraDenseLayer<f64, _, _>)
+                .lock_layer(),
+            NeuraDenseLayer::from_rng(12, 2, &mut rng, Relu, NeuraL0) as NeuraDenseLayer<f64, _, _>
+        ];
+
+        let summary = network.summary();
+
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary.rows[0].layer, "NeuraDenseLayer<f64, Relu, NeuraL0>");
+        assert_eq!(summary.rows[0].input_shapes, vec![NeuraShape::Vector(8)]);
+        assert_eq!(summary.rows[0].output_shape, NeuraShape::Vector(12));
+        assert_eq!(summary.rows[0].parameters, 8 * 12 + 12);
+        assert!(summary.rows[0].locked);
+        assert_eq!(summary.rows[1].input_shapes, vec![NeuraShape::Vector(12)]);
+        assert!(!summary.rows[1].locked);
+
+        assert_eq!(summary.total_parameters(), 8 * 12 + 12 + 12 * 2 + 2);
+        assert_eq!(summary.trainable_parameters(), 12 * 2 + 2);
+
+        let table = summary.to_string();
+        assert!(table.contains("NeuraDenseLayer<f64, Relu, NeuraL0> │ (8)"));
+        assert!(table.ends_with("Locked parameters: 108"));
+    }
+
+    #[test]
+    fn test_selective_lock() {
+        let mut rng = rand::thread_rng();
+
+        let mut network = neura_sequential![
+            NeuraDenseLayer::from_rng(2, 3, &mut rng, Relu, NeuraL0) as NeuraDenseLayer<f64, _, _>,
+            NeuraDenseLayer::from_rng(3, 3, &mut rng, Relu, NeuraL0) as NeuraDenseLayer<f64, _, _>,
+            NeuraDenseLayer::from_rng(3, 1, &mut rng, Relu, NeuraL0) as NeuraDenseLayer<f64, _, _>
+        ]
+        .lockable();
+
+        assert_eq!(network.layer_count(), 3);
+        network.lock_layers(..network.layer_count() - 1);
+        assert_eq!(network.locked_layers(), vec![0, 1]);
+        assert_eq!(network.summary().trainable_parameters(), 3 + 1);
+
+        // The gradient of locked layers isn't computed
+        let gradient = NeuraBackprop::new(Euclidean).get_gradient(
+            &network,
+            &dvector![1.0, 2.0],
+            &dvector![-1.0],
+        );
+        assert!(gradient.0 .0.iter().all(|&x| x == 0.0));
+        assert!(gradient.1 .0 .0.iter().all(|&x| x == 0.0));
+
+        // Locked layers ignore their gradient
+        let first_weights = network.layer.get().weights.clone();
+        let last_weights = network
+            .child_network
+            .child_network
+            .layer
+            .get()
+            .weights
+            .clone();
+        let mut gradient = network.default_gradient();
+        gradient.0 .0.fill(1.0);
+        gradient.1 .1 .0 .0.fill(1.0);
+        network.apply_gradient(&gradient);
+        assert_eq!(network.layer.get().weights, first_weights);
+        assert_ne!(
+            network.child_network.child_network.layer.get().weights,
+            last_weights
+        );
+
+        network.unlock_layers(1..);
+        assert_eq!(network.locked_layers(), vec![0]);
+        network.lock_where(|_, layer| layer.parameter_count() == 4);
+        assert_eq!(network.locked_layers(), vec![0, 2]);
+
+        network.unlock();
+        assert!(network.locked_layers().is_empty());
+        network.apply_gradient(&gradient);
+        assert_ne!(network.layer.get().weights, first_weights);
+    }
+
+    #[test]
+    fn test_learning_rate_multiplier() {
+        let mut rng = rand::thread_rng();
+
+        let backbone = neura_sequential![
+            NeuraDenseLayer::from_rng(2, 3, &mut rng, Relu, NeuraL0) as NeuraDenseLayer<f64, _, _>,
+            NeuraDenseLayer::from_rng(3, 3, &mut rng, Relu, NeuraL0) as NeuraDenseLayer<f64, _, _>
+        ];
+        let mut network = neura_sequential![
+            ..backbone.learning_rate_multiplier(0.5),
+            NeuraDenseLayer::from_rng(3, 1, &mut rng, Relu, NeuraL0) as NeuraDenseLayer<f64, _, _>
+        ];
+        assert_eq!(network.summary().len(), 3);
+
+        let first_weights = network.layer.get().weights.clone();
+        let last_weights = network.child_network.child_network.layer.weights.clone();
+        let mut gradient = network.default_gradient();
+        gradient.0 .0.fill(1.0);
+        gradient.1 .1 .0 .0.fill(1.0);
+        network.apply_gradient(&gradient);
+
+        assert_eq!(network.layer.get().weights, first_weights.add_scalar(0.5));
+        assert_eq!(
+            network.child_network.child_network.layer.weights,
+            last_weights.add_scalar(1.0)
+        );
+    }
+
+    #[test]
+    fn test_prune_neurons() {
+        let mut network = neura_sequential![
+            NeuraDenseLayer::new(
+                dmatrix![1.0, 0.0; 0.1, 0.1; 0.0, -2.0],
+                dvector![0.0, 0.5, 0.0],
+                Linear,
+                NeuraL0
+            ),
+            NeuraDenseLayer::new(
+                dmatrix![1.0, 3.0, 0.5; 0.0, 4.0, -1.5],
+                dvector![0.0, 0.0],
+                Linear,
+                NeuraL0
+            ),
+            NeuraDenseLayer::new(dmatrix![1.0, 2.0], dvector![0.0], Linear, NeuraL0)
+        ];
+
+        network.prune_neurons(0.4);
+
+        // The neurons with the smallest norm are removed, along with the matching inputs of the next layer;
+        // the last layer is never pruned
+        assert_eq!(network.layer.weights, dmatrix![1.0, 0.0; 0.0, -2.0]);
+        assert_eq!(network.layer.output_shape(), NeuraShape::Vector(2));
+        assert_eq!(network.child_network.layer.weights, dmatrix![0.0, -1.5]);
+        assert_eq!(
+            network.child_network.layer.output_shape(),
+            NeuraShape::Vector(1)
+        );
+        assert_eq!(
+            network.child_network.child_network.layer.weights,
+            dmatrix![2.0]
+        );
+        assert_eq!(network.eval(&dvector![1.0, 1.0]), dvector![6.0]);
+
+        // Unstructured pruning applies a single threshold to the whole network
+        network.prune_magnitude(0.5);
+        assert_eq!(network.sparsity(), 4.0 / 7.0);
+        assert_eq!(network.layer.weights, dmatrix![0.0, 0.0; 0.0, -2.0]);
+        assert_eq!(network.child_network.layer.weights, dmatrix![0.0, -1.5]);
+    }
+
+    #[test]
+    fn test_prune_locked() {
+        let mut network = neura_sequential![
+            NeuraDenseLayer::new(
+                dmatrix![0.1, 0.2; 0.3, 0.4],
+                dvector![0.0, 0.0],
+                Linear,
+                NeuraL0
+            )
+            .lock_layer(),
+            NeuraDenseLayer::new(dmatrix![1.0, 2.0], dvector![0.0], Linear, NeuraL0)
+        ];
+
+        // Locked layers are neither pruned nor counted in the sparsity
+        network.prune_magnitude(0.5);
+        assert_eq!(network.sparsity(), 0.5);
+        assert_eq!(network.layer.get().weights, dmatrix![0.1, 0.2; 0.3, 0.4]);
+        assert_eq!(network.child_network.layer.weights, dmatrix![0.0, 2.0]);
+    }
 }
@@ -0,0 +1,45 @@
+use crate::layer::precision::{NeuraConvertPrecision, NeuraMixedPrecision};
+
+use super::*;
+
+impl<F> NeuraConvertPrecision<F> for NeuraSequentialLast {
+    type Converted = Self;
+
+    fn convert_precision(&self) -> Self::Converted {
+        *self
+    }
+}
+
+impl<F, Layer: NeuraConvertPrecision<F>, ChildNetwork: NeuraConvertPrecision<F>>
+    NeuraConvertPrecision<F> for NeuraSequential<Layer, ChildNetwork>
+{
+    type Converted = NeuraSequential<Layer::Converted, ChildNetwork::Converted>;
+
+    fn convert_precision(&self) -> Self::Converted {
+        NeuraSequential {
+            layer: self.layer.convert_precision(),
+            child_network: Box::new(self.child_network.convert_precision()),
+        }
+    }
+}
+
+impl<H> NeuraMixedPrecision<H> for NeuraSequentialLast {
+    type Mixed = Self;
+
+    fn mixed_precision(&self, _loss_scale: f32) -> Self::Mixed {
+        *self
+    }
+}
+
+impl<H, Layer: NeuraMixedPrecision<H>, ChildNetwork: NeuraMixedPrecision<H>> NeuraMixedPrecision<H>
+    for NeuraSequential<Layer, ChildNetwork>
+{
+    type Mixed = NeuraSequential<Layer::Mixed, ChildNetwork::Mixed>;
+
+    fn mixed_precision(&self, loss_scale: f32) -> Self::Mixed {
+        NeuraSequential {
+            layer: self.layer.mixed_precision(loss_scale),
+            child_network: Box::new(self.child_network.mixed_precision(loss_scale)),
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use crate::layer::prune::NeuraPrunable;
+
+use super::*;
+
+impl NeuraPruneNeurons for NeuraSequentialLast {
+    fn first_prunable_mut(&mut self) -> Option<&mut dyn NeuraPrunable> {
+        None
+    }
+
+    fn prune_neurons_with(&mut self, _select: &mut dyn FnMut(&dyn NeuraPrunable) -> Vec<usize>) {
+        // Noop
+    }
+}
+
+impl<Layer: NeuraLayerBase, ChildNetwork: NeuraPruneNeurons> NeuraPruneNeurons
+    for NeuraSequential<Layer, ChildNetwork>
+{
+    fn first_prunable_mut(&mut self) -> Option<&mut dyn NeuraPrunable> {
+        self.layer.prunable_mut()
+    }
+
+    fn prune_neurons_with(&mut self, select: &mut dyn FnMut(&dyn NeuraPrunable) -> Vec<usize>) {
+        if let (Some(layer), Some(next_layer)) = (
+            self.layer.prunable_mut(),
+            self.child_network.first_prunable_mut(),
+        ) {
+            let neurons = select(layer);
+            layer.retain_neurons(&neurons);
+            next_layer.retain_inputs(&neurons);
+        }
+
+        self.child_network.prune_neurons_with(select);
+    }
+}
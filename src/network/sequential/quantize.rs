@@ -0,0 +1,49 @@
+use nalgebra::DVector;
+
+use crate::layer::quantize::NeuraQuantizable;
+
+use super::*;
+
+/// Converts a trained network into an int8 inference-only network, see `NeuraQuantizedDense`.
+///
+/// Every layer of the network must implement `NeuraQuantizable`.
+/// The inputs of each layer are calibrated by evaluating the original network on a sample of inputs.
+pub trait NeuraSequentialQuantize {
+    type Quantized;
+
+    /// Quantizes the network, given `calibration`, a sample of the inputs of its first layer
+    fn quantize_from(&self, calibration: Vec<DVector<f32>>) -> Self::Quantized;
+
+    /// Quantizes the network, using the inputs of `calibration` to estimate the range of the inputs of each layer.
+    /// A few hundred inputs representative of the training set are usually enough.
+    fn quantize(&self, calibration: impl IntoIterator<Item = DVector<f32>>) -> Self::Quantized {
+        self.quantize_from(calibration.into_iter().collect())
+    }
+}
+
+impl NeuraSequentialQuantize for NeuraSequentialLast {
+    type Quantized = NeuraSequentialLast;
+
+    fn quantize_from(&self, _calibration: Vec<DVector<f32>>) -> Self::Quantized {
+        *self
+    }
+}
+
+impl<Layer: NeuraQuantizable, ChildNetwork: NeuraSequentialQuantize> NeuraSequentialQuantize
+    for NeuraSequential<Layer, ChildNetwork>
+{
+    type Quantized = NeuraSequential<Layer::Quantized, ChildNetwork::Quantized>;
+
+    fn quantize_from(&self, calibration: Vec<DVector<f32>>) -> Self::Quantized {
+        let layer = self.layer.quantize_layer(&calibration);
+        let outputs = calibration
+            .iter()
+            .map(|input| self.layer.eval(input))
+            .collect();
+
+        NeuraSequential {
+            layer,
+            child_network: Box::new(self.child_network.quantize_from(outputs)),
+        }
+    }
+}
@@ -27,6 +27,20 @@ impl NeuraLayerBase for NeuraSequentialLast {
             .expect("Called NeuraSequentialLast::output_shape() without building it")
     }
 
+    #[inline(always)]
+    fn accept_visitor(&self, _input_shapes: &[NeuraShape], _visitor: &mut dyn NeuraLayerVisitor) {
+        // Noop
+    }
+
+    #[inline(always)]
+    fn accept_visitor_mut(
+        &mut self,
+        _input_shapes: &[NeuraShape],
+        _visitor: &mut dyn NeuraLayerVisitorMut,
+    ) {
+        // Noop
+    }
+
     #[inline(always)]
     fn default_gradient(&self) -> Self::Gradient {
         
@@ -0,0 +1,193 @@
+use std::fmt::{self, Display};
+
+use super::{NeuraLayerInfo, NeuraLayerVisitor, NeuraVisitContext};
+use crate::layer::NeuraShape;
+
+/// A row of a `NeuraSummary`, describing a single layer
+#[derive(Clone, Debug, PartialEq)]
+pub struct NeuraSummaryRow {
+    /// The type of the layer, without its module path
+    pub layer: String,
+
+    /// The shapes of the inputs of the layer; residual and graph nodes can have several.
+    /// Empty if the shape isn't known, which can be the case for the first layer of a `NeuraSequential`.
+    pub input_shapes: Vec<NeuraShape>,
+
+    pub output_shape: NeuraShape,
+
+    /// The number of trainable parameters of the layer, as given by the size of its gradient
+    pub parameters: usize,
+
+    /// Whether the layer is wrapped in a `NeuraLockLayer`
+    pub locked: bool,
+}
+
+/// A table of the layers of a constructed network, as returned by `NeuraLayerBase::summary`.
+///
+/// Unlike the `Debug` implementation of networks, it only prints the type, shapes and parameter count of each layer.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NeuraSummary {
+    pub rows: Vec<NeuraSummaryRow>,
+}
+
+impl NeuraSummary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// The total number of parameters of the network, including locked ones
+    pub fn total_parameters(&self) -> usize {
+        self.rows.iter().map(|row| row.parameters).sum()
+    }
+
+    /// The number of parameters that aren't locked
+    pub fn trainable_parameters(&self) -> usize {
+        self.rows
+            .iter()
+            .filter(|row| !row.locked)
+            .map(|row| row.parameters)
+            .sum()
+    }
+
+    pub fn locked_parameters(&self) -> usize {
+        self.total_parameters() - self.trainable_parameters()
+    }
+}
+
+impl NeuraLayerVisitor for NeuraSummary {
+    fn visit(&mut self, layer: &dyn NeuraLayerInfo, context: &NeuraVisitContext) {
+        self.rows.push(NeuraSummaryRow {
+            layer: short_type_name(layer.name()),
+            input_shapes: context.input_shapes.to_vec(),
+            output_shape: layer.shape(),
+            parameters: layer.parameter_count(),
+            locked: context.locked,
+        });
+    }
+}
+
+impl Display for NeuraSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const HEADER: [&str; 5] = [
+            "Layer",
+            "Input shape",
+            "Output shape",
+            "Parameters",
+            "Locked",
+        ];
+
+        let cells: Vec<[String; 5]> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let input_shapes = if row.input_shapes.is_empty() {
+                    String::from("?")
+                } else {
+                    row.input_shapes
+                        .iter()
+                        .map(format_shape)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+
+                [
+                    row.layer.clone(),
+                    input_shapes,
+                    format_shape(&row.output_shape),
+                    row.parameters.to_string(),
+                    String::from(if row.locked { "yes" } else { "" }),
+                ]
+            })
+            .collect();
+
+        let mut widths = HEADER.map(|name| name.chars().count());
+        for row in cells.iter() {
+            for (width, cell) in widths.iter_mut().zip(row.iter()) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        let separator: String = widths
+            .iter()
+            .map(|width| "─".repeat(width + 2))
+            .collect::<Vec<_>>()
+            .join("┼");
+
+        let write_row = |f: &mut fmt::Formatter<'_>, row: &[&str]| -> fmt::Result {
+            for (i, (cell, width)) in row.iter().zip(widths.iter()).enumerate() {
+                if i > 0 {
+                    write!(f, "│")?;
+                }
+                // Parameter counts are right-aligned
+                if i == 3 {
+                    write!(f, " {:>width$} ", cell, width = width)?;
+                } else {
+                    write!(f, " {:<width$} ", cell, width = width)?;
+                }
+            }
+            writeln!(f)
+        };
+
+        write_row(f, &HEADER)?;
+        writeln!(f, "{}", separator)?;
+        for row in cells.iter() {
+            write_row(f, &row.each_ref().map(String::as_str))?;
+        }
+        writeln!(f, "{}", separator)?;
+
+        writeln!(f, "Total parameters: {}", self.total_parameters())?;
+        writeln!(f, "Trainable parameters: {}", self.trainable_parameters())?;
+        write!(f, "Locked parameters: {}", self.locked_parameters())
+    }
+}
+
+fn format_shape(shape: &NeuraShape) -> String {
+    let dims: Vec<_> = shape.dimensions().iter().map(|dim| dim.to_string()).collect();
+    format!("({})", dims.join(", "))
+}
+
+/// Strips the module paths from a type name, turning `a::b::Foo<c::Bar>` into `Foo<Bar>`
+fn short_type_name(name: &str) -> String {
+    let mut res = String::with_capacity(name.len());
+    // Where the current path started in `res`, and whether a `::` was just encountered
+    let mut segment_start = 0;
+    let mut in_path = false;
+
+    for ch in name.chars() {
+        if ch == ':' {
+            in_path = true;
+        } else if ch.is_alphanumeric() || ch == '_' {
+            if in_path {
+                res.truncate(segment_start);
+                in_path = false;
+            }
+            res.push(ch);
+        } else {
+            res.push(ch);
+            segment_start = res.len();
+        }
+    }
+
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_short_type_name() {
+        assert_eq!(short_type_name("Foo"), "Foo");
+        assert_eq!(
+            short_type_name("a::b::Foo<f32, c::Bar, (d::Baz, ())>"),
+            "Foo<f32, Bar, (Baz, ())>"
+        );
+    }
+}
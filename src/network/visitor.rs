@@ -0,0 +1,202 @@
+use std::{any::Any, fmt::Debug};
+
+use crate::{
+    algebra::NeuraVectorSpace,
+    layer::{prune::NeuraPrunable, *},
+};
+
+/// A type-erased view of a layer, as passed to `NeuraLayerVisitor` and `NeuraLayerVisitorMut`.
+///
+/// It is implemented for every `NeuraLayerBase`; use `downcast_ref` or `downcast_mut` to get back the concrete layer.
+pub trait NeuraLayerInfo: Debug {
+    /// The full type name of the layer, see `std::any::type_name`
+    fn name(&self) -> &'static str;
+
+    /// The output shape of the layer, see `NeuraLayerBase::output_shape`
+    fn shape(&self) -> NeuraShape;
+
+    /// The number of trainable parameters of the layer, as given by the size of its gradient
+    fn parameter_count(&self) -> usize;
+
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    /// See `NeuraLayerBase::prunable`
+    fn as_prunable(&self) -> Option<&dyn NeuraPrunable>;
+
+    /// See `NeuraLayerBase::prunable_mut`
+    fn as_prunable_mut(&mut self) -> Option<&mut dyn NeuraPrunable>;
+}
+
+impl<Layer: NeuraLayerBase> NeuraLayerInfo for Layer {
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Layer>()
+    }
+
+    fn shape(&self) -> NeuraShape {
+        self.output_shape()
+    }
+
+    fn parameter_count(&self) -> usize {
+        let mut res = 0;
+        self.default_gradient().map_entries(&mut |x| {
+            res += 1;
+            x
+        });
+        res
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn as_prunable(&self) -> Option<&dyn NeuraPrunable> {
+        self.prunable()
+    }
+
+    fn as_prunable_mut(&mut self) -> Option<&mut dyn NeuraPrunable> {
+        self.prunable_mut()
+    }
+}
+
+impl dyn NeuraLayerInfo + '_ {
+    pub fn downcast_ref<Layer: 'static>(&self) -> Option<&Layer> {
+        self.as_any().downcast_ref()
+    }
+
+    pub fn downcast_mut<Layer: 'static>(&mut self) -> Option<&mut Layer> {
+        self.as_any_mut().downcast_mut()
+    }
+}
+
+/// Where a visited layer sits within its network
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NeuraVisitContext<'a> {
+    /// The shapes of the inputs of the layer; residual and graph nodes can have several.
+    /// Empty if the shape isn't known, which can be the case for the first layer of a `NeuraSequential`.
+    pub input_shapes: &'a [NeuraShape],
+
+    /// Whether the layer is wrapped in a `NeuraLockLayer`
+    pub locked: bool,
+}
+
+impl<'a> NeuraVisitContext<'a> {
+    pub fn new(input_shapes: &'a [NeuraShape]) -> Self {
+        Self {
+            input_shapes,
+            locked: false,
+        }
+    }
+}
+
+/// Called on each layer of a network by `NeuraLayerBase::visit_layers`, in order of evaluation.
+///
+/// Networks themselves (`NeuraSequential`, `NeuraResidual`, `NeuraGraph`) are not visited, only the layers they contain.
+pub trait NeuraLayerVisitor {
+    fn visit(&mut self, layer: &dyn NeuraLayerInfo, context: &NeuraVisitContext);
+}
+
+/// Called on each layer of a network by `NeuraLayerBase::visit_layers_mut`, see `NeuraLayerVisitor`.
+///
+/// The shapes of the layers are read before visiting them, so visitors changing the output shape of a layer
+/// will cause the following layers to receive stale `input_shapes`.
+pub trait NeuraLayerVisitorMut {
+    fn visit_mut(&mut self, layer: &mut dyn NeuraLayerInfo, context: &NeuraVisitContext);
+}
+
+impl<F: FnMut(&dyn NeuraLayerInfo, &NeuraVisitContext)> NeuraLayerVisitor for F {
+    fn visit(&mut self, layer: &dyn NeuraLayerInfo, context: &NeuraVisitContext) {
+        self(layer, context)
+    }
+}
+
+impl<F: FnMut(&mut dyn NeuraLayerInfo, &NeuraVisitContext)> NeuraLayerVisitorMut for F {
+    fn visit_mut(&mut self, layer: &mut dyn NeuraLayerInfo, context: &NeuraVisitContext) {
+        self(layer, context)
+    }
+}
+
+/// Marks the visited layers as locked, used by `NeuraLockLayer`
+pub(crate) struct NeuraLockedVisitor<'a>(pub &'a mut dyn NeuraLayerVisitor);
+
+impl NeuraLayerVisitor for NeuraLockedVisitor<'_> {
+    fn visit(&mut self, layer: &dyn NeuraLayerInfo, context: &NeuraVisitContext) {
+        self.0.visit(
+            layer,
+            &NeuraVisitContext {
+                locked: true,
+                ..*context
+            },
+        );
+    }
+}
+
+/// Marks the visited layers as locked, used by `NeuraLockLayer`
+pub(crate) struct NeuraLockedVisitorMut<'a>(pub &'a mut dyn NeuraLayerVisitorMut);
+
+impl NeuraLayerVisitorMut for NeuraLockedVisitorMut<'_> {
+    fn visit_mut(&mut self, layer: &mut dyn NeuraLayerInfo, context: &NeuraVisitContext) {
+        self.0.visit_mut(
+            layer,
+            &NeuraVisitContext {
+                locked: true,
+                ..*context
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nalgebra::{dmatrix, dvector};
+
+    use super::*;
+    use crate::{
+        derivable::{activation::Relu, regularize::NeuraL0},
+        layer::dense::NeuraDenseLayer,
+        neura_sequential,
+    };
+
+    #[test]
+    fn test_visit_layers() {
+        let mut network = neura_sequential![
+            NeuraDenseLayer::new(
+                dmatrix![1.0, 2.0; 3.0, 4.0],
+                dvector![0.0, 0.0],
+                Relu,
+                NeuraL0
+            )
+            .lock_layer(),
+            NeuraDenseLayer::new(dmatrix![1.0, -1.0], dvector![0.5], Relu, NeuraL0)
+        ];
+
+        let mut visited = Vec::new();
+        network.visit_layers(
+            &mut |layer: &dyn NeuraLayerInfo, context: &NeuraVisitContext| {
+                visited.push((layer.shape(), layer.parameter_count(), context.locked));
+            },
+        );
+        assert_eq!(
+            visited,
+            vec![
+                (NeuraShape::Vector(2), 6, true),
+                (NeuraShape::Vector(1), 3, false)
+            ]
+        );
+
+        // Zero out the bias of every dense layer
+        network.visit_layers_mut(
+            &mut |layer: &mut dyn NeuraLayerInfo, _context: &NeuraVisitContext| {
+                if let Some(dense) = layer.downcast_mut::<NeuraDenseLayer<f64, Relu, NeuraL0>>() {
+                    dense.bias.fill(0.0);
+                }
+            },
+        );
+        assert_eq!(network.child_network.layer.bias, dvector![0.0]);
+    }
+}
@@ -0,0 +1,445 @@
+//! Preprocessing transforms, fit on the training data and then applied to both the training and the test data.
+//!
+//! All of the transforms are invertible (to recover regression outputs for instance), serializable,
+//! and can be turned into a frozen layer with `NeuraTransform::into_layer`, to be prepended to a network:
+//!
+//! ```
+//! use neuramethyst::{prelude::*, preprocess::{NeuraStandardScaler, NeuraTransform}};
+//! use nalgebra::dvector;
+//!
+//! let train_inputs = vec![dvector![1.0, 200.0], dvector![3.0, 400.0], dvector![2.0, 300.0]];
+//! let scaler = NeuraStandardScaler::fit(&train_inputs).unwrap();
+//!
+//! let network = neura_sequential![neura_layer!("dense", 1, f64)]
+//!     .construct(NeuraShape::Vector(2))
+//!     .unwrap()
+//!     .push_front(scaler.into_layer());
+//!
+//! let output = network.eval(&dvector![2.5, 250.0]);
+//! ```
+
+use std::borrow::Borrow;
+
+use nalgebra::{DMatrix, DVector, RealField, Scalar};
+use num::Float;
+use serde::{Deserialize, Serialize};
+
+use crate::{dataset::NeuraDataset, layer::transform::NeuraTransformLayer};
+
+pub trait NeuraTransform<F: Scalar> {
+    /// The length of the vectors accepted by `transform`
+    fn input_len(&self) -> usize;
+
+    /// The length of the vectors returned by `transform`
+    fn output_len(&self) -> usize;
+
+    fn transform(&self, input: &DVector<F>) -> DVector<F>;
+
+    /// Should return `input` such that `self.transform(input) == output`
+    fn inverse_transform(&self, output: &DVector<F>) -> DVector<F>;
+
+    /// Should return `J^T * epsilon`, where `J` is the jacobian matrix of `transform`;
+    /// this is used by `NeuraTransformLayer` to backpropagate through the transform.
+    fn backprop(&self, epsilon: &DVector<F>) -> DVector<F>;
+
+    /// Applies the transform to the inputs of `dataset`
+    fn transform_inputs<Target>(
+        &self,
+        dataset: &(impl NeuraDataset<Input = DVector<F>, Target = Target> + ?Sized),
+    ) -> Vec<(DVector<F>, Target)> {
+        dataset
+            .pairs()
+            .map(|(input, target)| (self.transform(&input), target))
+            .collect()
+    }
+
+    /// Applies the transform to the targets of `dataset`;
+    /// the outputs of a network trained on them can be mapped back with `inverse_transform`.
+    fn transform_targets<Input>(
+        &self,
+        dataset: &(impl NeuraDataset<Input = Input, Target = DVector<F>> + ?Sized),
+    ) -> Vec<(Input, DVector<F>)> {
+        dataset
+            .pairs()
+            .map(|(input, target)| (input, self.transform(&target)))
+            .collect()
+    }
+
+    /// Wraps the transform in a frozen layer
+    fn into_layer(self) -> NeuraTransformLayer<Self>
+    where
+        Self: Sized,
+    {
+        NeuraTransformLayer::new(self)
+    }
+}
+
+/// Standardizes each feature, by removing its mean and dividing it by its standard deviation.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NeuraStandardScaler<F: Scalar> {
+    pub mean: DVector<F>,
+    /// The standard deviation of each feature; constant features have a standard deviation of `1`
+    pub stddev: DVector<F>,
+}
+
+impl<F: Float + Scalar> NeuraStandardScaler<F> {
+    /// Computes the mean and standard deviation of each feature of `inputs`; returns `None` if `inputs` is empty.
+    pub fn fit(inputs: impl IntoIterator<Item = impl Borrow<DVector<F>>>) -> Option<Self> {
+        let mut inputs = inputs.into_iter();
+        let first = inputs.next()?;
+        let first = first.borrow();
+
+        // Welford's online algorithm
+        let mut count = F::one();
+        let mut mean = first.clone();
+        let mut m2 = DVector::from_element(first.len(), F::zero());
+
+        for input in inputs {
+            let input = input.borrow();
+            count = count + F::one();
+
+            for i in 0..mean.len() {
+                let delta = input[i] - mean[i];
+                mean[i] = mean[i] + delta / count;
+                m2[i] = m2[i] + delta * (input[i] - mean[i]);
+            }
+        }
+
+        let stddev = m2.map(|m2| {
+            let stddev = (m2 / count).sqrt();
+            if stddev > F::zero() {
+                stddev
+            } else {
+                F::one()
+            }
+        });
+
+        Some(Self { mean, stddev })
+    }
+}
+
+impl<F: Float + Scalar> NeuraTransform<F> for NeuraStandardScaler<F> {
+    fn input_len(&self) -> usize {
+        self.mean.len()
+    }
+
+    fn output_len(&self) -> usize {
+        self.mean.len()
+    }
+
+    fn transform(&self, input: &DVector<F>) -> DVector<F> {
+        DVector::from_fn(input.len(), |i, _| {
+            (input[i] - self.mean[i]) / self.stddev[i]
+        })
+    }
+
+    fn inverse_transform(&self, output: &DVector<F>) -> DVector<F> {
+        DVector::from_fn(output.len(), |i, _| {
+            output[i] * self.stddev[i] + self.mean[i]
+        })
+    }
+
+    fn backprop(&self, epsilon: &DVector<F>) -> DVector<F> {
+        epsilon.zip_map(&self.stddev, |epsilon, stddev| epsilon / stddev)
+    }
+}
+
+/// Linearly maps each feature from `[min; max]` (as found in the training data) to `range`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NeuraMinMaxScaler<F: Scalar> {
+    pub min: DVector<F>,
+    pub max: DVector<F>,
+    /// Defaults to `(0, 1)`
+    pub range: (F, F),
+}
+
+impl<F: Float + Scalar> NeuraMinMaxScaler<F> {
+    /// Computes the minimum and maximum of each feature of `inputs`; returns `None` if `inputs` is empty.
+    pub fn fit(inputs: impl IntoIterator<Item = impl Borrow<DVector<F>>>) -> Option<Self> {
+        let mut inputs = inputs.into_iter();
+        let first = inputs.next()?;
+
+        let mut min = first.borrow().clone();
+        let mut max = min.clone();
+
+        for input in inputs {
+            let input = input.borrow();
+
+            for i in 0..min.len() {
+                min[i] = min[i].min(input[i]);
+                max[i] = max[i].max(input[i]);
+            }
+        }
+
+        Some(Self {
+            min,
+            max,
+            range: (F::zero(), F::one()),
+        })
+    }
+
+    pub fn range(mut self, low: F, high: F) -> Self {
+        self.range = (low, high);
+        self
+    }
+
+    /// Returns the factor by which the `i`-th feature is multiplied
+    #[inline(always)]
+    fn scale(&self, i: usize) -> F {
+        let width = self.max[i] - self.min[i];
+
+        if width > F::zero() {
+            (self.range.1 - self.range.0) / width
+        } else {
+            F::one()
+        }
+    }
+}
+
+impl<F: Float + Scalar> NeuraTransform<F> for NeuraMinMaxScaler<F> {
+    fn input_len(&self) -> usize {
+        self.min.len()
+    }
+
+    fn output_len(&self) -> usize {
+        self.min.len()
+    }
+
+    fn transform(&self, input: &DVector<F>) -> DVector<F> {
+        DVector::from_fn(input.len(), |i, _| {
+            (input[i] - self.min[i]) * self.scale(i) + self.range.0
+        })
+    }
+
+    fn inverse_transform(&self, output: &DVector<F>) -> DVector<F> {
+        DVector::from_fn(output.len(), |i, _| {
+            (output[i] - self.range.0) / self.scale(i) + self.min[i]
+        })
+    }
+
+    fn backprop(&self, epsilon: &DVector<F>) -> DVector<F> {
+        DVector::from_fn(epsilon.len(), |i, _| epsilon[i] * self.scale(i))
+    }
+}
+
+/// Decorrelates the features and scales them to unit variance, using the principal components of the training data.
+///
+/// The inputs are transformed as `x -> Λ^(-1/2) * U^T * (x - mean)`, where `U` and `Λ` are the eigenvectors and eigenvalues
+/// of the covariance matrix of the training data. `epsilon` is added to the eigenvalues to avoid dividing by zero.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NeuraPcaWhitening<F: Scalar> {
+    pub mean: DVector<F>,
+    /// The whitening matrix, `Λ^(-1/2) * U^T`
+    pub whitening: DMatrix<F>,
+    /// The inverse of the whitening matrix, `U * Λ^(1/2)`
+    pub coloring: DMatrix<F>,
+}
+
+impl<F: RealField + Copy> NeuraPcaWhitening<F> {
+    /// Computes the principal components of `inputs`; returns `None` if `inputs` is empty.
+    pub fn fit(
+        inputs: impl IntoIterator<Item = impl Borrow<DVector<F>>>,
+        epsilon: F,
+    ) -> Option<Self> {
+        let inputs: Vec<DVector<F>> = inputs.into_iter().map(|x| x.borrow().clone()).collect();
+        let first = inputs.first()?;
+        let count = F::from_subset(&(inputs.len() as f64));
+
+        let mut mean = DVector::zeros(first.len());
+        for input in inputs.iter() {
+            mean += input;
+        }
+        mean /= count;
+
+        let mut covariance = DMatrix::zeros(first.len(), first.len());
+        for input in inputs.iter() {
+            let centered = input - &mean;
+            covariance += &centered * centered.transpose();
+        }
+        covariance /= count;
+
+        let eigen = covariance.symmetric_eigen();
+        let scale = eigen
+            .eigenvalues
+            .map(|x| (x.max(F::zero()) + epsilon).sqrt());
+
+        let whitening =
+            DMatrix::from_diagonal(&scale.map(|x| F::one() / x)) * eigen.eigenvectors.transpose();
+        let coloring = eigen.eigenvectors * DMatrix::from_diagonal(&scale);
+
+        Some(Self {
+            mean,
+            whitening,
+            coloring,
+        })
+    }
+}
+
+impl<F: RealField + Copy> NeuraTransform<F> for NeuraPcaWhitening<F> {
+    fn input_len(&self) -> usize {
+        self.mean.len()
+    }
+
+    fn output_len(&self) -> usize {
+        self.whitening.nrows()
+    }
+
+    fn transform(&self, input: &DVector<F>) -> DVector<F> {
+        &self.whitening * (input - &self.mean)
+    }
+
+    fn inverse_transform(&self, output: &DVector<F>) -> DVector<F> {
+        &self.coloring * output + &self.mean
+    }
+
+    fn backprop(&self, epsilon: &DVector<F>) -> DVector<F> {
+        self.whitening.tr_mul(epsilon)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::dvector;
+
+    use super::*;
+    use crate::{
+        derivable::loss::Euclidean, gradient_solver::gradient_check_layer, prelude::*,
+        utils::uniform_vector,
+    };
+
+    fn inputs() -> Vec<DVector<f64>> {
+        (0..50)
+            .map(|_| {
+                let x = uniform_vector(3);
+                // Correlated and offset features
+                dvector![x[0] * 10.0 + 5.0, x[0] + x[1] * 0.1, x[2] - 3.0]
+            })
+            .collect()
+    }
+
+    fn covariance(outputs: &[DVector<f64>]) -> (DVector<f64>, DMatrix<f64>) {
+        let count = outputs.len() as f64;
+        let mean = outputs.iter().sum::<DVector<f64>>() / count;
+        let covariance = outputs
+            .iter()
+            .map(|x| (x - &mean) * (x - &mean).transpose())
+            .sum::<DMatrix<f64>>()
+            / count;
+
+        (mean, covariance)
+    }
+
+    #[test]
+    fn test_standard_scaler() {
+        let inputs = inputs();
+        let scaler = NeuraStandardScaler::fit(&inputs).unwrap();
+
+        let outputs: Vec<_> = inputs.iter().map(|x| scaler.transform(x)).collect();
+        let (mean, covariance) = covariance(&outputs);
+        assert_relative_eq!(mean, DVector::zeros(3), epsilon = 1e-10);
+        assert_relative_eq!(
+            covariance.diagonal(),
+            DVector::from_element(3, 1.0),
+            epsilon = 1e-10
+        );
+
+        assert_relative_eq!(
+            scaler.inverse_transform(&outputs[7]),
+            inputs[7],
+            epsilon = 1e-10
+        );
+        assert!(NeuraStandardScaler::<f64>::fit(Vec::<DVector<f64>>::new()).is_none());
+
+        // Constant features are left unscaled
+        let scaler = NeuraStandardScaler::fit([dvector![1.0], dvector![1.0]]).unwrap();
+        assert_eq!(scaler.transform(&dvector![3.0]), dvector![2.0]);
+    }
+
+    #[test]
+    fn test_min_max_scaler() {
+        let inputs = inputs();
+        let scaler = NeuraMinMaxScaler::fit(&inputs).unwrap().range(-1.0, 1.0);
+
+        for input in inputs.iter() {
+            let output = scaler.transform(input);
+            assert!(output.iter().all(|&x| (-1.0..=1.0).contains(&x)));
+            assert_relative_eq!(scaler.inverse_transform(&output), input, epsilon = 1e-10);
+        }
+
+        assert_relative_eq!(
+            scaler.transform(&scaler.min),
+            DVector::from_element(3, -1.0)
+        );
+        assert_relative_eq!(scaler.transform(&scaler.max), DVector::from_element(3, 1.0));
+    }
+
+    #[test]
+    fn test_pca_whitening() {
+        let inputs = inputs();
+        let whitening = NeuraPcaWhitening::fit(&inputs, 0.0).unwrap();
+
+        let outputs: Vec<_> = inputs.iter().map(|x| whitening.transform(x)).collect();
+        let (mean, covariance) = covariance(&outputs);
+        assert_relative_eq!(mean, DVector::zeros(3), epsilon = 1e-8);
+        assert_relative_eq!(covariance, DMatrix::identity(3, 3), epsilon = 1e-8);
+
+        assert_relative_eq!(
+            whitening.inverse_transform(&outputs[3]),
+            inputs[3],
+            epsilon = 1e-8
+        );
+    }
+
+    #[test]
+    fn test_serialize() {
+        let inputs = inputs();
+        let scaler = NeuraMinMaxScaler::fit(&inputs).unwrap();
+
+        let json = serde_json::to_string(&scaler).unwrap();
+        let deserialized: NeuraMinMaxScaler<f64> = serde_json::from_str(&json).unwrap();
+        assert_relative_eq!(scaler.min, deserialized.min);
+        assert_relative_eq!(scaler.max, deserialized.max);
+        assert_eq!(scaler.range, deserialized.range);
+
+        let whitening = NeuraPcaWhitening::fit(&inputs, 1e-5).unwrap();
+        let json = serde_json::to_string(&whitening).unwrap();
+        let deserialized: NeuraPcaWhitening<f64> = serde_json::from_str(&json).unwrap();
+        assert_relative_eq!(whitening.mean, deserialized.mean);
+        assert_relative_eq!(whitening.whitening, deserialized.whitening);
+        assert_relative_eq!(whitening.coloring, deserialized.coloring);
+    }
+
+    #[test]
+    fn test_transform_layer() {
+        let inputs = inputs();
+        let scaler = NeuraStandardScaler::fit(&inputs).unwrap();
+        let dataset: Vec<_> = inputs.iter().map(|x| (x.clone(), dvector![1.0])).collect();
+
+        let network = neura_sequential![neura_layer!("dense", 1, f64)]
+            .construct(NeuraShape::Vector(3))
+            .unwrap();
+        let scaled_dataset = scaler.transform_inputs(&dataset);
+        let network = network.push_front(scaler.clone().into_layer());
+
+        assert_eq!(
+            network.eval(&dataset[0].0),
+            network.child_network.eval(&scaled_dataset[0].0)
+        );
+
+        let target = dvector![0.5, -0.5, 1.0];
+        let check =
+            gradient_check_layer(&scaler.into_layer(), &inputs[0], &target, Euclidean, 1e-6);
+        assert!(check.max_error() < 1e-4, "{:#?}", check);
+
+        let whitening = NeuraPcaWhitening::fit(&inputs, 1e-5).unwrap();
+        let check = gradient_check_layer(
+            &whitening.into_layer(),
+            &inputs[0],
+            &target,
+            Euclidean,
+            1e-6,
+        );
+        assert!(check.max_error() < 1e-4, "{:#?}", check);
+    }
+}
@@ -0,0 +1,140 @@
+//! Seedable random number generation, used to make the construction and the training of networks reproducible.
+//!
+//! All of the layers created by `neura_layer!` draw their randomness from `NeuraRng`,
+//! which reads from a generator shared by the current thread.
+//! Calling `seed` will make that generator deterministic, so that two runs with the same seed
+//! (and the same sequence of operations) produce bit-identical weights and losses:
+//!
+//! ```
+//! use neuramethyst::{prelude::*, rng::{self, NeuraRng}, cycle_shuffling};
+//!
+//! rng::seed(42);
+//! let network = neura_sequential![
+//!     neura_layer!("dense", 4),
+//!     neura_layer!("dropout", 0.2),
+//!     neura_layer!("dense", 1),
+//! ]
+//! .construct(NeuraShape::Vector(2))
+//! .unwrap();
+//! # let inputs: Vec<(nalgebra::DVector<f32>, nalgebra::DVector<f32>)> = vec![];
+//! // Use `NeuraRng` wherever a random number generator is expected:
+//! let inputs = cycle_shuffling(inputs.into_iter(), NeuraRng);
+//! ```
+//!
+//! If no seed was set, then `NeuraRng` falls back to `rand::thread_rng()`.
+//!
+//! Layers can also be given their own generator, for instance by passing a `rand::rngs::StdRng` to `NeuraDenseLayer::new_partial`.
+
+use std::cell::RefCell;
+
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+thread_local! {
+    static RNG: RefCell<Option<StdRng>> = const { RefCell::new(None) };
+}
+
+/// Seeds the random number generator of the current thread, used by `NeuraRng`.
+pub fn seed(seed: u64) {
+    RNG.with(|rng| {
+        *rng.borrow_mut() = Some(StdRng::seed_from_u64(seed));
+    });
+}
+
+/// Removes the seed set by `seed`, making `NeuraRng` fall back to `rand::thread_rng()`.
+pub fn unseed() {
+    RNG.with(|rng| {
+        *rng.borrow_mut() = None;
+    });
+}
+
+/// A handle to the random number generator of the current thread, see the module-level documentation.
+///
+/// All instances of `NeuraRng` share the same state, so cloning it does not duplicate the random sequence.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NeuraRng;
+
+impl NeuraRng {
+    #[inline]
+    fn with<T>(callback: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+        RNG.with(|rng| match rng.borrow_mut().as_mut() {
+            Some(rng) => callback(rng),
+            None => callback(&mut rand::thread_rng()),
+        })
+    }
+}
+
+impl RngCore for NeuraRng {
+    fn next_u32(&mut self) -> u32 {
+        Self::with(|rng| rng.next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        Self::with(|rng| rng.next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        Self::with(|rng| rng.fill_bytes(dest))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        Self::with(|rng| rng.try_fill_bytes(dest))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nalgebra::dvector;
+
+    use super::*;
+    use crate::{cycle_shuffling, derivable::loss::Euclidean, prelude::*};
+
+    #[test]
+    fn test_seeded_training() {
+        let run = || {
+            seed(1234);
+
+            let mut network = neura_sequential![
+                neura_layer!("dense", 6, f64),
+                neura_layer!("dropout", 0.3),
+                neura_layer!("dense", 1, f64),
+            ]
+            .construct(NeuraShape::Vector(2))
+            .unwrap();
+
+            let inputs = [
+                (dvector![0.0, 0.0], dvector![0.0]),
+                (dvector![0.0, 1.0], dvector![1.0]),
+                (dvector![1.0, 0.0], dvector![1.0]),
+                (dvector![1.0, 1.0], dvector![0.0]),
+            ];
+
+            let mut trainer = NeuraBatchedTrainer::new()
+                .batch_size(2)
+                .iterations(20)
+                .log_iterations(5);
+            trainer.learning_momentum = 0.01;
+
+            let report = trainer.train(
+                &NeuraBackprop::new(Euclidean),
+                &mut network,
+                cycle_shuffling(inputs.iter().cloned(), NeuraRng),
+                &inputs,
+            );
+
+            unseed();
+
+            (
+                network.layer.weights,
+                network.child_network.child_network.layer.weights,
+                report.losses(),
+            )
+        };
+
+        let first = run();
+        let second = run();
+
+        assert_eq!(first.0, second.0);
+        assert_eq!(first.1, second.1);
+        assert_eq!(first.2, second.2);
+    }
+}
@@ -0,0 +1,1056 @@
+use std::time::Instant;
+
+use nalgebra::{DVector, Scalar};
+use num::Float;
+use rand::Rng;
+
+use crate::{
+    algebra::NeuraVectorSpace,
+    dataset::NeuraDataset,
+    gradient_solver::NeuraGradientSolver,
+    layer::*,
+    metrics::{evaluate_metrics, NeuraMetric},
+};
+
+mod prune;
+pub use prune::*;
+
+mod report;
+pub use report::*;
+
+#[non_exhaustive]
+pub struct NeuraBatchedTrainer {
+    /// The learning rate of the gradient descent algorithm; the weights `W` will be updated as follows:
+    /// `W += -learning_rate * gradient_average`.
+    ///
+    /// Defaults to `0.1`
+    pub learning_rate: f64,
+
+    /// The momentum of the gradient descent algorithm; if set to a non-zero value, then the weights `W` will be updated as follows:
+    /// `W += -learning_rate * gradient_average - learning_momentum * previous_gradient`.
+    /// This value should be smaller than `learning_rate`.
+    ///
+    /// Defaults to `0.0`
+    pub learning_momentum: f64,
+
+    /// The decoupled weight decay factor (as introduced by AdamW); if set to a non-zero value,
+    /// then before each update, the weights `W` of layers supporting it (see `NeuraLayerBase::decay_layer`) will be updated as follows:
+    /// `W *= 1 - learning_rate * weight_decay`.
+    ///
+    /// Unlike the regularization of the layers, which is added to the gradient (scaled by `-learning_rate`)
+    /// and is thus subject to momentum and gradient clipping, weight decay is applied directly to the weights.
+    ///
+    /// Defaults to `0.0`
+    pub weight_decay: f64,
+
+    /// How many gradient computations to average before updating the weights
+    pub batch_size: usize,
+
+    /// How many batches to run for in `train`; if `iterations * batch_size` exceeds the input length, then training will stop.
+    /// You should use `cycle_shuffling` to avoid this, or use `train_epochs` instead.
+    ///
+    /// Note that this is different from epochs, which count how many times the dataset has been fully iterated over.
+    pub iterations: usize,
+
+    /// How many times `train_epochs` iterates over the dataset.
+    ///
+    /// Defaults to `1`
+    pub epochs: usize,
+
+    /// The trainer will log progress at every multiple of `log_iterations` iterations.
+    /// If `log_iterations` is zero (default), then no progress will be logged.
+    ///
+    /// The test inputs is used to measure the score of the network.
+    pub log_iterations: usize,
+
+    /// How progress is printed at each logging step; it is recorded in the returned `NeuraTrainingReport` regardless.
+    ///
+    /// Defaults to `NeuraTrainingLog::Compact`
+    pub logging: NeuraTrainingLog,
+
+    /// If set, each entry of the batch gradient will be clamped to `[-clip_value; clip_value]` before being applied.
+    ///
+    /// Defaults to `None`
+    pub clip_value: Option<f64>,
+
+    /// If set, the gradient of each layer will be rescaled so that its L2 norm does not exceed `clip_layer_norm`,
+    /// see `NeuraLayerBase::clip_layer_gradient`.
+    ///
+    /// Defaults to `None`
+    pub clip_layer_norm: Option<f64>,
+
+    /// If set, the batch gradient will be rescaled so that its global L2 norm does not exceed `clip_norm`.
+    ///
+    /// Clipping is applied to the gradient averaged over the batch, before it is multiplied by the learning rate
+    /// and before the regularization terms are added. If several clipping options are set,
+    /// then they are applied in the following order: `clip_value`, `clip_layer_norm`, `clip_norm`.
+    ///
+    /// Defaults to `None`
+    pub clip_norm: Option<f64>,
+
+    /// If set, the network will be pruned by magnitude during training, following the given schedule.
+    /// Iterations are counted in batches, across epochs for `train_epochs`.
+    ///
+    /// Defaults to `None`
+    pub pruning: Option<NeuraPruningSchedule>,
+}
+
+impl Default for NeuraBatchedTrainer {
+    fn default() -> Self {
+        Self {
+            learning_rate: 0.1,
+            learning_momentum: 0.0,
+            weight_decay: 0.0,
+            batch_size: 100,
+            iterations: 100,
+            epochs: 1,
+            log_iterations: 0,
+            logging: NeuraTrainingLog::default(),
+            clip_value: None,
+            clip_layer_norm: None,
+            clip_norm: None,
+            pruning: None,
+        }
+    }
+}
+
+impl NeuraBatchedTrainer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    pub fn learning_rate(mut self, learning_rate: f64) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    pub fn weight_decay(mut self, weight_decay: f64) -> Self {
+        self.weight_decay = weight_decay;
+        self
+    }
+
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    pub fn log_iterations(mut self, log_iterations: usize) -> Self {
+        self.log_iterations = log_iterations;
+        self
+    }
+
+    pub fn logging(mut self, logging: NeuraTrainingLog) -> Self {
+        self.logging = logging;
+        self
+    }
+
+    pub fn clip_value(mut self, max_value: f64) -> Self {
+        self.clip_value = Some(max_value);
+        self
+    }
+
+    pub fn clip_layer_norm(mut self, max_norm: f64) -> Self {
+        self.clip_layer_norm = Some(max_norm);
+        self
+    }
+
+    pub fn clip_norm(mut self, max_norm: f64) -> Self {
+        self.clip_norm = Some(max_norm);
+        self
+    }
+
+    pub fn pruning(mut self, schedule: NeuraPruningSchedule) -> Self {
+        self.pruning = Some(schedule);
+        self
+    }
+
+    pub fn epochs(mut self, epochs: usize) -> Self {
+        self.epochs = epochs;
+        self
+    }
+
+    #[deprecated]
+    pub fn with_epochs(
+        learning_rate: f64,
+        epochs: usize,
+        batch_size: usize,
+        training_size: usize,
+    ) -> Self {
+        Self {
+            learning_rate,
+            iterations: (training_size * epochs / batch_size).max(1),
+            log_iterations: (training_size / batch_size).max(1),
+            batch_size,
+            ..Default::default()
+        }
+    }
+
+    /// Clips `gradient_sum`, the sum of the gradients of a batch of `batch_size` inputs,
+    /// according to `clip_value`, `clip_layer_norm` and `clip_norm`.
+    /// Returns the norm of the averaged gradient before it was clipped.
+    fn clip_gradient<Network: NeuraLayerBase>(
+        &self,
+        network: &Network,
+        gradient_sum: &mut Network::Gradient,
+        batch_size: usize,
+    ) -> f64 {
+        // The thresholds are scaled by the batch size, which is equivalent to clipping the averaged gradient
+        let batch_size = batch_size as f64;
+        let norm = gradient_sum.norm_squared().sqrt() / batch_size;
+
+        if let Some(max_value) = self.clip_value {
+            gradient_sum.clip_values(max_value * batch_size);
+        }
+
+        if let Some(max_norm) = self.clip_layer_norm {
+            network.clip_layer_gradient(gradient_sum, max_norm * batch_size);
+        }
+
+        if let Some(max_norm) = self.clip_norm {
+            gradient_sum.clip_norm(max_norm * batch_size);
+        }
+
+        norm
+    }
+
+    /// Computes the gradient of `network` over `batch` and applies it, along with the regularization, weight decay and momentum terms,
+    /// then prunes the network if `iteration` is a step of the pruning schedule.
+    /// If the gradient overflowed (containing infinite or NaN values), which can happen with `LossScaling`,
+    /// then the whole step is skipped and the network is left untouched.
+    /// Returns the summed training loss of the batch and the norm of the averaged gradient.
+    fn train_batch<
+        Input,
+        Target,
+        Network: NeuraLayer<Input>,
+        GradientSolver: NeuraGradientSolver<Input, Target, Network>,
+    >(
+        &self,
+        gradient_solver: &GradientSolver,
+        network: &mut Network,
+        batch: &[(Input, Target)],
+        iteration: usize,
+        previous_gradient_sum: &mut Network::Gradient,
+    ) -> (f64, f64) {
+        let factor = -self.learning_rate / (batch.len() as f64);
+        let momentum_factor = self.learning_momentum / self.learning_rate;
+        let reg_factor = -self.learning_rate;
+
+        let mut gradient_sum = network.default_gradient();
+        let mut train_loss = 0.0;
+        network.prepare_layer(true);
+
+        for (input, target) in batch {
+            let gradient = gradient_solver.get_gradient(network, input, target);
+            gradient_sum.add_assign(&gradient);
+
+            train_loss += gradient_solver.score(network, input, target);
+        }
+
+        let gradient_norm = self.clip_gradient(network, &mut gradient_sum, batch.len());
+
+        // An infinite or NaN entry makes the norm non-finite; such a gradient must not be applied to any layer
+        if gradient_norm.is_finite() {
+            gradient_sum.mul_assign(factor);
+
+            // Add regularization gradient
+            let mut reg_gradient = network.regularize_layer();
+            reg_gradient.mul_assign(reg_factor);
+            gradient_sum.add_assign(&reg_gradient);
+
+            if self.weight_decay != 0.0 {
+                network.decay_layer(self.learning_rate * self.weight_decay);
+            }
+
+            network.apply_gradient(&gradient_sum);
+
+            if self.learning_momentum != 0.0 {
+                // `previous_gradient_sum` contains `momentum_factor * factor * gradient_sum_previous_iter`
+                network.apply_gradient(previous_gradient_sum);
+                *previous_gradient_sum = gradient_sum;
+                previous_gradient_sum.mul_assign(momentum_factor);
+            }
+        }
+
+        if let Some(sparsity) = self.pruning.as_ref().and_then(|schedule| schedule.step(iteration)) {
+            network.prune_magnitude(sparsity);
+        }
+
+        (train_loss, gradient_norm)
+    }
+
+    /// Returns the average loss of `network` over `test_inputs`
+    fn validation_loss<
+        Input,
+        Target,
+        Network: NeuraLayer<Input>,
+        GradientSolver: NeuraGradientSolver<Input, Target, Network>,
+        TestInputs: NeuraDataset<Input = Input, Target = Target> + ?Sized,
+    >(
+        &self,
+        gradient_solver: &GradientSolver,
+        network: &mut Network,
+        test_inputs: &TestInputs,
+    ) -> f64 {
+        network.prepare_layer(false);
+
+        let mut val_loss = 0.0;
+        for (input, target) in test_inputs.pairs() {
+            val_loss += gradient_solver.score(network, &input, &target);
+        }
+
+        val_loss / test_inputs.len() as f64
+    }
+
+    pub fn train<
+        Input: Clone,
+        Target: Clone,
+        Network: NeuraLayer<Input>,
+        GradientSolver: NeuraGradientSolver<Input, Target, Network>,
+        Inputs: IntoIterator<Item = (Input, Target)>,
+        TestInputs: NeuraDataset<Input = Input, Target = Target> + ?Sized,
+    >(
+        &self,
+        gradient_solver: &GradientSolver,
+        network: &mut Network,
+        inputs: Inputs,
+        test_inputs: &TestInputs,
+    ) -> NeuraTrainingReport
+    where
+        Network::Gradient: std::fmt::Debug,
+    {
+        self.train_logged(gradient_solver, network, inputs, test_inputs, |_, _| {
+            Vec::new()
+        })
+    }
+
+    /// Same as `train`, but `metrics` are also evaluated on `test_inputs` and logged alongside the validation loss.
+    pub fn train_with_metrics<
+        F: Float + Scalar,
+        Input: Clone,
+        Network: NeuraLayer<Input, Output = DVector<F>>,
+        GradientSolver: NeuraGradientSolver<Input, DVector<F>, Network>,
+        Inputs: IntoIterator<Item = (Input, DVector<F>)>,
+        TestInputs: NeuraDataset<Input = Input, Target = DVector<F>> + ?Sized,
+    >(
+        &self,
+        gradient_solver: &GradientSolver,
+        network: &mut Network,
+        inputs: Inputs,
+        test_inputs: &TestInputs,
+        metrics: &[NeuraMetric],
+    ) -> NeuraTrainingReport
+    where
+        Network::Gradient: std::fmt::Debug,
+    {
+        self.train_logged(
+            gradient_solver,
+            network,
+            inputs,
+            test_inputs,
+            |network, test_inputs| collect_metrics(metrics, network, test_inputs),
+        )
+    }
+
+    /// Implementation of `train`; `log_metrics` returns the metrics to record at each step.
+    fn train_logged<
+        Input: Clone,
+        Target: Clone,
+        Network: NeuraLayer<Input>,
+        GradientSolver: NeuraGradientSolver<Input, Target, Network>,
+        Inputs: IntoIterator<Item = (Input, Target)>,
+        TestInputs: NeuraDataset<Input = Input, Target = Target> + ?Sized,
+    >(
+        &self,
+        gradient_solver: &GradientSolver,
+        network: &mut Network,
+        inputs: Inputs,
+        test_inputs: &TestInputs,
+        log_metrics: impl Fn(&Network, &TestInputs) -> Vec<(String, f64)>,
+    ) -> NeuraTrainingReport {
+        let mut report = NeuraTrainingReport::default();
+        let mut iter = inputs.into_iter();
+        let mut timer = StepTimer::new();
+
+        let mut previous_gradient_sum = network.default_gradient();
+        let mut train_loss = 0.0;
+        let mut gradient_norm = 0.0;
+        for iteration in 0..self.iterations {
+            let batch: Vec<_> = (&mut iter).take(self.batch_size).collect();
+            if batch.len() < self.batch_size {
+                break;
+            }
+
+            let (batch_loss, batch_norm) = self.train_batch(
+                gradient_solver,
+                network,
+                &batch,
+                iteration,
+                &mut previous_gradient_sum,
+            );
+            train_loss += batch_loss;
+            gradient_norm += batch_norm;
+
+            if self.log_iterations > 0 && (iteration + 1) % self.log_iterations == 0 {
+                let samples = self.batch_size * self.log_iterations;
+                let (elapsed, samples_per_sec) = timer.step(samples);
+                let val_loss = self.validation_loss(gradient_solver, network, test_inputs);
+
+                let step = NeuraTrainingStep {
+                    iteration: iteration + 1,
+                    epoch: None,
+                    elapsed,
+                    samples_per_sec,
+                    train_loss: train_loss / samples as f64,
+                    val_loss,
+                    gradient_norm: gradient_norm / self.log_iterations as f64,
+                    learning_rate: self.learning_rate,
+                    metrics: log_metrics(network, test_inputs),
+                };
+                self.logging.log(&step);
+                report.steps.push(step);
+
+                train_loss = 0.0;
+                gradient_norm = 0.0;
+            }
+        }
+
+        network.prepare_layer(false);
+
+        report
+    }
+
+    /// Trains `network` on `dataset` for `epochs` epochs: each epoch goes through every pair of `dataset` exactly once,
+    /// in an order shuffled with `rng`, in batches of `batch_size` (the last batch of an epoch may be smaller).
+    ///
+    /// `iterations` and `log_iterations` are ignored; progress is instead logged at the end of every epoch,
+    /// and the returned report contains one step per epoch.
+    pub fn train_epochs<
+        Input,
+        Target,
+        Network: NeuraLayer<Input>,
+        GradientSolver: NeuraGradientSolver<Input, Target, Network>,
+        Dataset: NeuraDataset<Input = Input, Target = Target> + ?Sized,
+        TestInputs: NeuraDataset<Input = Input, Target = Target> + ?Sized,
+    >(
+        &self,
+        gradient_solver: &GradientSolver,
+        network: &mut Network,
+        dataset: &Dataset,
+        test_inputs: &TestInputs,
+        rng: impl Rng,
+    ) -> NeuraTrainingReport {
+        self.train_epochs_logged(
+            gradient_solver,
+            network,
+            dataset,
+            test_inputs,
+            rng,
+            |_, _| Vec::new(),
+        )
+    }
+
+    /// Same as `train_epochs`, but `metrics` are also evaluated on `test_inputs` and logged alongside the validation loss.
+    pub fn train_epochs_with_metrics<
+        F: Float + Scalar,
+        Input,
+        Network: NeuraLayer<Input, Output = DVector<F>>,
+        GradientSolver: NeuraGradientSolver<Input, DVector<F>, Network>,
+        Dataset: NeuraDataset<Input = Input, Target = DVector<F>> + ?Sized,
+        TestInputs: NeuraDataset<Input = Input, Target = DVector<F>> + ?Sized,
+    >(
+        &self,
+        gradient_solver: &GradientSolver,
+        network: &mut Network,
+        dataset: &Dataset,
+        test_inputs: &TestInputs,
+        rng: impl Rng,
+        metrics: &[NeuraMetric],
+    ) -> NeuraTrainingReport {
+        self.train_epochs_logged(
+            gradient_solver,
+            network,
+            dataset,
+            test_inputs,
+            rng,
+            |network, test_inputs| collect_metrics(metrics, network, test_inputs),
+        )
+    }
+
+    /// Implementation of `train_epochs`; `log_metrics` returns the metrics to record at each step.
+    fn train_epochs_logged<
+        Input,
+        Target,
+        Network: NeuraLayer<Input>,
+        GradientSolver: NeuraGradientSolver<Input, Target, Network>,
+        Dataset: NeuraDataset<Input = Input, Target = Target> + ?Sized,
+        TestInputs: NeuraDataset<Input = Input, Target = Target> + ?Sized,
+    >(
+        &self,
+        gradient_solver: &GradientSolver,
+        network: &mut Network,
+        dataset: &Dataset,
+        test_inputs: &TestInputs,
+        mut rng: impl Rng,
+        log_metrics: impl Fn(&Network, &TestInputs) -> Vec<(String, f64)>,
+    ) -> NeuraTrainingReport {
+        let mut report = NeuraTrainingReport::default();
+        let mut timer = StepTimer::new();
+        let mut previous_gradient_sum = network.default_gradient();
+        let mut iteration = 0;
+
+        for epoch in 0..self.epochs {
+            let mut train_loss = 0.0;
+            let mut gradient_norm = 0.0;
+            let mut batches = 0;
+
+            for batch in dataset.batches(self.batch_size, &mut rng) {
+                let (batch_loss, batch_norm) = self.train_batch(
+                    gradient_solver,
+                    network,
+                    &batch,
+                    iteration + batches,
+                    &mut previous_gradient_sum,
+                );
+                train_loss += batch_loss;
+                gradient_norm += batch_norm;
+                batches += 1;
+            }
+            iteration += batches;
+
+            let (elapsed, samples_per_sec) = timer.step(dataset.len());
+            let val_loss = self.validation_loss(gradient_solver, network, test_inputs);
+
+            let step = NeuraTrainingStep {
+                iteration,
+                epoch: Some(epoch + 1),
+                elapsed,
+                samples_per_sec,
+                train_loss: train_loss / dataset.len().max(1) as f64,
+                val_loss,
+                gradient_norm: gradient_norm / batches.max(1) as f64,
+                learning_rate: self.learning_rate,
+                metrics: log_metrics(network, test_inputs),
+            };
+            self.logging.log(&step);
+            report.steps.push(step);
+        }
+
+        network.prepare_layer(false);
+
+        report
+    }
+}
+
+/// Evaluates `metrics` for `network` over `test_inputs`, to be recorded in the trainer's report
+fn collect_metrics<
+    F: Float + Scalar,
+    Input,
+    Network: NeuraLayer<Input, Output = DVector<F>>,
+    TestInputs: NeuraDataset<Input = Input, Target = DVector<F>> + ?Sized,
+>(
+    metrics: &[NeuraMetric],
+    network: &Network,
+    test_inputs: &TestInputs,
+) -> Vec<(String, f64)> {
+    let values = evaluate_metrics(metrics, network, test_inputs);
+
+    metrics
+        .iter()
+        .filter_map(|&metric| Some((metric.name(), values.get(metric)?)))
+        .collect()
+}
+
+/// Measures the wall-clock time and throughput between the logging steps of the trainer
+struct StepTimer {
+    start: Instant,
+    previous: Instant,
+}
+
+impl StepTimer {
+    fn new() -> Self {
+        let now = Instant::now();
+
+        Self {
+            start: now,
+            previous: now,
+        }
+    }
+
+    /// Returns the time elapsed since the start, and the number of samples per second since the previous step
+    fn step(&mut self, samples: usize) -> (f64, f64) {
+        let now = Instant::now();
+        let duration = (now - self.previous).as_secs_f64();
+        self.previous = now;
+
+        let samples_per_sec = if duration > 0.0 {
+            samples as f64 / duration
+        } else {
+            0.0
+        };
+
+        ((now - self.start).as_secs_f64(), samples_per_sec)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use nalgebra::{dmatrix, dvector};
+    use ndarray::Array1;
+
+    use super::*;
+    use crate::{
+        algebra::NeuraTensor,
+        assert_approx,
+        derivable::{activation::Linear, loss::Euclidean, regularize::NeuraL0, NeuraLoss},
+        gradient_solver::NeuraBackprop,
+        layer::{
+            array::NeuraArrayLayer, dense::NeuraDenseLayer, normalize::NeuraNormalizeLayer,
+            softmax::NeuraSoftmaxLayer, tensor::NeuraTensorLayer, NeuraLayer,
+        },
+        network::sequential::{NeuraSequential, NeuraSequentialTail},
+        neura_sequential,
+    };
+
+    #[test]
+    fn test_backpropagation_simple() {
+        for wa in [0.0, 0.25, 0.5, 1.0] {
+            for wb in [0.0, 0.25, 0.5, 1.0] {
+                let network = NeuraSequential::new(
+                    NeuraDenseLayer::new(dmatrix![wa, wb], dvector![0.0], Linear, NeuraL0),
+                    (),
+                );
+
+                let (gradient, _) = NeuraBackprop::new(Euclidean).get_gradient(
+                    &network,
+                    &dvector![1.0, 1.0],
+                    &dvector![0.0],
+                );
+
+                let expected = wa + wb;
+                assert!((gradient.0[(0, 0)] - expected) < 0.001);
+                assert!((gradient.0[(0, 1)] - expected) < 0.001);
+            }
+        }
+    }
+
+    #[test]
+    fn test_backpropagation_complex() {
+        const EPSILON: f64 = 0.00001;
+        // Test that we get the same values as https://hmkcode.com/ai/backpropagation-step-by-step/
+        let network = neura_sequential![
+            NeuraDenseLayer::new(
+                dmatrix![0.11, 0.21; 0.12, 0.08],
+                dvector![0.0, 0.0],
+                Linear,
+                NeuraL0
+            ),
+            NeuraDenseLayer::new(dmatrix![0.14, 0.15], dvector![0.0], Linear, NeuraL0)
+        ];
+
+        let input = dvector![2.0, 3.0];
+        let target = dvector![1.0];
+
+        let intermediary = network.clone().trim_tail().eval(&input);
+        assert_approx!(0.85, intermediary[0], EPSILON);
+        assert_approx!(0.48, intermediary[1], EPSILON);
+        assert_approx!(0.191, network.eval(&input)[0], EPSILON);
+
+        assert_approx!(0.327, Euclidean.eval(&target, &network.eval(&input)), 0.001);
+
+        let delta = network.eval(&input)[0] - target[0];
+
+        let (gradient_first, gradient_second) =
+            NeuraBackprop::new(Euclidean).get_gradient(&network, &input, &target);
+        let gradient_first = gradient_first.0;
+        let gradient_second = gradient_second.0 .0;
+
+        assert_approx!(gradient_second[0], intermediary[0] * delta, EPSILON);
+        assert_approx!(gradient_second[1], intermediary[1] * delta, EPSILON);
+
+        assert_approx!(gradient_first[(0, 0)], input[0] * delta * 0.14, EPSILON);
+        assert_approx!(gradient_first[(0, 1)], input[1] * delta * 0.14, EPSILON);
+
+        assert_approx!(gradient_first[(1, 0)], input[0] * delta * 0.15, EPSILON);
+        assert_approx!(gradient_first[(1, 1)], input[1] * delta * 0.15, EPSILON);
+    }
+
+    #[test]
+    fn test_backpropagation_ndarray() {
+        let network = neura_sequential![
+            NeuraDenseLayer::new(
+                dmatrix![0.11, 0.21; 0.12, 0.08; -0.3, 0.5],
+                dvector![0.1, 0.0, -0.1],
+                Linear,
+                NeuraL0
+            ),
+            NeuraNormalizeLayer::new(),
+            NeuraDenseLayer::new(
+                dmatrix![0.14, 0.15, -0.2; 0.3, -0.1, 0.25],
+                dvector![0.0, 0.2],
+                Linear,
+                NeuraL0
+            ),
+            NeuraSoftmaxLayer::new()
+        ];
+
+        let array_network = neura_sequential![
+            NeuraArrayLayer::new(network.layer.clone()),
+            NeuraArrayLayer::new(network.child_network.layer.clone()),
+            NeuraArrayLayer::new(network.child_network.child_network.layer.clone()),
+            NeuraArrayLayer::new(
+                network
+                    .child_network
+                    .child_network
+                    .child_network
+                    .layer
+                    .clone()
+            )
+        ];
+
+        let input = dvector![2.0, 3.0];
+        let target = dvector![1.0, 0.0];
+        let array_input = Array1::from_vec(vec![2.0, 3.0]);
+        let array_target = Array1::from_vec(vec![1.0, 0.0]);
+
+        let output = network.eval(&input);
+        let array_output = array_network.eval(&array_input);
+        assert_relative_eq!(output.as_slice(), array_output.as_slice().unwrap());
+
+        let gradient = NeuraBackprop::new(Euclidean).get_gradient(&network, &input, &target);
+        let array_gradient = NeuraBackprop::new(Euclidean).get_gradient(
+            &array_network,
+            &array_input,
+            &array_target,
+        );
+
+        assert_relative_eq!(gradient.0 .0, array_gradient.0 .0, epsilon = 1e-12);
+        assert_relative_eq!(gradient.0 .1, array_gradient.0 .1, epsilon = 1e-12);
+        assert_relative_eq!(gradient.1 .1 .0 .0, array_gradient.1 .1 .0 .0, epsilon = 1e-12);
+        assert_relative_eq!(gradient.1 .1 .0 .1, array_gradient.1 .1 .0 .1, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_backpropagation_tensor() {
+        let network = neura_sequential![
+            NeuraNormalizeLayer::new(),
+            NeuraDenseLayer::new(
+                dmatrix![0.14, 0.15, -0.2, 0.1; 0.3, -0.1, 0.25, 0.0],
+                dvector![0.0, 0.2],
+                Linear,
+                NeuraL0
+            ),
+            NeuraSoftmaxLayer::new()
+        ];
+        let tensor_network = neura_sequential![
+            NeuraTensorLayer::new(network.layer.clone()),
+            NeuraTensorLayer::new(network.child_network.layer.clone()),
+            NeuraTensorLayer::new(network.child_network.child_network.layer.clone())
+        ];
+        let Ok(tensor_network) = tensor_network.construct(NeuraShape::Matrix(2, 2)) else {
+            panic!("Couldn't construct the network");
+        };
+        let Ok(network) = network.construct(NeuraShape::Matrix(2, 2)) else {
+            panic!("Couldn't construct the network");
+        };
+
+        let input = dvector![2.0, 3.0, -1.0, 0.5];
+        let target = dvector![1.0, 0.0];
+        let tensor_input = NeuraTensor::from_vector(input.clone(), (2, 2)).unwrap();
+        let tensor_target = NeuraTensor::from(target.clone());
+
+        let output = tensor_network.eval(&tensor_input);
+        assert_eq!(output.shape(), NeuraShape::Vector(2));
+        assert_relative_eq!(output.into_vector(), network.eval(&input));
+
+        let gradient = NeuraBackprop::new(Euclidean).get_gradient(&network, &input, &target);
+        let tensor_gradient = NeuraBackprop::new(Euclidean).get_gradient(
+            &tensor_network,
+            &tensor_input,
+            &tensor_target,
+        );
+
+        assert_relative_eq!(gradient.1 .0 .0, tensor_gradient.1 .0 .0);
+        assert_relative_eq!(gradient.1 .0 .1, tensor_gradient.1 .0 .1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Tensor does not have the shape expected by the layer")]
+    fn test_tensor_wrong_shape() {
+        let network = neura_sequential![NeuraTensorLayer::new(NeuraNormalizeLayer::new())];
+        let Ok(network) = network.construct(NeuraShape::Matrix(2, 2)) else {
+            panic!("Couldn't construct the network");
+        };
+
+        let input = NeuraTensor::from_vector(dvector![2.0, 3.0, -1.0, 0.5], 4).unwrap();
+        network.eval(&input);
+    }
+
+    #[test]
+    fn test_weight_decay() {
+        let network = neura_sequential![NeuraDenseLayer::new(
+            dmatrix![3.0, 4.0],
+            dvector![1.0],
+            Linear,
+            NeuraL0
+        )];
+        // The network's output matches the target, so the loss gradient is zero
+        let inputs = [(dvector![0.0, 0.0], dvector![1.0f64])];
+        let trainer = NeuraBatchedTrainer::new()
+            .learning_rate(0.1)
+            .weight_decay(0.5)
+            .batch_size(1)
+            .iterations(1);
+
+        let mut trained = network.clone();
+        trainer.train(
+            &NeuraBackprop::new(Euclidean),
+            &mut trained,
+            inputs.iter().cloned(),
+            &inputs,
+        );
+        assert_relative_eq!(trained.layer.weights, dmatrix![2.85, 3.8]);
+        assert_relative_eq!(trained.layer.bias, dvector![1.0]);
+
+        let mut trained = neura_sequential![network.layer.clone().regularize_bias(true)];
+        trainer.train(
+            &NeuraBackprop::new(Euclidean),
+            &mut trained,
+            inputs.iter().cloned(),
+            &inputs,
+        );
+        assert_relative_eq!(trained.layer.bias, dvector![0.95]);
+
+        // The max-norm constraint is applied after the gradient
+        let mut trained = neura_sequential![network.layer.clone().max_norm(2.5)];
+        trainer.train(
+            &NeuraBackprop::new(Euclidean),
+            &mut trained,
+            inputs.iter().cloned(),
+            &inputs,
+        );
+        assert_relative_eq!(trained.layer.weights, dmatrix![1.5, 2.0]);
+    }
+
+    #[test]
+    fn test_gradient_clipping() {
+        let network = neura_sequential![
+            NeuraDenseLayer::new(dmatrix![1.0, -2.0], dvector![0.5], Linear, NeuraL0),
+            NeuraDenseLayer::new(dmatrix![3.0], dvector![0.0], Linear, NeuraL0)
+        ];
+        let inputs = [(dvector![10.0, 20.0], dvector![100.0f64])];
+
+        let get_update = |trainer: NeuraBatchedTrainer| {
+            let mut trained = network.clone();
+            trainer
+                .batch_size(1)
+                .learning_rate(1.0)
+                .iterations(1)
+                .train(
+                    &NeuraBackprop::new(Euclidean),
+                    &mut trained,
+                    inputs.iter().cloned(),
+                    &inputs,
+                );
+
+            (
+                (
+                    &trained.layer.weights - &network.layer.weights,
+                    &trained.layer.bias - &network.layer.bias,
+                ),
+                Box::new((
+                    &trained.child_network.layer.weights - &network.child_network.layer.weights,
+                    &trained.child_network.layer.bias - &network.child_network.layer.bias,
+                )),
+            )
+        };
+
+        let update = get_update(NeuraBatchedTrainer::new());
+        assert!(update.norm_squared().sqrt() > 1.0);
+
+        let update = get_update(NeuraBatchedTrainer::new().clip_norm(1.0));
+        assert_approx!(update.norm_squared().sqrt(), 1.0, 0.00001);
+
+        let update = get_update(NeuraBatchedTrainer::new().clip_layer_norm(0.5));
+        assert_approx!(update.0.norm_squared().sqrt(), 0.5, 0.00001);
+        assert_approx!(update.1.norm_squared().sqrt(), 0.5, 0.00001);
+
+        let update = get_update(NeuraBatchedTrainer::new().clip_value(0.1));
+        for &x in update.0 .0.iter().chain(update.1 .0.iter()) {
+            assert_approx!(x.abs(), 0.1, 0.00001);
+        }
+    }
+
+    #[test]
+    fn test_skip_overflow() {
+        let network = neura_sequential![
+            NeuraDenseLayer::new(dmatrix![1e-307, 0.0], dvector![0.0], Linear, NeuraL0),
+            NeuraDenseLayer::new(dmatrix![3.0], dvector![0.0], Linear, NeuraL0)
+        ];
+        // Only the gradient of the first layer overflows
+        let inputs = [(dvector![1e307, 0.0], dvector![100.0f64])];
+        let trainer = NeuraBatchedTrainer::new()
+            .learning_rate(0.1)
+            .batch_size(1)
+            .iterations(1);
+
+        let mut trained = network.clone();
+        trainer.train(
+            &NeuraBackprop::new(Euclidean),
+            &mut trained,
+            inputs.iter().cloned(),
+            &inputs,
+        );
+
+        assert_eq!(trained.layer.weights, network.layer.weights);
+        assert_eq!(
+            trained.child_network.layer.weights,
+            network.child_network.layer.weights
+        );
+        assert_eq!(
+            trained.child_network.layer.bias,
+            network.child_network.layer.bias
+        );
+    }
+
+    #[test]
+    fn test_train_epochs() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let network = neura_sequential![NeuraDenseLayer::new(
+            dmatrix![0.0, 0.0],
+            dvector![0.0],
+            Linear,
+            NeuraL0
+        )];
+        let dataset: Vec<_> = [[1.0, 0.0], [0.0, 1.0], [1.0, 1.0], [2.0, -1.0], [-1.0, 0.5]]
+            .into_iter()
+            .map(|[x, y]| (dvector![x, y], dvector![2.0 * x - y]))
+            .collect();
+
+        // With a batch as large as the dataset, one epoch is one iteration
+        let trainer = NeuraBatchedTrainer::new()
+            .batch_size(5)
+            .learning_rate(0.1)
+            .iterations(1)
+            .epochs(1);
+        let mut expected = network.clone();
+        trainer.train(
+            &NeuraBackprop::new(Euclidean),
+            &mut expected,
+            dataset.iter().cloned(),
+            &dataset,
+        );
+
+        let mut trained = network.clone();
+        let report = trainer.train_epochs(
+            &NeuraBackprop::new(Euclidean),
+            &mut trained,
+            &dataset,
+            &dataset,
+            StdRng::seed_from_u64(0),
+        );
+        assert_eq!(report.len(), 1);
+        assert_relative_eq!(trained.layer.weights, expected.layer.weights);
+
+        // The last batch of each epoch is smaller
+        let mut trained = network.clone();
+        let report = NeuraBatchedTrainer::new()
+            .batch_size(2)
+            .learning_rate(0.1)
+            .epochs(20)
+            .train_epochs(
+                &NeuraBackprop::new(Euclidean),
+                &mut trained,
+                &dataset,
+                &dataset,
+                StdRng::seed_from_u64(0),
+            );
+        assert_eq!(report.len(), 20);
+        assert!(report.steps[19].val_loss < report.steps[0].val_loss / 10.0);
+        assert_eq!(report.steps[19].epoch, Some(20));
+        assert_eq!(report.steps[19].iteration, 60);
+    }
+
+    #[test]
+    fn test_train_with_metrics() {
+        use crate::{layer::softmax::NeuraSoftmaxLayer, metrics::NeuraClassificationMetrics};
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let network = neura_sequential![
+            NeuraDenseLayer::new(
+                dmatrix![1.0, 0.0; 0.0, 1.0],
+                dvector![0.0, 0.0],
+                Linear,
+                NeuraL0
+            ),
+            NeuraSoftmaxLayer::new()
+        ];
+        let dataset = vec![
+            (dvector![1.0, 0.0], dvector![0.0, 1.0]),
+            (dvector![0.0, 1.0], dvector![1.0, 0.0]),
+        ];
+        let trainer = NeuraBatchedTrainer::new()
+            .batch_size(2)
+            .learning_rate(1.0)
+            .epochs(50);
+
+        let mut expected = network.clone();
+        let expected_report = trainer.train_epochs(
+            &NeuraBackprop::new(Euclidean),
+            &mut expected,
+            &dataset,
+            &dataset,
+            StdRng::seed_from_u64(0),
+        );
+
+        // Logging metrics does not affect training
+        let mut trained = network.clone();
+        let report = trainer.train_epochs_with_metrics(
+            &NeuraBackprop::new(Euclidean),
+            &mut trained,
+            &dataset,
+            &dataset,
+            StdRng::seed_from_u64(0),
+            &[NeuraMetric::Accuracy, NeuraMetric::MacroF1],
+        );
+        assert_eq!(report.losses(), expected_report.losses());
+        assert_eq!(report.metric_names(), vec!["Accuracy", "Macro F1"]);
+        assert_eq!(report.last().unwrap().metrics[0].1, 1.0);
+
+        let metrics = NeuraClassificationMetrics::evaluate(&trained, &dataset);
+        assert_eq!(metrics.accuracy(), 1.0);
+    }
+
+    #[test]
+    fn test_gradual_pruning() {
+        let network = neura_sequential![NeuraDenseLayer::new(
+            dmatrix![1.0, 2.0, 3.0, 4.0],
+            dvector![0.0],
+            Linear,
+            NeuraL0
+        )];
+        // The network's output matches the target, so the loss gradient is zero
+        let inputs = vec![(dvector![0.0, 0.0, 0.0, 0.0], dvector![0.0f64]); 10];
+
+        let mut trained = network.clone();
+        NeuraBatchedTrainer::new()
+            .batch_size(1)
+            .iterations(10)
+            .pruning(NeuraPruningSchedule::new(0.75, 2, 6).frequency(2))
+            .train(
+                &NeuraBackprop::new(Euclidean),
+                &mut trained,
+                inputs.iter().cloned(),
+                &inputs,
+            );
+
+        assert_relative_eq!(trained.sparsity(), 0.75);
+        assert_eq!(trained.layer.weights, dmatrix![0.0, 0.0, 0.0, 4.0]);
+    }
+}
@@ -0,0 +1,108 @@
+/// A gradual pruning schedule, as introduced by Zhu and Gupta (2017) in "To prune, or not to prune".
+///
+/// Starting at iteration `start`, the network is pruned by magnitude every `frequency` iterations
+/// (see `NeuraLayerBase::prune_magnitude`), with a target sparsity increasing from `initial_sparsity`
+/// to `final_sparsity` at iteration `end`, following
+/// `final_sparsity + (initial_sparsity - final_sparsity) * (1 - (iteration - start) / (end - start))^3`.
+///
+/// Pruning quickly at first and slowly towards the end lets the network recover from the removal of its least important weights.
+/// Set `NeuraBatchedTrainer::pruning` to have the trainer follow the schedule.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NeuraPruningSchedule {
+    /// The sparsity targeted at iteration `start`.
+    ///
+    /// Defaults to `0.0`
+    pub initial_sparsity: f64,
+
+    /// The sparsity targeted from iteration `end` onwards
+    pub final_sparsity: f64,
+
+    /// The iteration at which pruning starts
+    pub start: usize,
+
+    /// The iteration at which pruning stops
+    pub end: usize,
+
+    /// How many iterations to wait between two pruning steps.
+    ///
+    /// Defaults to `1`
+    pub frequency: usize,
+}
+
+impl NeuraPruningSchedule {
+    pub fn new(final_sparsity: f64, start: usize, end: usize) -> Self {
+        assert!(
+            start <= end,
+            "NeuraPruningSchedule: start must not come after end"
+        );
+
+        Self {
+            initial_sparsity: 0.0,
+            final_sparsity,
+            start,
+            end,
+            frequency: 1,
+        }
+    }
+
+    pub fn initial_sparsity(mut self, initial_sparsity: f64) -> Self {
+        self.initial_sparsity = initial_sparsity;
+        self
+    }
+
+    pub fn frequency(mut self, frequency: usize) -> Self {
+        assert!(
+            frequency > 0,
+            "NeuraPruningSchedule: frequency must be at least 1"
+        );
+
+        self.frequency = frequency;
+        self
+    }
+
+    /// Returns the sparsity targeted at `iteration`
+    pub fn sparsity(&self, iteration: usize) -> f64 {
+        if iteration < self.start {
+            return 0.0;
+        } else if iteration >= self.end {
+            return self.final_sparsity;
+        }
+
+        let progress = (iteration - self.start) as f64 / (self.end - self.start) as f64;
+
+        self.final_sparsity
+            + (self.initial_sparsity - self.final_sparsity) * (1.0 - progress).powi(3)
+    }
+
+    /// Returns the sparsity to prune the network to after `iteration`, or `None` if no pruning should happen at that iteration
+    pub fn step(&self, iteration: usize) -> Option<f64> {
+        let is_pruning_step = iteration == self.end
+            || (iteration >= self.start
+                && iteration < self.end
+                && (iteration - self.start).is_multiple_of(self.frequency));
+
+        is_pruning_step.then(|| self.sparsity(iteration))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_pruning_schedule() {
+        let schedule = NeuraPruningSchedule::new(0.8, 10, 20)
+            .initial_sparsity(0.1)
+            .frequency(5);
+
+        assert_eq!(schedule.step(0), None);
+        assert_relative_eq!(schedule.step(10).unwrap(), 0.1);
+        assert_eq!(schedule.step(12), None);
+        assert_relative_eq!(schedule.step(15).unwrap(), 0.8 - 0.7 / 8.0);
+        assert_eq!(schedule.step(20), Some(0.8));
+        assert_eq!(schedule.step(25), None);
+        assert_eq!(schedule.sparsity(30), 0.8);
+    }
+}
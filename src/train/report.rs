@@ -0,0 +1,272 @@
+use std::{
+    fmt::{self, Display},
+    io::Write,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// How `NeuraBatchedTrainer` prints its progress
+#[derive(Clone, Copy, Debug, Default)]
+pub enum NeuraTrainingLog {
+    /// Nothing is printed; the progress is only recorded in the returned `NeuraTrainingReport`
+    Silent,
+
+    /// Prints the losses and gradient norm of each step on stdout
+    #[default]
+    Compact,
+
+    /// Also prints the elapsed time, throughput and learning rate of each step on stdout
+    Verbose,
+
+    /// Calls the given function on each step
+    Custom(fn(&NeuraTrainingStep)),
+}
+
+impl NeuraTrainingLog {
+    pub(crate) fn log(&self, step: &NeuraTrainingStep) {
+        match self {
+            Self::Silent => {}
+            Self::Compact => println!("{}", step),
+            Self::Verbose => println!("{:#}", step),
+            Self::Custom(callback) => callback(step),
+        }
+    }
+}
+
+/// The progress of the training at one of its logging steps (every `log_iterations` iterations, or every epoch)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NeuraTrainingStep {
+    /// The number of batches trained on so far
+    pub iteration: usize,
+
+    /// The number of epochs completed so far, if trained with `train_epochs`
+    pub epoch: Option<usize>,
+
+    /// The wall-clock time since the start of the training, in seconds
+    pub elapsed: f64,
+
+    /// How many training samples were processed per second since the previous step
+    pub samples_per_sec: f64,
+
+    /// The average training loss since the previous step
+    pub train_loss: f64,
+
+    /// The average loss on the test inputs
+    pub val_loss: f64,
+
+    /// The average norm of the batch gradients since the previous step, before clipping
+    pub gradient_norm: f64,
+
+    pub learning_rate: f64,
+
+    /// The metrics evaluated on the test inputs, if any were requested
+    pub metrics: Vec<(String, f64)>,
+}
+
+impl Display for NeuraTrainingStep {
+    /// Formats the step as a log line; the alternate flag (`{:#}`) adds the timing information and learning rate.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.epoch {
+            Some(epoch) => write!(f, "Epoch {}", epoch)?,
+            None => write!(f, "Iteration {}", self.iteration)?,
+        }
+
+        write!(
+            f,
+            ", Training loss: {:.3}, Validation loss: {:.3}, Gradient norm: {:.3}",
+            self.train_loss, self.val_loss, self.gradient_norm
+        )?;
+
+        for (name, value) in self.metrics.iter() {
+            write!(f, ", {}: {:.3}", name, value)?;
+        }
+
+        if f.alternate() {
+            write!(
+                f,
+                ", Learning rate: {}, Elapsed: {:.1}s, {:.0} samples/s",
+                self.learning_rate, self.elapsed, self.samples_per_sec
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The progress of a training, as returned by `NeuraBatchedTrainer::train` and `NeuraBatchedTrainer::train_epochs`.
+///
+/// It can be passed to `plot_losses`, and exported with `write_csv` or `to_json`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NeuraTrainingReport {
+    pub steps: Vec<NeuraTrainingStep>,
+}
+
+impl NeuraTrainingReport {
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    pub fn last(&self) -> Option<&NeuraTrainingStep> {
+        self.steps.last()
+    }
+
+    /// Returns the training and validation losses of each step
+    pub fn losses(&self) -> Vec<(f64, f64)> {
+        self.steps
+            .iter()
+            .map(|step| (step.train_loss, step.val_loss))
+            .collect()
+    }
+
+    /// Returns the names of the metrics found in the steps, in order of appearance
+    pub fn metric_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+
+        for (name, _) in self.steps.iter().flat_map(|step| step.metrics.iter()) {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+
+        names
+    }
+
+    /// Writes the report as CSV, with one row per step and one column per metric.
+    /// Metrics missing from a step are left empty, and so is the epoch if not trained with `train_epochs`.
+    pub fn write_csv(&self, writer: impl Write) -> csv::Result<()> {
+        let metric_names = self.metric_names();
+        let mut writer = csv::Writer::from_writer(writer);
+
+        let mut header = vec![
+            "iteration",
+            "epoch",
+            "elapsed",
+            "samples_per_sec",
+            "train_loss",
+            "val_loss",
+            "gradient_norm",
+            "learning_rate",
+        ];
+        header.extend(metric_names.iter().map(String::as_str));
+        writer.write_record(&header)?;
+
+        for step in self.steps.iter() {
+            let mut record = vec![
+                step.iteration.to_string(),
+                step.epoch
+                    .map(|epoch| epoch.to_string())
+                    .unwrap_or_default(),
+                step.elapsed.to_string(),
+                step.samples_per_sec.to_string(),
+                step.train_loss.to_string(),
+                step.val_loss.to_string(),
+                step.gradient_norm.to_string(),
+                step.learning_rate.to_string(),
+            ];
+
+            for name in metric_names.iter() {
+                record.push(
+                    step.metrics
+                        .iter()
+                        .find(|(metric, _)| metric == name)
+                        .map(|(_, value)| value.to_string())
+                        .unwrap_or_default(),
+                );
+            }
+
+            writer.write_record(&record)?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Returns the report as CSV, see `write_csv`
+    pub fn to_csv(&self) -> String {
+        let mut res = Vec::new();
+        self.write_csv(&mut res)
+            .expect("Writing to a Vec should not fail");
+
+        String::from_utf8(res).expect("The report should be valid UTF-8")
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+impl From<NeuraTrainingReport> for Vec<(f64, f64)> {
+    fn from(report: NeuraTrainingReport) -> Self {
+        report.losses()
+    }
+}
+
+impl From<&NeuraTrainingReport> for Vec<(f64, f64)> {
+    fn from(report: &NeuraTrainingReport) -> Self {
+        report.losses()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn report() -> NeuraTrainingReport {
+        let step = |iteration: usize, metrics: Vec<(String, f64)>| NeuraTrainingStep {
+            iteration,
+            epoch: None,
+            elapsed: iteration as f64 * 0.5,
+            samples_per_sec: 200.0,
+            train_loss: 1.0 / iteration as f64,
+            val_loss: 2.0 / iteration as f64,
+            gradient_norm: 0.25,
+            learning_rate: 0.1,
+            metrics,
+        };
+
+        NeuraTrainingReport {
+            steps: vec![
+                step(1, vec![]),
+                step(2, vec![(String::from("Accuracy"), 0.75)]),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_report_export() {
+        let report = report();
+        assert_eq!(report.losses(), vec![(1.0, 2.0), (0.5, 1.0)]);
+
+        assert_eq!(
+            report.to_csv(),
+            "iteration,epoch,elapsed,samples_per_sec,train_loss,val_loss,gradient_norm,learning_rate,Accuracy\n\
+            1,,0.5,200,1,2,0.25,0.1,\n\
+            2,,1,200,0.5,1,0.25,0.1,0.75\n"
+        );
+
+        let json = report.to_json().unwrap();
+        assert_eq!(NeuraTrainingReport::from_json(&json).unwrap(), report);
+    }
+
+    #[test]
+    fn test_step_display() {
+        let report = report();
+
+        assert_eq!(
+            report.steps[1].to_string(),
+            "Iteration 2, Training loss: 0.500, Validation loss: 1.000, Gradient norm: 0.250, Accuracy: 0.750"
+        );
+        assert_eq!(
+            format!("{:#}", report.steps[0]),
+            "Iteration 1, Training loss: 1.000, Validation loss: 2.000, Gradient norm: 0.250, Learning rate: 0.1, Elapsed: 0.5s, 200 samples/s"
+        );
+    }
+}
@@ -7,37 +7,10 @@ pub(crate) fn assign_add_vector<const N: usize>(sum: &mut [f64; N], operand: &[f
     }
 }
 
-struct Chunked<J: Iterator> {
-    iter: J,
-    chunk_size: usize,
-}
-
-impl<J: Iterator> Iterator for Chunked<J> {
-    type Item = Vec<J::Item>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let mut result = Vec::with_capacity(self.chunk_size);
-
-        for _ in 0..self.chunk_size {
-            if let Some(item) = self.iter.next() {
-                result.push(item);
-            } else {
-                break;
-            }
-        }
-
-        if !result.is_empty() {
-            Some(result)
-        } else {
-            None
-        }
-    }
-}
-
 struct ShuffleCycled<I: Iterator, R: rand::Rng> {
     buffer: Vec<I::Item>,
     index: usize,
-    iter: I,
+    iter: Option<I>,
     rng: R,
 }
 
@@ -51,28 +24,30 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         use rand::prelude::SliceRandom;
 
-        if let Some(next) = self.iter.next() {
-            // Base iterator is not empty yet
-            self.buffer.push(next.clone());
-            Some(next)
-        } else if !self.buffer.is_empty() {
-            if self.index == 0 {
-                // Shuffle the vector and return the first element, setting the index to 1
-                self.buffer.shuffle(&mut self.rng);
-                self.index = 1;
-                Some(self.buffer[0].clone())
-            } else {
-                // Keep consuming the shuffled vector
-                let res = self.buffer[self.index].clone();
-                self.index = (self.index + 1) % self.buffer.len();
-                Some(res)
-            }
-        } else {
-            None
+        if let Some(iter) = self.iter.take() {
+            // Consume the base iterator, so that the first pass is shuffled too
+            self.buffer.extend(iter);
+        }
+
+        if self.buffer.is_empty() {
+            return None;
         }
+
+        if self.index == 0 {
+            // Reshuffle the vector at the start of every pass
+            self.buffer.shuffle(&mut self.rng);
+        }
+
+        let res = self.buffer[self.index].clone();
+        self.index = (self.index + 1) % self.buffer.len();
+        Some(res)
     }
 }
 
+/// Cycles through the items of `iter` indefinitely, shuffling them with `rng` before every pass.
+///
+/// The base iterator is entirely consumed on the first call to `next`.
+/// For finite datasets, prefer using `NeuraDataset` together with `NeuraBatchedTrainer::train_epochs`.
 pub fn cycle_shuffling<I: Iterator>(iter: I, rng: impl rand::Rng) -> impl Iterator<Item = I::Item>
 where
     I::Item: Clone,
@@ -83,7 +58,7 @@ where
     ShuffleCycled {
         buffer: Vec::with_capacity(size_hint),
         index: 0,
-        iter,
+        iter: Some(iter),
         rng,
     }
 }
@@ -128,10 +103,13 @@ macro_rules! assert_approx {
     };
 }
 
+/// Plots the training and validation losses in the terminal; accepts a `NeuraTrainingReport` or a list of `(train_loss, val_loss)` pairs.
 // TODO: put this behind a feature
-pub fn plot_losses(losses: Vec<(f64, f64)>, width: u32, height: u32) {
+pub fn plot_losses(losses: impl Into<Vec<(f64, f64)>>, width: u32, height: u32) {
     use textplots::{Chart, ColorPlot, Plot, Shape};
 
+    let losses = losses.into();
+
     let train_losses: Vec<_> = losses
         .iter()
         .enumerate()